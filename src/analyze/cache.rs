@@ -0,0 +1,159 @@
+//! Persistent per-file analysis cache.
+//!
+//! Stores each file's symbols, imports and memory markers under the output
+//! directory, keyed by relative path and validated by content hash, so
+//! unchanged files are not re-parsed between runs.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::analyze::lang::BACKEND;
+use crate::emit::write_atomic;
+use crate::types::{FileEntry, MemoryEntry, Symbol};
+
+const CACHE_FILE: &str = ".cache.json";
+
+/// Version of the parsers' output format. Bump whenever symbol, import or
+/// memory extraction changes so stale cache entries are discarded.
+pub const PARSER_VERSION: u32 = 13;
+
+/// Cached analysis results for a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedAnalysis {
+    /// Content hash the results were computed from
+    pub hash: String,
+    pub symbols: Vec<Symbol>,
+    pub imports: Vec<String>,
+    pub memory: Vec<MemoryEntry>,
+}
+
+/// Analysis cache stored as `.cache.json` in the output directory
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisCache {
+    /// Parser version the entries were produced with
+    pub parser_version: u32,
+    /// Parsing backend (regex or tree-sitter) the entries were produced with
+    #[serde(default)]
+    pub backend: String,
+    /// Cached results keyed by relative file path
+    pub entries: BTreeMap<String, CachedAnalysis>,
+    #[serde(skip)]
+    hits: usize,
+    #[serde(skip)]
+    misses: usize,
+}
+
+impl Default for AnalysisCache {
+    fn default() -> Self {
+        Self {
+            parser_version: PARSER_VERSION,
+            backend: BACKEND.to_string(),
+            entries: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl AnalysisCache {
+    /// Load the cache from the output directory. Returns an empty cache if
+    /// none exists, it cannot be read, or it was written by another parser
+    /// version or backend.
+    pub fn load(output_dir: &Path) -> Self {
+        let cache_path = output_dir.join(CACHE_FILE);
+        let cache: Self = match fs::read_to_string(&cache_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => Self::default(),
+        };
+
+        if cache.parser_version != PARSER_VERSION || cache.backend != BACKEND {
+            return Self::default();
+        }
+        cache
+    }
+
+    /// Save the cache to the output directory
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        fs::create_dir_all(output_dir)?;
+        let content = serde_json::to_string(self)?;
+        write_atomic(&output_dir.join(CACHE_FILE), content)?;
+        Ok(())
+    }
+
+    /// Look up results for a file, valid only if its content hash still matches
+    pub fn get(&mut self, relative_path: &str, hash: &str) -> Option<&CachedAnalysis> {
+        match self.entries.get(relative_path) {
+            Some(entry) if entry.hash == hash => {
+                self.hits += 1;
+                Some(entry)
+            }
+            _ => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store results for a file
+    pub fn insert(&mut self, relative_path: String, analysis: CachedAnalysis) {
+        self.entries.insert(relative_path, analysis);
+    }
+
+    /// Drop entries for files that no longer exist
+    pub fn retain_files(&mut self, files: &[FileEntry]) {
+        let current: HashSet<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+        self.entries
+            .retain(|path, _| current.contains(path.as_str()));
+    }
+
+    /// Number of lookups served from the cache / re-parsed in this run
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits, self.misses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(hash: &str) -> CachedAnalysis {
+        CachedAnalysis {
+            hash: hash.to_string(),
+            symbols: Vec::new(),
+            imports: vec!["std::fs".to_string()],
+            memory: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_hash_check() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = AnalysisCache::default();
+        cache.insert("src/lib.rs".to_string(), entry("abc"));
+        cache.save(dir.path()).unwrap();
+
+        let mut loaded = AnalysisCache::load(dir.path());
+        assert!(loaded.get("src/lib.rs", "abc").is_some());
+        assert!(loaded.get("src/lib.rs", "changed").is_none());
+        assert!(loaded.get("src/other.rs", "abc").is_none());
+        assert_eq!(loaded.stats(), (1, 2));
+    }
+
+    #[test]
+    fn test_cache_discarded_on_parser_version_change() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = AnalysisCache {
+            parser_version: PARSER_VERSION + 1,
+            ..Default::default()
+        };
+        cache.insert("src/lib.rs".to_string(), entry("abc"));
+        cache.save(dir.path()).unwrap();
+
+        let loaded = AnalysisCache::load(dir.path());
+        assert!(loaded.entries.is_empty());
+    }
+}
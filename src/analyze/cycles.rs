@@ -0,0 +1,303 @@
+//! Dependency cycle detection.
+//!
+//! Finds the strongly connected components of the file import graph and of
+//! the module dependency graph derived from it (Tarjan's algorithm). Every
+//! component with more than one member is a group of files or modules that
+//! all depend on each other; one concrete import path through it is kept so
+//! the cycle can be shown and broken.
+
+use crate::analyze::{FileGraph, ModuleInfo};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Files or modules that transitively import each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// Members of the strongly connected component, sorted
+    pub members: Vec<String>,
+    /// Shortest import path from the first member back to itself
+    pub path: Vec<String>,
+}
+
+impl Cycle {
+    /// The import path as `a → b → a`
+    pub fn display_path(&self) -> String {
+        self.path.join(" → ")
+    }
+
+    /// Whether every member also belongs to `other`, i.e. the cycle was
+    /// already present when `other` was recorded
+    pub fn is_within(&self, other: &[String]) -> bool {
+        self.members.iter().all(|m| other.contains(m))
+    }
+}
+
+/// Cycles at file and module level
+#[derive(Debug, Clone, Default)]
+pub struct CycleReport {
+    pub modules: Vec<Cycle>,
+    pub files: Vec<Cycle>,
+}
+
+impl CycleReport {
+    pub fn detect(modules: &[ModuleInfo], graph: &FileGraph) -> Self {
+        let mut file_edges: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (file, imports) in &graph.imports {
+            let targets = file_edges.entry(file.as_str()).or_default();
+            // External packages have no outgoing edges, so they can't be in a cycle
+            targets.extend(
+                imports
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|i| graph.imports.contains_key(*i)),
+            );
+        }
+
+        let dependencies = module_dependencies(modules, graph);
+        let mut module_edges: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (from, to) in &dependencies {
+            module_edges.entry(from).or_default().insert(to);
+        }
+
+        Self {
+            modules: find_cycles(&module_edges),
+            files: find_cycles(&file_edges),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.files.is_empty()
+    }
+
+    /// Module cycles `module` is part of, and file cycles through its files
+    pub fn for_module(&self, module: &ModuleInfo) -> (Vec<&Cycle>, Vec<&Cycle>) {
+        let modules = self
+            .modules
+            .iter()
+            .filter(|c| c.members.contains(&module.slug))
+            .collect();
+        let files = self
+            .files
+            .iter()
+            .filter(|c| c.members.iter().any(|f| module.files.contains(f)))
+            .collect();
+        (modules, files)
+    }
+}
+
+/// Module-to-module import edges, as sorted `(from, to)` slug pairs
+pub fn module_dependencies(modules: &[ModuleInfo], graph: &FileGraph) -> Vec<(String, String)> {
+    let mut deps: HashSet<(String, String)> = HashSet::new();
+
+    let file_to_module: HashMap<&str, &str> = modules
+        .iter()
+        .flat_map(|m| m.files.iter().map(move |f| (f.as_str(), m.slug.as_str())))
+        .collect();
+
+    for module in modules {
+        for file in &module.files {
+            if let Some(imports) = graph.imports.get(file) {
+                for imp in imports {
+                    if let Some(&target_module) = file_to_module.get(imp.as_str()) {
+                        if target_module != module.slug {
+                            deps.insert((module.slug.clone(), target_module.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<_> = deps.into_iter().collect();
+    result.sort();
+    result
+}
+
+/// Strongly connected components with more than one node, sorted by their
+/// first member
+fn find_cycles(edges: &BTreeMap<&str, BTreeSet<&str>>) -> Vec<Cycle> {
+    let mut tarjan = Tarjan {
+        edges,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for &node in edges.keys() {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(node);
+        }
+    }
+
+    let mut cycles: Vec<Cycle> = tarjan
+        .components
+        .into_iter()
+        .filter(|c| c.len() > 1)
+        .map(|mut members| {
+            members.sort();
+            let path = shortest_cycle(edges, &members);
+            Cycle {
+                members: members.iter().map(|m| m.to_string()).collect(),
+                path,
+            }
+        })
+        .collect();
+    cycles.sort_by(|a, b| a.members.cmp(&b.members));
+    cycles
+}
+
+struct Tarjan<'a, 'e> {
+    edges: &'e BTreeMap<&'a str, BTreeSet<&'a str>>,
+    index: HashMap<&'a str, usize>,
+    low: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a, '_> {
+    /// Iterative depth-first search, so deep import chains can't overflow
+    /// the stack
+    fn visit(&mut self, root: &'a str) {
+        let mut work: Vec<(&'a str, Vec<&'a str>)> = Vec::new();
+        self.open(root);
+        work.push((root, self.successors(root)));
+
+        while let Some((node, pending)) = work.last_mut() {
+            let node = *node;
+            if let Some(next) = pending.pop() {
+                if !self.index.contains_key(next) {
+                    self.open(next);
+                    let successors = self.successors(next);
+                    work.push((next, successors));
+                } else if self.on_stack.contains(next) {
+                    let low = self.low[node].min(self.index[next]);
+                    self.low.insert(node, low);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some((parent, _)) = work.last() {
+                let low = self.low[*parent].min(self.low[node]);
+                self.low.insert(*parent, low);
+            }
+            if self.low[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn open(&mut self, node: &'a str) {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+    }
+
+    fn successors(&self, node: &'a str) -> Vec<&'a str> {
+        // Reversed so nodes are popped in sorted order, for stable output
+        self.edges
+            .get(node)
+            .into_iter()
+            .flatten()
+            .rev()
+            .copied()
+            .collect()
+    }
+}
+
+/// Shortest path from the first member back to itself, staying inside the
+/// component
+fn shortest_cycle(edges: &BTreeMap<&str, BTreeSet<&str>>, members: &[&str]) -> Vec<String> {
+    let start = members[0];
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        for &next in edges.get(node).into_iter().flatten() {
+            if !members.contains(&next) {
+                continue;
+            }
+            if next == start {
+                let mut path = vec![start.to_string()];
+                let mut current = node;
+                while current != start {
+                    path.push(current.to_string());
+                    current = previous[current];
+                }
+                path.push(start.to_string());
+                let end = path.len() - 1;
+                path[1..end].reverse();
+                return path;
+            }
+            if !previous.contains_key(next) {
+                previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    members.iter().map(|m| m.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::BoundaryType;
+
+    fn module(path: &str, files: &[&str]) -> ModuleInfo {
+        let mut module = ModuleInfo::new(path, BoundaryType::Implicit, None);
+        for file in files {
+            module.add_file(file.to_string());
+        }
+        module
+    }
+
+    #[test]
+    fn test_detects_file_and_module_cycles() {
+        let modules = vec![
+            module("a", &["a/one.rs", "a/two.rs"]),
+            module("b", &["b/lib.rs"]),
+            module("c", &["c/lib.rs"]),
+        ];
+        let mut graph = FileGraph::new();
+        graph.add_file(
+            "a/one.rs",
+            vec!["a/two.rs".to_string(), "serde".to_string()],
+        );
+        graph.add_file("a/two.rs", vec!["b/lib.rs".to_string()]);
+        graph.add_file("b/lib.rs", vec!["a/one.rs".to_string()]);
+        graph.add_file("c/lib.rs", vec!["a/one.rs".to_string()]);
+
+        let report = CycleReport::detect(&modules, &graph);
+
+        assert_eq!(report.modules.len(), 1);
+        assert_eq!(report.modules[0].members, vec!["a", "b"]);
+        assert_eq!(report.modules[0].display_path(), "a → b → a");
+
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(
+            report.files[0].display_path(),
+            "a/one.rs → a/two.rs → b/lib.rs → a/one.rs"
+        );
+
+        let (module_cycles, file_cycles) = report.for_module(&modules[2]);
+        assert!(module_cycles.is_empty() && file_cycles.is_empty());
+        assert!(report.files[0].is_within(&[
+            "a/one.rs".to_string(),
+            "a/two.rs".to_string(),
+            "b/lib.rs".to_string(),
+            "c/lib.rs".to_string(),
+        ]));
+    }
+}
@@ -0,0 +1,325 @@
+//! Third-party dependency inventory.
+//!
+//! Lists the packages declared by each build manifest, as read by
+//! [`crate::analyze::manifest`].
+//!
+//! Path, workspace and SDK dependencies are project code, not third-party, and
+//! are left out. Each external import in the file graph is then attributed to
+//! the declared package it comes from, looked up in the manifests of the
+//! importing file's ecosystem (nearest directory first).
+
+use crate::analyze::manifest::parse_manifest;
+use crate::analyze::resolve::parent_dir;
+use crate::analyze::{FileGraph, PackageEcosystem};
+use crate::types::{FileEntry, Language};
+use std::collections::{BTreeMap, HashSet};
+
+/// Python import names that differ from their distribution names
+const PYTHON_IMPORT_ALIASES: &[(&str, &str)] = &[
+    ("bs4", "beautifulsoup4"),
+    ("cv2", "opencv-python"),
+    ("dateutil", "python-dateutil"),
+    ("dotenv", "python-dotenv"),
+    ("jwt", "pyjwt"),
+    ("pil", "pillow"),
+    ("sklearn", "scikit-learn"),
+    ("yaml", "pyyaml"),
+];
+
+/// A third-party package declared by a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// Name as declared (Cargo: the key code imports it under)
+    pub name: String,
+    /// Version requirement, if any
+    pub version: Option<String>,
+    pub ecosystem: PackageEcosystem,
+    /// Manifest declaring it, relative to the project root
+    pub manifest: String,
+    /// Only needed for development, tests or builds
+    pub dev: bool,
+    /// Project files importing it, sorted
+    pub used_by: Vec<String>,
+}
+
+/// Declared third-party packages and which imports use them
+#[derive(Debug, Default)]
+pub struct DependencyInventory {
+    /// Sorted by ecosystem, then name
+    pub dependencies: Vec<Dependency>,
+    /// Declared package each (file, import) pair comes from, by index
+    usages: BTreeMap<(String, String), usize>,
+}
+
+impl DependencyInventory {
+    /// Build the inventory from manifest contents keyed by relative path and
+    /// attribute the external imports in `graph`
+    pub fn from_manifests(
+        manifests: &BTreeMap<String, String>,
+        files: &[FileEntry],
+        graph: &FileGraph,
+    ) -> Self {
+        let mut dependencies: Vec<Dependency> = manifests
+            .iter()
+            .flat_map(|(path, content)| manifest_dependencies(path, content))
+            .collect();
+        dependencies.sort_by(|a, b| {
+            (a.ecosystem, a.name.to_lowercase(), &a.manifest).cmp(&(
+                b.ecosystem,
+                b.name.to_lowercase(),
+                &b.manifest,
+            ))
+        });
+        dependencies.dedup_by(|a, b| {
+            a.ecosystem == b.ecosystem && a.name == b.name && a.manifest == b.manifest
+        });
+
+        let project_files: HashSet<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+        let mut inventory = Self {
+            dependencies,
+            usages: BTreeMap::new(),
+        };
+
+        for file in files {
+            let Some(ecosystem) = ecosystem_for(file.language) else {
+                continue;
+            };
+            let Some(imports) = graph.imports.get(&file.relative_path) else {
+                continue;
+            };
+            for import in imports {
+                if project_files.contains(import.as_str()) {
+                    continue;
+                }
+                if let Some(index) = inventory.find(ecosystem, &file.relative_path, import) {
+                    inventory
+                        .usages
+                        .insert((file.relative_path.clone(), import.clone()), index);
+                    let used_by = &mut inventory.dependencies[index].used_by;
+                    if !used_by.contains(&file.relative_path) {
+                        used_by.push(file.relative_path.clone());
+                    }
+                }
+            }
+        }
+        for dependency in &mut inventory.dependencies {
+            dependency.used_by.sort();
+        }
+
+        inventory
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// The declared package `file`'s import `import` comes from
+    pub fn package_for(&self, file: &str, import: &str) -> Option<&Dependency> {
+        self.usages
+            .get(&(file.to_string(), import.to_string()))
+            .map(|&index| &self.dependencies[index])
+    }
+
+    /// Declared packages imported by any of `files`
+    pub fn used_by_any(&self, files: &[String]) -> Vec<&Dependency> {
+        self.dependencies
+            .iter()
+            .filter(|d| d.used_by.iter().any(|f| files.contains(f)))
+            .collect()
+    }
+
+    /// Index of the dependency `import` refers to, preferring the manifest
+    /// nearest to `file`
+    fn find(&self, ecosystem: PackageEcosystem, file: &str, import: &str) -> Option<usize> {
+        let wanted = package_key(ecosystem, import)?;
+        self.dependencies
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.ecosystem == ecosystem)
+            .filter(|(_, d)| match ecosystem {
+                PackageEcosystem::Composer => composer_matches(&d.name, import),
+                _ => normalize(ecosystem, &d.name) == wanted,
+            })
+            .max_by_key(|(_, d)| {
+                let dir = parent_dir(&d.manifest);
+                let contains = dir.is_empty() || file.starts_with(&format!("{}/", dir));
+                (contains, dir.len())
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+/// Ecosystem whose manifests declare the packages a language imports
+fn ecosystem_for(language: Language) -> Option<PackageEcosystem> {
+    match language {
+        Language::Rust => Some(PackageEcosystem::Cargo),
+        Language::JavaScript | Language::TypeScript => Some(PackageEcosystem::Npm),
+        Language::Go => Some(PackageEcosystem::Go),
+        Language::Python => Some(PackageEcosystem::Python),
+        Language::Php => Some(PackageEcosystem::Composer),
+        Language::Ruby => Some(PackageEcosystem::Gem),
+        Language::Dart => Some(PackageEcosystem::Pub),
+        Language::Swift => Some(PackageEcosystem::Swift),
+        _ => None,
+    }
+}
+
+/// Normalized package name an import refers to, if it can name one
+fn package_key(ecosystem: PackageEcosystem, import: &str) -> Option<String> {
+    let package = match ecosystem {
+        PackageEcosystem::Npm => {
+            if import.starts_with('.') || import.starts_with('/') || import.contains(':') {
+                return None;
+            }
+            let mut segments = import.split('/');
+            let first = segments.next()?;
+            if first.starts_with('@') {
+                format!("{}/{}", first, segments.next()?)
+            } else {
+                first.to_string()
+            }
+        }
+        PackageEcosystem::Pub => import
+            .strip_prefix("package:")?
+            .split('/')
+            .next()?
+            .to_string(),
+        PackageEcosystem::Python => {
+            if import.starts_with('.') {
+                return None;
+            }
+            let top = import.split('.').next()?.to_lowercase();
+            PYTHON_IMPORT_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == top)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or(top)
+        }
+        PackageEcosystem::Gem => import.split('/').next()?.to_string(),
+        PackageEcosystem::Cargo
+        | PackageEcosystem::Go
+        | PackageEcosystem::Composer
+        | PackageEcosystem::Swift => import.to_string(),
+    };
+    Some(normalize(ecosystem, &package))
+}
+
+/// Package names compare the way each ecosystem's tooling treats them
+fn normalize(ecosystem: PackageEcosystem, name: &str) -> String {
+    match ecosystem {
+        PackageEcosystem::Cargo => name.replace('-', "_"),
+        PackageEcosystem::Python => name.to_lowercase().replace(['-', '.'], "_"),
+        // Gem and Swift module names rarely match the package exactly:
+        // active_record / activerecord, ArgumentParser / swift-argument-parser
+        PackageEcosystem::Gem | PackageEcosystem::Swift => name
+            .to_lowercase()
+            .trim_start_matches("swift-")
+            .replace(['-', '_'], ""),
+        _ => name.to_string(),
+    }
+}
+
+/// Whether a PHP namespace import (`Symfony\Component\Console\Command`)
+/// belongs to a Composer package (`symfony/console`): the vendor matches the
+/// first namespace segment and the package name a later one
+fn composer_matches(package: &str, import: &str) -> bool {
+    let Some((vendor, name)) = package.split_once('/') else {
+        return false;
+    };
+    let mut segments = import.trim_start_matches('\\').split('\\');
+    let Some(first) = segments.next() else {
+        return false;
+    };
+    let name = name.replace(['-', '_'], "");
+    first.eq_ignore_ascii_case(vendor) && segments.any(|s| s.eq_ignore_ascii_case(&name))
+}
+
+/// Third-party dependencies declared by one manifest
+fn manifest_dependencies(path: &str, content: &str) -> Vec<Dependency> {
+    let Some(manifest) = parse_manifest(path, content) else {
+        return Vec::new();
+    };
+
+    manifest
+        .dependencies
+        .into_iter()
+        .chain(manifest.workspace_dependencies)
+        .filter(|d| !d.local)
+        .map(|d| Dependency {
+            name: d.name,
+            version: d.version,
+            ecosystem: manifest.ecosystem,
+            manifest: path.to_string(),
+            dev: d.dev,
+            used_by: Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> FileEntry {
+        FileEntry::new(path.into(), path.to_string(), 0, 0, 500)
+    }
+
+    #[test]
+    fn test_imports_attributed_to_packages() {
+        let manifests = BTreeMap::from([
+            (
+                "Cargo.toml".to_string(),
+                "[dependencies]\nserde_json = \"1\"\n".to_string(),
+            ),
+            (
+                "web/package.json".to_string(),
+                r#"{"dependencies": {"@acme/ui": "workspace:*", "react": "^18", "@tanstack/query": "5"}}"#
+                    .to_string(),
+            ),
+            (
+                "pyproject.toml".to_string(),
+                "[project]\ndependencies = [\"PyYAML>=6\"]\n".to_string(),
+            ),
+        ]);
+        let files = vec![
+            entry("src/lib.rs"),
+            entry("web/src/app.tsx"),
+            entry("tools/gen.py"),
+        ];
+        let mut graph = FileGraph::new();
+        graph.add_file("src/lib.rs", vec!["serde_json".into(), "std".into()]);
+        graph.add_file(
+            "web/src/app.tsx",
+            vec![
+                "react/jsx-runtime".into(),
+                "@tanstack/query/core".into(),
+                "node:fs".into(),
+            ],
+        );
+        graph.add_file("tools/gen.py", vec!["yaml".into(), "os".into()]);
+
+        let inventory = DependencyInventory::from_manifests(&manifests, &files, &graph);
+        let package =
+            |file: &str, import: &str| inventory.package_for(file, import).map(|d| d.name.as_str());
+
+        assert_eq!(inventory.dependencies.len(), 4);
+        assert_eq!(package("src/lib.rs", "serde_json"), Some("serde_json"));
+        assert_eq!(package("src/lib.rs", "std"), None);
+        assert_eq!(
+            package("web/src/app.tsx", "react/jsx-runtime"),
+            Some("react")
+        );
+        assert_eq!(
+            package("web/src/app.tsx", "@tanstack/query/core"),
+            Some("@tanstack/query")
+        );
+        assert_eq!(package("web/src/app.tsx", "node:fs"), None);
+        assert_eq!(package("tools/gen.py", "yaml"), Some("PyYAML"));
+        assert_eq!(
+            inventory
+                .used_by_any(&["web/src/app.tsx".to_string()])
+                .len(),
+            2
+        );
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::path::Path;
 
 #[derive(Debug, Default)]
 pub struct FileGraph {
@@ -23,22 +22,6 @@ impl FileGraph {
         }
     }
 
-    pub fn resolve_import(&self, from_file: &str, import_path: &str) -> Option<String> {
-        let from_dir = Path::new(from_file).parent()?;
-
-        if import_path.starts_with("./") || import_path.starts_with("../") {
-            let resolved = from_dir.join(import_path);
-            return Some(normalize_path(&resolved.to_string_lossy()));
-        }
-
-        if !import_path.contains('/') && !import_path.contains('.') {
-            let potential = from_dir.join(import_path);
-            return Some(normalize_path(&potential.to_string_lossy()));
-        }
-
-        Some(import_path.to_string())
-    }
-
     pub fn hub_files(&self) -> Vec<(String, usize)> {
         let mut hubs: Vec<(String, usize)> = self
             .importers
@@ -47,7 +30,7 @@ impl FileGraph {
             .map(|(file, importers)| (file.clone(), importers.len()))
             .collect();
 
-        hubs.sort_by(|a, b| b.1.cmp(&a.1));
+        hubs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         hubs
     }
 
@@ -59,13 +42,6 @@ impl FileGraph {
     }
 }
 
-fn normalize_path(path: &str) -> String {
-    path.replace("\\", "/")
-        .replace("//", "/")
-        .trim_start_matches("./")
-        .to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -23,6 +23,9 @@ static ENUM_PATTERN: Lazy<Regex> =
 static TYPEDEF_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^typedef\s+.+?\s+(\w+)\s*;").unwrap());
 
+static INCLUDE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^[ \t]*#[ \t]*include[ \t]*([<"])([^>"]+)[>"]"#).unwrap());
+
 impl LanguageParser for CParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -119,6 +122,25 @@ impl LanguageParser for CParser {
         symbols.dedup_by(|a, b| a.name == b.name && a.line_range.start == b.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        // System includes keep their angle brackets so resolution can tell
+        // them from quoted ones
+        for cap in INCLUDE_PATTERN.captures_iter(content) {
+            if let Some(m) = cap.get(2) {
+                let path = if &cap[1] == "<" {
+                    format!("<{}>", m.as_str())
+                } else {
+                    m.as_str().to_string()
+                };
+                push_unique(&mut imports, path);
+            }
+        }
+
+        imports
+    }
 }
 
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
@@ -161,3 +183,17 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "#include <stdio.h>\n#include \"util/list.h\"\n  #  include <sys/types.h>\n#include <stdio.h>\n";
+        assert_eq!(
+            CParser.parse_imports(content),
+            vec!["<stdio.h>", "util/list.h", "<sys/types.h>"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -35,6 +35,9 @@ static METHOD_DECL_PATTERN: Lazy<Regex> = Lazy::new(|| {
 static VISIBILITY_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^(public|private|protected)\s*:").unwrap());
 
+static INCLUDE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^[ \t]*#[ \t]*include[ \t]*([<"])([^>"]+)[>"]"#).unwrap());
+
 impl LanguageParser for CppParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -156,6 +159,25 @@ impl LanguageParser for CppParser {
         symbols.dedup_by(|a, b| a.name == b.name && a.line_range.start == b.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        // System includes keep their angle brackets so resolution can tell
+        // them from quoted ones
+        for cap in INCLUDE_PATTERN.captures_iter(content) {
+            if let Some(m) = cap.get(2) {
+                let path = if &cap[1] == "<" {
+                    format!("<{}>", m.as_str())
+                } else {
+                    m.as_str().to_string()
+                };
+                push_unique(&mut imports, path);
+            }
+        }
+
+        imports
+    }
 }
 
 fn is_control_flow_keyword(name: &str) -> bool {
@@ -202,3 +224,17 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "#include <vector>\n#include \"engine/world.hpp\"\n#include <vector>\n";
+        assert_eq!(
+            CppParser.parse_imports(content),
+            vec!["<vector>", "engine/world.hpp"]
+        );
+    }
+}
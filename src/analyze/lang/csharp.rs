@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -23,6 +23,12 @@ static PROPERTY_PATTERN: Lazy<Regex> = Lazy::new(|| {
         .unwrap()
 });
 
+// using X.Y; using static X.Y; using Alias = X.Y; global using X;
+static USING_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:global\s+)?using\s+(?:static\s+)?(?:\w+\s*=\s*)?([\w.]+)\s*;")
+        .unwrap()
+});
+
 impl LanguageParser for CSharpParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -127,6 +133,10 @@ impl LanguageParser for CSharpParser {
         symbols.sort_by_key(|s| s.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        collect_unique(&USING_PATTERN, content)
+    }
 }
 
 fn parse_visibility(s: &str) -> Visibility {
@@ -179,3 +189,17 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "global using System;\nusing static System.Math;\nusing Json = Newtonsoft.Json;\n\nclass A {\n    void F() {\n        using (var s = Open()) {}\n        using var t = Open();\n    }\n}\n";
+        assert_eq!(
+            CSharpParser.parse_imports(content),
+            vec!["System", "System.Math", "Newtonsoft.Json"]
+        );
+    }
+}
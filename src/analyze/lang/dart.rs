@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -35,6 +35,10 @@ static GETTER_PATTERN: Lazy<Regex> =
 static SETTER_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^\s*set\s+(\w+)\s*\([^)]*\)\s*\{").unwrap());
 
+// import 'package:foo/foo.dart' as f; export 'src/a.dart'; part 'b.dart';
+static IMPORT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^[ \t]*(?:import|export|part)\s+['"]([^'"]+)['"]"#).unwrap());
+
 impl LanguageParser for DartParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -180,6 +184,10 @@ impl LanguageParser for DartParser {
         symbols.dedup_by(|a, b| a.name == b.name && a.line_range.start == b.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        collect_unique(&IMPORT_PATTERN, content)
+    }
 }
 
 fn get_visibility(name: &str) -> Visibility {
@@ -234,3 +242,22 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "import 'package:flutter/material.dart';\nimport '../models/user.dart' as user;\nexport 'src/widgets.dart' show Button;\npart 'app.g.dart';\n";
+        assert_eq!(
+            DartParser.parse_imports(content),
+            vec![
+                "package:flutter/material.dart",
+                "../models/user.dart",
+                "src/widgets.dart",
+                "app.g.dart"
+            ]
+        );
+    }
+}
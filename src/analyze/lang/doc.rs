@@ -0,0 +1,380 @@
+//! Doc comment extraction shared by every parser backend.
+//!
+//! Works on source lines around each symbol's start line, so it applies equally
+//! to regex and tree-sitter symbols: comments directly above a declaration
+//! (skipping attributes, annotations and decorators), Python docstrings below
+//! the `def`/`class` header, and Rust `//!` inner docs for inline modules.
+
+use crate::types::{Language, Symbol, SymbolKind};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static XML_REF: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<(?:see|seealso|paramref|typeparamref)\s+\w+="([^"]+)"\s*/>"#).unwrap()
+});
+
+/// Longest multi-line attribute searched for its opening line
+const MAX_ATTRIBUTE_LINES: usize = 30;
+
+static XML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"</?\w+[^>]*>").unwrap());
+
+/// How a language writes doc comments
+struct DocStyle {
+    /// Line comment prefixes that mark a doc line
+    line_prefixes: &'static [&'static str],
+    /// Whether `/** ... */` blocks are doc comments
+    block: bool,
+}
+
+fn doc_style(language: Language) -> Option<DocStyle> {
+    let style = match language {
+        Language::Rust => DocStyle {
+            line_prefixes: &["///"],
+            block: true,
+        },
+        Language::JavaScript
+        | Language::TypeScript
+        | Language::Java
+        | Language::Kotlin
+        | Language::Php => DocStyle {
+            line_prefixes: &[],
+            block: true,
+        },
+        Language::CSharp => DocStyle {
+            line_prefixes: &["///"],
+            block: false,
+        },
+        Language::Swift | Language::Dart => DocStyle {
+            line_prefixes: &["///"],
+            block: true,
+        },
+        Language::C | Language::Cpp => DocStyle {
+            line_prefixes: &["///", "//!"],
+            block: true,
+        },
+        Language::Go => DocStyle {
+            line_prefixes: &["//"],
+            block: false,
+        },
+        Language::Ruby => DocStyle {
+            line_prefixes: &["#"],
+            block: false,
+        },
+        Language::Python | Language::Unknown => return None,
+    };
+    Some(style)
+}
+
+/// Fill in `doc_comment` for every symbol that has one
+pub fn attach_doc_comments(language: Language, content: &str, symbols: &mut [Symbol]) {
+    let lines: Vec<&str> = content.lines().collect();
+
+    for sym in symbols.iter_mut() {
+        let idx = sym.line_range.start.saturating_sub(1);
+        if idx >= lines.len() {
+            continue;
+        }
+
+        let doc = if language == Language::Python {
+            python_docstring(&lines, idx)
+        } else {
+            let mut doc = doc_style(language).and_then(|style| leading_doc(&lines, idx, &style));
+            if doc.is_none() && language == Language::Rust && sym.kind == SymbolKind::Module {
+                doc = rust_inner_doc(&lines, idx);
+            }
+            doc
+        };
+
+        if let Some(doc) = doc {
+            sym.doc_comment = Some(doc);
+        }
+    }
+}
+
+/// Attributes, annotations, decorators and compiler directives that may sit
+/// between a doc comment and its declaration
+pub(super) fn is_attribute(line: &str) -> bool {
+    line.starts_with("#[")
+        || line.starts_with('@')
+        || line.starts_with("//go:")
+        || (line.starts_with('[') && line.ends_with(']'))
+}
+
+/// Start of the attributes directly above the line at `end`, including ones
+/// spanning several lines (`#[derive(\n    Debug,\n)]`, `@Component({ ... })`)
+fn attributes_start(lines: &[&str], mut end: usize) -> usize {
+    while end > 0 {
+        if is_attribute(lines[end - 1].trim()) {
+            end -= 1;
+        } else if let Some(start) = multiline_attribute_start(lines, end - 1) {
+            end = start;
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+/// First line of a multi-line attribute ending on the line at `last`, found by
+/// balancing brackets upward
+fn multiline_attribute_start(lines: &[&str], last: usize) -> Option<usize> {
+    if !lines[last].trim_end().ends_with([')', ']', '}']) {
+        return None;
+    }
+
+    let mut depth = 0i32;
+    for i in (last.saturating_sub(MAX_ATTRIBUTE_LINES)..=last).rev() {
+        for c in lines[i].chars().rev() {
+            match c {
+                ')' | ']' | '}' => depth += 1,
+                '(' | '[' | '{' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 {
+            let first = lines[i].trim();
+            let opens_attribute =
+                first.starts_with("#[") || first.starts_with('@') || first.starts_with('[');
+            return (depth == 0 && i < last && opens_attribute).then_some(i);
+        }
+    }
+    None
+}
+
+/// Doc comment directly above the line at `idx`
+fn leading_doc(lines: &[&str], idx: usize, style: &DocStyle) -> Option<String> {
+    let end = attributes_start(lines, idx);
+    if end == 0 {
+        return None;
+    }
+
+    let last = lines[end - 1].trim();
+    if style.block && last.ends_with("*/") {
+        let start = (0..end)
+            .rev()
+            .find(|&i| lines[i].trim().starts_with("/*"))?;
+        let first = lines[start].trim();
+        if !(first.starts_with("/**") || first.starts_with("/*!")) || first.starts_with("/**/") {
+            return None;
+        }
+        return normalize(lines[start..end].iter().map(|l| strip_block_line(l.trim())));
+    }
+
+    let prefix = style.line_prefixes.iter().find(|p| is_doc_line(last, p))?;
+    let start = (0..end)
+        .rev()
+        .take_while(|&i| is_doc_line(lines[i].trim(), prefix))
+        .last()?;
+
+    normalize(lines[start..end].iter().map(|l| {
+        let text = l.trim()[prefix.len()..]
+            .strip_prefix(' ')
+            .unwrap_or(&l.trim()[prefix.len()..]);
+        strip_xml(text)
+    }))
+}
+
+fn is_doc_line(line: &str, prefix: &str) -> bool {
+    if !line.starts_with(prefix) {
+        return false;
+    }
+    let rest = &line[prefix.len()..];
+    match prefix {
+        // `////` is a plain comment, not a doc comment
+        "///" => !rest.starts_with('/'),
+        // Go compiler directives are not part of the doc
+        "//" => !rest.starts_with("go:") && !rest.starts_with("line "),
+        // Shebangs, encoding and rubocop directives
+        "#" => {
+            !rest.starts_with('!')
+                && !rest.starts_with('[')
+                && !rest.contains("frozen_string_literal")
+                && !rest.contains("rubocop:")
+                && !rest.contains("-*-")
+        }
+        _ => true,
+    }
+}
+
+fn strip_block_line(line: &str) -> String {
+    let line = line
+        .trim_start_matches("/**")
+        .trim_start_matches("/*!")
+        .trim_end_matches("*/")
+        .trim();
+    let line = line.strip_prefix('*').unwrap_or(line);
+    strip_xml(line.strip_prefix(' ').unwrap_or(line))
+}
+
+/// Drop C#-style XML doc tags, keeping referenced names
+fn strip_xml(line: &str) -> String {
+    if !line.contains('<') {
+        return line.to_string();
+    }
+    let line = XML_REF.replace_all(line, "`$1`");
+    XML_TAG.replace_all(&line, "").to_string()
+}
+
+/// Rust `//!` lines at the top of an inline module body
+fn rust_inner_doc(lines: &[&str], idx: usize) -> Option<String> {
+    let doc: Vec<&str> = lines[idx + 1..]
+        .iter()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with("//!"))
+        .map(|l| l[3..].strip_prefix(' ').unwrap_or(&l[3..]))
+        .collect();
+    normalize(doc.into_iter().map(String::from))
+}
+
+/// Docstring opening the body of the `def`/`class` at `idx`
+fn python_docstring(lines: &[&str], idx: usize) -> Option<String> {
+    // The header may span several lines; it ends where brackets balance. A
+    // header that doesn't end in `:` there is a one-liner with no docstring.
+    let mut depth = 0i32;
+    let mut header_end = None;
+    for (i, line) in lines.iter().enumerate().skip(idx).take(20) {
+        let code = line.split('#').next().unwrap_or("");
+        depth += code.matches(['(', '[', '{']).count() as i32;
+        depth -= code.matches([')', ']', '}']).count() as i32;
+        if depth <= 0 {
+            if code.trim_end().ends_with(':') {
+                header_end = Some(i);
+            }
+            break;
+        }
+    }
+    let header_end = header_end?;
+    let body = (header_end + 1..lines.len()).find(|&i| !lines[i].trim().is_empty())?;
+
+    let first = lines[body].trim();
+    let opening = first.trim_start_matches(['r', 'u', 'R', 'U']);
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| opening.starts_with(q))?;
+    let rest = &opening[quote.len()..];
+
+    if let Some(end) = rest.find(quote) {
+        return normalize(std::iter::once(rest[..end].to_string()));
+    }
+
+    let mut doc = vec![rest.to_string()];
+    for line in &lines[body + 1..] {
+        if let Some(end) = line.find(quote) {
+            doc.push(line[..end].to_string());
+            break;
+        }
+        doc.push(line.to_string());
+    }
+    normalize(dedent(doc).into_iter())
+}
+
+/// Remove the common indentation of every line after the first
+fn dedent(mut lines: Vec<String>) -> Vec<String> {
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    for line in lines.iter_mut().skip(1) {
+        *line = line.get(indent..).unwrap_or("").to_string();
+    }
+    lines
+}
+
+/// Trim trailing whitespace and surrounding blank lines; `None` if nothing is left
+fn normalize(lines: impl Iterator<Item = String>) -> Option<String> {
+    let lines: Vec<String> = lines.map(|l| l.trim_end().to_string()).collect();
+    let start = lines.iter().position(|l| !l.is_empty())?;
+    let end = lines.iter().rposition(|l| !l.is_empty())?;
+    Some(lines[start..=end].join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Visibility;
+
+    fn doc_at(language: Language, content: &str, line: usize) -> Option<String> {
+        let mut symbols = vec![Symbol::new(
+            SymbolKind::Function,
+            "f".to_string(),
+            line,
+            Visibility::Public,
+        )];
+        attach_doc_comments(language, content, &mut symbols);
+        symbols.remove(0).doc_comment
+    }
+
+    #[test]
+    fn test_line_comment_docs_skip_attributes() {
+        let rust = "//// banner\n/// Adds two numbers.\n///\n/// Saturates on overflow.\n#[inline]\npub fn add() {}\n";
+        assert_eq!(
+            doc_at(Language::Rust, rust, 6).as_deref(),
+            Some("Adds two numbers.\n\nSaturates on overflow.")
+        );
+
+        let go = "// Run starts the server.\n//go:noinline\nfunc Run() {}\n";
+        assert_eq!(
+            doc_at(Language::Go, go, 3).as_deref(),
+            Some("Run starts the server.")
+        );
+
+        let csharp = "/// <summary>Loads a <see cref=\"Config\"/>.</summary>\n[Obsolete]\npublic void Load() {}\n";
+        assert_eq!(
+            doc_at(Language::CSharp, csharp, 3).as_deref(),
+            Some("Loads a `Config`.")
+        );
+    }
+
+    #[test]
+    fn test_docs_skip_multiline_attributes() {
+        let rust = "/// A point.\n#[derive(\n    Debug,\n    Clone,\n)]\n#[serde(default)]\npub struct Point {}\n";
+        assert_eq!(doc_at(Language::Rust, rust, 7).as_deref(), Some("A point."));
+
+        let ts = "/** The app shell. */\n@Component({\n  selector: 'app-root',\n  template: '<p></p>',\n})\nexport class App {}\n";
+        assert_eq!(
+            doc_at(Language::TypeScript, ts, 6).as_deref(),
+            Some("The app shell.")
+        );
+
+        let csharp = "/// <summary>Old loader.</summary>\n[Obsolete(\n    \"Use Load2\")]\npublic void Load() {}\n";
+        assert_eq!(
+            doc_at(Language::CSharp, csharp, 4).as_deref(),
+            Some("Old loader.")
+        );
+
+        // A preceding function body is not an attribute
+        let rust = "/// Not mine.\nfn a() {\n}\nfn b() {}\n";
+        assert_eq!(doc_at(Language::Rust, rust, 4), None);
+    }
+
+    #[test]
+    fn test_block_docs() {
+        let ts = "/**\n * Fetch a user.\n * @param id - user id\n */\n@Get()\nexport function fetch() {}\n";
+        assert_eq!(
+            doc_at(Language::TypeScript, ts, 6).as_deref(),
+            Some("Fetch a user.\n@param id - user id")
+        );
+
+        let plain = "/* not a doc */\nfunction f() {}\n";
+        assert_eq!(doc_at(Language::JavaScript, plain, 2), None);
+    }
+
+    #[test]
+    fn test_python_docstrings() {
+        let py =
+            "def f(\n    a,\n):\n    \"\"\"Do things.\n\n    More detail.\n    \"\"\"\n    pass\n";
+        assert_eq!(
+            doc_at(Language::Python, py, 1).as_deref(),
+            Some("Do things.\n\nMore detail.")
+        );
+
+        let one_line = "class A:\n    '''A thing.'''\n";
+        assert_eq!(
+            doc_at(Language::Python, one_line, 1).as_deref(),
+            Some("A thing.")
+        );
+    }
+}
@@ -1,12 +1,14 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 pub struct GoParser;
 
-static FUNC_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^func\s+(?:\([^)]+\)\s+)?(\w+)\s*\(").unwrap());
+static FUNC_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^func\s+(?:\(\s*(?:\w+\s+)?\*?\s*(\w+)(?:\[[^\]]*\])?\s*\)\s+)?(\w+)\s*\(")
+        .unwrap()
+});
 
 static STRUCT_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^type\s+(\w+)\s+struct\b").unwrap());
@@ -16,13 +18,14 @@ static INTERFACE_PATTERN: Lazy<Regex> =
 
 static CONST_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^const\s+(\w+)\s*=").unwrap());
 
-static IMPORT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?m)^\s*"([^"]+)"\s*$"#).unwrap());
+static IMPORT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*(?:[\w.]+\s+)?"([^"]+)""#).unwrap());
 
 static IMPORT_BLOCK_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?s)import\s*\(([^)]+)\)").unwrap());
 
 static SINGLE_IMPORT_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"(?m)^import\s+"([^"]+)""#).unwrap());
+    Lazy::new(|| Regex::new(r#"(?m)^import\s+(?:[\w.]+\s+)?"([^"]+)""#).unwrap());
 
 impl LanguageParser for GoParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
@@ -30,7 +33,8 @@ impl LanguageParser for GoParser {
         let lines: Vec<&str> = content.lines().collect();
 
         for cap in FUNC_PATTERN.captures_iter(content) {
-            let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let receiver = cap.get(1).map(|m| m.as_str().to_string());
+            let name = cap.get(2).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
             let signature = lines.get(line - 1).map(|s| s.trim().to_string());
             let end_line = find_brace_end(content, cap.get(0).unwrap().end());
@@ -41,8 +45,13 @@ impl LanguageParser for GoParser {
                 .map(|c| c.is_uppercase())
                 .unwrap_or(false);
 
+            let kind = if receiver.is_some() {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            };
             let mut sym = Symbol::new(
-                SymbolKind::Function,
+                kind,
                 name.to_string(),
                 line,
                 if is_exported {
@@ -57,6 +66,9 @@ impl LanguageParser for GoParser {
             if let Some(end) = end_line {
                 sym = sym.with_line_range(line, end);
             }
+            if let Some(receiver) = receiver {
+                sym = sym.with_parent(receiver);
+            }
             symbols.push(sym);
         }
 
@@ -141,25 +153,13 @@ impl LanguageParser for GoParser {
     }
 
     fn parse_imports(&self, content: &str) -> Vec<String> {
-        let mut imports = Vec::new();
-
-        for cap in SINGLE_IMPORT_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let path = extract_go_package_name(m.as_str());
-                if !imports.contains(&path) {
-                    imports.push(path);
-                }
-            }
-        }
+        let mut imports = collect_unique(&SINGLE_IMPORT_PATTERN, content);
 
         for cap in IMPORT_BLOCK_PATTERN.captures_iter(content) {
             if let Some(block) = cap.get(1) {
                 for line_cap in IMPORT_PATTERN.captures_iter(block.as_str()) {
                     if let Some(m) = line_cap.get(1) {
-                        let path = extract_go_package_name(m.as_str());
-                        if !imports.contains(&path) {
-                            imports.push(path);
-                        }
+                        push_unique(&mut imports, m.as_str().to_string());
                     }
                 }
             }
@@ -169,14 +169,6 @@ impl LanguageParser for GoParser {
     }
 }
 
-fn extract_go_package_name(import_path: &str) -> String {
-    import_path
-        .rsplit('/')
-        .next()
-        .unwrap_or(import_path)
-        .to_string()
-}
-
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -20,6 +20,9 @@ static METHOD_PATTERN: Lazy<Regex> = Lazy::new(|| {
 static ANNOTATION_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^\s*@interface\s+(\w+)").unwrap());
 
+static IMPORT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*import\s+(?:static\s+)?([\w.]+(?:\.\*)?)\s*;").unwrap());
+
 impl LanguageParser for JavaParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -113,6 +116,10 @@ impl LanguageParser for JavaParser {
         symbols.sort_by_key(|s| s.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        collect_unique(&IMPORT_PATTERN, content)
+    }
 }
 
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
@@ -155,3 +162,21 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "package app;\nimport java.util.List;\nimport static org.junit.Assert.assertEquals;\nimport com.example.model.*;\nimport java.util.List;\n";
+        assert_eq!(
+            JavaParser.parse_imports(content),
+            vec![
+                "java.util.List",
+                "org.junit.Assert.assertEquals",
+                "com.example.model.*"
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -174,26 +174,10 @@ impl LanguageParser for JavaScriptParser {
     }
 
     fn parse_imports(&self, content: &str) -> Vec<String> {
-        let mut imports = Vec::new();
-
-        for cap in IMPORT_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let path = m.as_str().to_string();
-                if !imports.contains(&path) {
-                    imports.push(path);
-                }
-            }
-        }
-
-        for cap in REQUIRE_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let path = m.as_str().to_string();
-                if !imports.contains(&path) {
-                    imports.push(path);
-                }
-            }
+        let mut imports = collect_unique(&IMPORT_PATTERN, content);
+        for import in collect_unique(&REQUIRE_PATTERN, content) {
+            push_unique(&mut imports, import);
         }
-
         imports
     }
 }
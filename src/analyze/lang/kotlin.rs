@@ -0,0 +1,197 @@
+use crate::analyze::lang::{collect_unique, LanguageParser};
+use crate::types::{Symbol, SymbolKind, Visibility};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct KotlinParser;
+
+// data class Name(...), sealed interface Name, enum class Name, object Name
+static CLASS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*((?:(?:public|private|protected|internal|abstract|open|sealed|data|enum|annotation|inner|value|final|fun)[ \t]+)*)(class|interface|object)[ \t]+(\w+)")
+        .unwrap()
+});
+
+// fun name(, fun <T> Receiver.name(, suspend fun name(
+static FUN_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^([ \t]*)((?:(?:public|private|protected|internal|open|override|abstract|final|suspend|inline|operator|infix|tailrec|external)[ \t]+)*)fun[ \t]+(?:<[^>]*>[ \t]*)?(?:[\w.<>?, ]+\.)?(\w+)[ \t]*\(")
+        .unwrap()
+});
+
+// import a.b.C, import a.b.*, import a.b.C as D
+static IMPORT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*import[ \t]+(\w+(?:\.\w+)*(?:\.\*)?)").unwrap());
+
+impl LanguageParser for KotlinParser {
+    fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        for cap in CLASS_PATTERN.captures_iter(content) {
+            let modifiers = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let kind_str = cap.get(2).map(|m| m.as_str()).unwrap_or("class");
+            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+
+            let kind = match kind_str {
+                "interface" => SymbolKind::Interface,
+                _ if has_modifier(modifiers, "enum") => SymbolKind::Enum,
+                _ => SymbolKind::Class,
+            };
+
+            let end_line = find_body_end(content, cap.get(0).unwrap().end())
+                .map(|pos| line_number_at_offset(content, pos))
+                .unwrap_or(line);
+
+            let full_match = cap.get(0).unwrap().as_str().trim();
+            let mut sym = Symbol::new(kind, name.to_string(), line, get_visibility(modifiers));
+            sym = sym.with_line_range(line, end_line);
+            sym = sym.with_signature(full_match.to_string());
+            symbols.push(sym);
+        }
+
+        for cap in FUN_PATTERN.captures_iter(content) {
+            let indent = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let modifiers = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+
+            let kind = if indent.is_empty() {
+                SymbolKind::Function
+            } else {
+                SymbolKind::Method
+            };
+
+            let end_line = find_body_end(content, cap.get(0).unwrap().end())
+                .map(|pos| line_number_at_offset(content, pos))
+                .unwrap_or(line);
+
+            let full_match = cap.get(0).unwrap().as_str().trim();
+            let signature = full_match.trim_end_matches('(').to_string() + "(...)";
+
+            let mut sym = Symbol::new(kind, name.to_string(), line, get_visibility(modifiers));
+            sym = sym.with_line_range(line, end_line);
+            sym = sym.with_signature(signature);
+            symbols.push(sym);
+        }
+
+        symbols.sort_by_key(|s| s.line_range.start);
+        symbols
+    }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        collect_unique(&IMPORT_PATTERN, content)
+    }
+}
+
+fn has_modifier(modifiers: &str, modifier: &str) -> bool {
+    modifiers.split_whitespace().any(|m| m == modifier)
+}
+
+/// Kotlin declarations are public unless marked otherwise
+fn get_visibility(modifiers: &str) -> Visibility {
+    if has_modifier(modifiers, "private") {
+        Visibility::Private
+    } else if has_modifier(modifiers, "protected") {
+        Visibility::Protected
+    } else if has_modifier(modifiers, "internal") {
+        Visibility::Internal
+    } else {
+        Visibility::Public
+    }
+}
+
+fn line_number_at_offset(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Offset of the `}` closing the body that opens after `start`. Declarations
+/// without a body (`class Id(val raw: String)`, `fun f() = 1`) end on the
+/// line where their header does.
+fn find_body_end(content: &str, start: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut parens = 0;
+    let mut in_string = false;
+    let mut string_char = b'"';
+    let mut i = start;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if in_string {
+            if b == string_char && (i == 0 || bytes[i - 1] != b'\\') {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        match b {
+            b'"' | b'\'' => {
+                in_string = true;
+                string_char = b;
+            }
+            b'(' => parens += 1,
+            b')' => parens -= 1,
+            b'=' if depth == 0 && parens == 0 => return None,
+            b'\n' if depth == 0 && parens == 0 => {
+                // A body or supertype list may start on the next line
+                let next = content[i + 1..].trim_start();
+                if !next.starts_with(['{', ':']) && !next.starts_with("where") {
+                    return None;
+                }
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_symbols_and_imports() {
+        let content = "package com.acme.billing\n\nimport com.acme.core.Money\nimport com.acme.util.*\nimport kotlinx.coroutines.flow.Flow as F\n\ndata class Invoice(val id: String)\n\nenum class Status { OPEN, PAID }\n\ninternal object Registry {\n    private fun lookup(id: String): Invoice? {\n        return null\n    }\n}\n\nsuspend fun <T> List<T>.total(): Money = TODO()\n";
+        let symbols = KotlinParser.parse_symbols(content);
+        let summary: Vec<(&str, SymbolKind, Visibility, usize, usize)> = symbols
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.kind,
+                    s.visibility,
+                    s.line_range.start,
+                    s.line_range.end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Invoice", SymbolKind::Class, Visibility::Public, 7, 7),
+                ("Status", SymbolKind::Enum, Visibility::Public, 9, 9),
+                ("Registry", SymbolKind::Class, Visibility::Internal, 11, 15),
+                ("lookup", SymbolKind::Method, Visibility::Private, 12, 14),
+                ("total", SymbolKind::Function, Visibility::Public, 17, 17),
+            ]
+        );
+
+        assert_eq!(
+            KotlinParser.parse_imports(content),
+            vec![
+                "com.acme.core.Money",
+                "com.acme.util.*",
+                "kotlinx.coroutines.flow.Flow"
+            ]
+        );
+    }
+}
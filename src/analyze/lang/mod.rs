@@ -2,29 +2,42 @@ mod c;
 mod cpp;
 mod csharp;
 mod dart;
+mod doc;
 mod go;
 mod java;
 mod javascript;
+mod kotlin;
+mod nesting;
 mod php;
 mod python;
 mod ruby;
 mod rust;
+mod signature;
 mod swift;
+#[cfg(feature = "tree-sitter")]
+mod treesitter;
 
 pub use c::CParser;
 pub use cpp::CppParser;
 pub use csharp::CSharpParser;
 pub use dart::DartParser;
+pub use doc::attach_doc_comments;
 pub use go::GoParser;
 pub use java::JavaParser;
 pub use javascript::JavaScriptParser;
+pub use kotlin::KotlinParser;
+pub use nesting::assign_parents;
 pub use php::PhpParser;
 pub use python::PythonParser;
 pub use ruby::RubyParser;
 pub use rust::RustParser;
+pub use signature::attach_signatures;
 pub use swift::SwiftParser;
+#[cfg(feature = "tree-sitter")]
+pub use treesitter::TreeSitterParser;
 
 use crate::types::{Language, Symbol};
+use regex::Regex;
 
 pub trait LanguageParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol>;
@@ -35,7 +48,49 @@ pub trait LanguageParser {
     }
 }
 
+/// First capture group of every `pattern` match, each import kept once in
+/// order of first appearance
+pub(crate) fn collect_unique(pattern: &Regex, content: &str) -> Vec<String> {
+    let mut imports = Vec::new();
+    for cap in pattern.captures_iter(content) {
+        if let Some(m) = cap.get(1) {
+            push_unique(&mut imports, m.as_str().to_string());
+        }
+    }
+    imports
+}
+
+/// Append `import` unless it is empty or already listed
+pub(crate) fn push_unique(imports: &mut Vec<String>, import: String) {
+    if !import.is_empty() && !imports.contains(&import) {
+        imports.push(import);
+    }
+}
+
+/// Name of the parsing backend compiled in, recorded with cached results
+pub const BACKEND: &str = if cfg!(feature = "tree-sitter") {
+    "tree-sitter"
+} else {
+    "regex"
+};
+
+/// Parser for a language: the tree-sitter backend when the `tree-sitter`
+/// feature is enabled, otherwise the regex parser
 pub fn get_parser(language: Language) -> Option<Box<dyn LanguageParser>> {
+    let parser = get_regex_parser(language)?;
+
+    #[cfg(feature = "tree-sitter")]
+    let parser: Box<dyn LanguageParser> = match language {
+        // No vendored grammar; Kotlin keeps its regex parser on both backends
+        Language::Kotlin => parser,
+        _ => Box::new(TreeSitterParser::new(language, parser)?),
+    };
+
+    Some(parser)
+}
+
+/// Line-regex parser for a language
+pub fn get_regex_parser(language: Language) -> Option<Box<dyn LanguageParser>> {
     match language {
         Language::Rust => Some(Box::new(RustParser)),
         Language::Python => Some(Box::new(PythonParser)),
@@ -43,6 +98,7 @@ pub fn get_parser(language: Language) -> Option<Box<dyn LanguageParser>> {
         Language::Go => Some(Box::new(GoParser)),
         Language::Php => Some(Box::new(PhpParser)),
         Language::Java => Some(Box::new(JavaParser)),
+        Language::Kotlin => Some(Box::new(KotlinParser)),
         Language::CSharp => Some(Box::new(CSharpParser)),
         Language::C => Some(Box::new(CParser)),
         Language::Cpp => Some(Box::new(CppParser)),
@@ -0,0 +1,76 @@
+//! Parent assignment for nested symbols.
+//!
+//! Backends that know the enclosing type (tree-sitter, Rust `impl` blocks, Go
+//! receivers) set `Symbol::parent` themselves. Everything else gets the
+//! innermost container symbol whose line range encloses it.
+
+use crate::types::Symbol;
+
+/// Fill in `parent` for symbols that don't have one yet
+pub fn assign_parents(symbols: &mut [Symbol]) {
+    let containers: Vec<(usize, usize, String)> = symbols
+        .iter()
+        .filter(|s| s.is_container() && s.line_range.end > s.line_range.start)
+        .map(|s| (s.line_range.start, s.line_range.end, s.name.clone()))
+        .collect();
+
+    for sym in symbols.iter_mut() {
+        if sym.parent.is_some() {
+            continue;
+        }
+
+        // C++ out-of-line definitions such as `Foo::bar`
+        if let Some((owner, name)) = sym.name.rsplit_once("::") {
+            if !owner.is_empty() && !name.is_empty() {
+                let (owner, name) = (owner.to_string(), name.to_string());
+                sym.parent = Some(owner);
+                sym.name = name;
+                continue;
+            }
+        }
+
+        let (start, end) = (sym.line_range.start, sym.line_range.end);
+        sym.parent = containers
+            .iter()
+            .filter(|(c_start, c_end, _)| *c_start < start && end <= *c_end)
+            .max_by_key(|(c_start, _, _)| *c_start)
+            .map(|(_, _, name)| name.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SymbolKind, Visibility};
+
+    fn sym(kind: SymbolKind, name: &str, start: usize, end: usize) -> Symbol {
+        Symbol::new(kind, name.to_string(), start, Visibility::Public).with_line_range(start, end)
+    }
+
+    #[test]
+    fn test_assign_parents_by_range() {
+        let mut symbols = vec![
+            sym(SymbolKind::Module, "Outer", 1, 20),
+            sym(SymbolKind::Class, "Inner", 2, 10),
+            sym(SymbolKind::Method, "run", 3, 5),
+            sym(SymbolKind::Function, "helper", 12, 14),
+            sym(SymbolKind::Function, "Inner::go", 30, 31),
+            sym(SymbolKind::Function, "free", 40, 41),
+        ];
+        assign_parents(&mut symbols);
+
+        let parents: Vec<_> = symbols.iter().map(|s| s.parent.as_deref()).collect();
+        assert_eq!(symbols[4].qualified_name(), "Inner::go");
+        assert_eq!(
+            parents,
+            vec![
+                None,
+                Some("Outer"),
+                Some("Inner"),
+                Some("Outer"),
+                Some("Inner"),
+                None
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -20,6 +20,14 @@ static FUNCTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
 static CONST_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^\s*(public|private|protected)?\s*const\s+(\w+)\s*=").unwrap());
 
+static USE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^use\s+(?:function\s+|const\s+)?([^;]+);").unwrap());
+
+static REQUIRE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:require|include)(?:_once)?\s*\(?\s*(__DIR__\s*\.\s*)?['"]([^'"]+)['"]"#)
+        .unwrap()
+});
+
 impl LanguageParser for PhpParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -129,6 +137,52 @@ impl LanguageParser for PhpParser {
         symbols.sort_by_key(|s| s.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        // Top-level `use` only; trait `use` statements sit inside class bodies
+        for cap in USE_PATTERN.captures_iter(content) {
+            let clause = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            for name in expand_use_clause(clause) {
+                push_unique(&mut imports, name);
+            }
+        }
+
+        for cap in REQUIRE_PATTERN.captures_iter(content) {
+            if let Some(m) = cap.get(2) {
+                let path = if cap.get(1).is_some() {
+                    format!(".{}", m.as_str())
+                } else {
+                    m.as_str().to_string()
+                };
+                push_unique(&mut imports, path);
+            }
+        }
+
+        imports
+    }
+}
+
+/// Names imported by a `use` clause: `A\B as C, D` or a group `A\{B, C as D}`
+fn expand_use_clause(clause: &str) -> Vec<String> {
+    let (prefix, names) = match clause.split_once('{') {
+        Some((prefix, group)) => (prefix.trim(), group.trim_end_matches('}')),
+        None => ("", clause),
+    };
+
+    names
+        .split(',')
+        .filter_map(|name| {
+            let name = name.split(" as ").next()?.trim();
+            let name = name
+                .trim_start_matches("function ")
+                .trim_start_matches("const ");
+            let full = format!("{}{}", prefix, name);
+            let full = full.trim().trim_start_matches('\\');
+            (!full.is_empty()).then(|| full.to_string())
+        })
+        .collect()
 }
 
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
@@ -171,3 +225,34 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = r#"<?php
+namespace App\Http;
+
+use App\Models\User;
+use App\Services\{Billing, Mailer as M};
+use function App\helpers\format_money;
+require_once __DIR__ . '/bootstrap.php';
+
+class UserController {
+    use HasFactory;
+}
+"#;
+        assert_eq!(
+            PhpParser.parse_imports(content),
+            vec![
+                "App\\Models\\User",
+                "App\\Services\\Billing",
+                "App\\Services\\Mailer",
+                "App\\helpers\\format_money",
+                "./bootstrap.php",
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -11,11 +11,12 @@ static DEF_PATTERN: Lazy<Regex> =
 static CLASS_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^([ \t]*)class\s+(\w+)").unwrap());
 
-static IMPORT_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^[ \t]*import\s+([\w.]+)").unwrap());
+static IMPORT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*import\s+([\w.]+(?:[ \t]*,[ \t]*[\w.]+|[ \t]+as[ \t]+\w+)*)").unwrap()
+});
 
 static FROM_IMPORT_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^[ \t]*from\s+([\w.]+)\s+import").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*from\s+([\w.]+)\s+import\s+(\([^)]*\)|[^\n#]*)").unwrap());
 
 impl LanguageParser for PythonParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
@@ -80,21 +81,27 @@ impl LanguageParser for PythonParser {
 
     fn parse_imports(&self, content: &str) -> Vec<String> {
         let mut imports = Vec::new();
+        let mut push = |module: String| push_unique(&mut imports, module);
 
         for cap in IMPORT_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let module = m.as_str().split('.').next().unwrap_or("").to_string();
-                if !module.is_empty() && !imports.contains(&module) {
-                    imports.push(module);
-                }
+            for name in imported_names(&cap[1]) {
+                push(name.to_string());
             }
         }
 
+        // `from pkg import a, b` records `pkg.a` and `pkg.b`; the resolver
+        // keeps the longest prefix that names a module
         for cap in FROM_IMPORT_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let module = m.as_str().split('.').next().unwrap_or("").to_string();
-                if !module.is_empty() && !imports.contains(&module) {
-                    imports.push(module);
+            let module = &cap[1];
+            let names = imported_names(&cap[2]);
+            if names.is_empty() || names.contains(&"*") {
+                push(module.to_string());
+            }
+            for name in names.into_iter().filter(|n| *n != "*") {
+                if module.ends_with('.') {
+                    push(format!("{}{}", module, name));
+                } else {
+                    push(format!("{}.{}", module, name));
                 }
             }
         }
@@ -103,28 +110,175 @@ impl LanguageParser for PythonParser {
     }
 }
 
+/// Names in an import list, without parentheses, `as` aliases or comments
+fn imported_names(list: &str) -> Vec<&str> {
+    list.lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split(','))
+        .filter_map(|item| {
+            item.trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '\\')
+                .split_whitespace()
+                .next()
+        })
+        .collect()
+}
+
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
 
+/// Last line of the block opened at `start_line`. The block ends before the
+/// first line indented at or left of `base_indent`; lines inside multi-line
+/// strings, open brackets and backslash continuations never end it.
 fn find_indent_end(lines: &[&str], start_line: usize, base_indent: usize) -> usize {
+    let mut scan = LineScanner::default();
     let mut end_line = start_line;
 
-    for (i, line) in lines.iter().enumerate().skip(start_line) {
+    for (i, line) in lines.iter().enumerate().skip(start_line - 1) {
+        let continuation = scan.is_open();
         let trimmed = line.trim();
+        let blank = trimmed.is_empty() || trimmed.starts_with('#');
 
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+        if i + 1 > start_line && !continuation && !blank {
+            let current_indent = line.len() - line.trim_start().len();
+            if current_indent <= base_indent {
+                break;
+            }
         }
 
-        let current_indent = line.len() - line.trim_start().len();
+        scan.scan(line);
+        if continuation || !blank {
+            end_line = i + 1;
+        }
+    }
+
+    end_line
+}
+
+/// Tracks the state a Python line leaves open for the next one
+#[derive(Default)]
+struct LineScanner {
+    /// Closing delimiter of an unterminated triple-quoted string
+    triple: Option<&'static str>,
+    /// Unclosed `(`, `[` and `{`
+    depth: i32,
+    /// Line ended with a `\` continuation
+    backslash: bool,
+}
+
+impl LineScanner {
+    fn is_open(&self) -> bool {
+        self.triple.is_some() || self.depth > 0 || self.backslash
+    }
+
+    fn scan(&mut self, line: &str) {
+        self.backslash = false;
+        let mut rest = line;
+
+        loop {
+            if let Some(delim) = self.triple {
+                match rest.find(delim) {
+                    Some(pos) => {
+                        rest = &rest[pos + delim.len()..];
+                        self.triple = None;
+                    }
+                    None => return,
+                }
+            }
 
-        if current_indent <= base_indent {
-            break;
+            let Some(c) = rest.chars().next() else {
+                break;
+            };
+            if let Some(delim) = ["\"\"\"", "'''"].into_iter().find(|d| rest.starts_with(d)) {
+                self.triple = Some(delim);
+                rest = &rest[delim.len()..];
+                continue;
+            }
+
+            match c {
+                '#' => return,
+                '"' | '\'' => match string_end(&rest[1..], c) {
+                    Some(len) => {
+                        rest = &rest[1 + len..];
+                        continue;
+                    }
+                    None => return,
+                },
+                '(' | '[' | '{' => self.depth += 1,
+                ')' | ']' | '}' => self.depth = (self.depth - 1).max(0),
+                _ => {}
+            }
+            rest = &rest[c.len_utf8()..];
         }
 
-        end_line = i + 1;
+        self.backslash = line.trim_end().ends_with('\\');
     }
+}
 
-    end_line
+/// Length of a single-line string body up to and including its closing quote
+fn string_end(s: &str, quote: char) -> Option<usize> {
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_lines_skip_strings_and_continuations() {
+        let content = r#"class Foo:
+    def bar(
+        self,
+        x,
+):
+        """Docs.
+
+Flush-left docstring line.
+"""
+        y = (1 +
+2)
+        return x
+    # trailing comment
+
+def baz(): return 1
+"#;
+        let symbols = PythonParser.parse_symbols(content);
+        let ends: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.line_range.end))
+            .collect();
+
+        assert_eq!(ends, vec![("Foo", 12), ("bar", 12), ("baz", 15)]);
+    }
+
+    #[test]
+    fn test_imports_keep_full_module_paths() {
+        let content = "import os.path, numpy as np
+from . import utils
+from ..core.models import (
+    User,  # the user
+    Group as G,
+)
+from pkg.sub import *
+";
+        assert_eq!(
+            PythonParser.parse_imports(content),
+            vec![
+                "os.path",
+                "numpy",
+                ".utils",
+                "..core.models.User",
+                "..core.models.Group",
+                "pkg.sub",
+            ]
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -14,25 +14,31 @@ static MODULE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^module\s+([A
 
 // def method_name or def method_name(params)
 static METHOD_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*def\s+(\w+[?!=]?)(?:\s*\(|$|\s)").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*def\s+(\w+[?!=]?)(?:\s*\(|$|\s|;)").unwrap());
 
 // def self.method_name (class method)
 static CLASS_METHOD_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*def\s+self\.(\w+[?!=]?)(?:\s*\(|$|\s)").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*def\s+self\.(\w+[?!=]?)(?:\s*\(|$|\s|;)").unwrap());
 
 // attr_reader :name, :other or attr_accessor :name
 static ATTR_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*attr_(reader|writer|accessor)\s+(.+)").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*attr_(reader|writer|accessor)\s+(.+)").unwrap());
+
+// require 'json' or require_relative '../lib/helper'
+static REQUIRE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^[ \t]*(require|require_relative)[ \t]*\(?[ \t]*['"]([^'"]+)['"]"#).unwrap()
+});
 
 impl LanguageParser for RubyParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
+        let deltas = block_deltas(&lines);
 
         for cap in CLASS_PATTERN.captures_iter(content) {
             let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let end_line = find_ruby_end(&lines, line);
+            let end_line = find_ruby_end(&deltas, line);
 
             let mut sym = Symbol::new(
                 SymbolKind::Class,
@@ -48,7 +54,7 @@ impl LanguageParser for RubyParser {
         for cap in MODULE_PATTERN.captures_iter(content) {
             let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let end_line = find_ruby_end(&lines, line);
+            let end_line = find_ruby_end(&deltas, line);
 
             let mut sym = Symbol::new(
                 SymbolKind::Module,
@@ -64,7 +70,7 @@ impl LanguageParser for RubyParser {
         for cap in CLASS_METHOD_PATTERN.captures_iter(content) {
             let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let end_line = find_ruby_end(&lines, line);
+            let end_line = find_ruby_end(&deltas, line);
             let visibility = get_visibility_at_line(&lines, line);
 
             let mut sym = Symbol::new(
@@ -86,7 +92,7 @@ impl LanguageParser for RubyParser {
 
             let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let end_line = find_ruby_end(&lines, line);
+            let end_line = find_ruby_end(&deltas, line);
             let visibility = get_visibility_at_line(&lines, line);
 
             let mut sym = Symbol::new(SymbolKind::Method, name.to_string(), line, visibility);
@@ -121,40 +127,160 @@ impl LanguageParser for RubyParser {
         symbols.dedup_by(|a, b| a.name == b.name && a.line_range.start == b.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        for cap in REQUIRE_PATTERN.captures_iter(content) {
+            let path = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+            // Relative requires are written as `./` paths so the resolver
+            // can tell them from load-path requires
+            let path = if &cap[1] == "require_relative" && !path.starts_with('.') {
+                format!("./{}", path)
+            } else {
+                path.to_string()
+            };
+            push_unique(&mut imports, path);
+        }
+
+        imports
+    }
 }
 
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
 
-fn find_ruby_end(lines: &[&str], start_line: usize) -> usize {
+/// Last line of the block opened at `start_line`, matching `end` keywords
+/// against the openers counted by [`block_deltas`]
+fn find_ruby_end(deltas: &[i32], start_line: usize) -> usize {
     let mut depth = 0;
-    for (i, line) in lines.iter().enumerate().skip(start_line - 1) {
-        let trimmed = line.trim();
-
-        if trimmed.starts_with("class ")
-            || trimmed.starts_with("module ")
-            || trimmed.starts_with("def ")
-            || trimmed.starts_with("do")
-            || trimmed.starts_with("if ")
-            || trimmed.starts_with("unless ")
-            || trimmed.starts_with("case ")
-            || trimmed.starts_with("begin")
-            || trimmed == "begin"
-        {
-            depth += 1;
-        }
-
-        if trimmed == "end" || trimmed.starts_with("end ") || trimmed.starts_with("end#") {
-            depth -= 1;
-            if depth == 0 {
-                return i + 1;
-            }
+    for (i, delta) in deltas.iter().enumerate().skip(start_line - 1) {
+        depth += delta;
+        if depth <= 0 {
+            return i + 1;
         }
     }
     start_line
 }
 
+/// Net change in block depth on each line: openers (`class`, `def`, `do`,
+/// statement-leading `if`, ...) minus `end`s. Strings, heredoc bodies,
+/// comments and `=begin`/`=end` blocks are ignored.
+fn block_deltas(lines: &[&str]) -> Vec<i32> {
+    let mut deltas = vec![0; lines.len()];
+    let mut heredocs: Vec<(String, bool)> = Vec::new();
+    let mut in_doc_comment = false;
+    let mut open_quote = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some((tag, indented)) = heredocs.first() {
+            let body = if *indented { line.trim() } else { *line };
+            if body == tag {
+                heredocs.remove(0);
+            }
+            continue;
+        }
+        if in_doc_comment {
+            in_doc_comment = !line.starts_with("=end");
+            continue;
+        }
+        if line.starts_with("=begin") {
+            in_doc_comment = true;
+            continue;
+        }
+        if *line == "__END__" {
+            break;
+        }
+
+        let code = strip_strings(line, &mut open_quote);
+        for cap in HEREDOC_PATTERN.captures_iter(&code) {
+            let indented = cap.get(1).is_some();
+            let tag = cap.get(2).or(cap.get(3)).unwrap().as_str().to_string();
+            heredocs.push((tag, indented));
+        }
+        deltas[i] = line_delta(&code);
+    }
+
+    deltas
+}
+
+static HEREDOC_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"<<([~-])?(?:([A-Za-z_]\w*)|['"](\w+)['"])"#).unwrap());
+
+static WORD_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\w.:@$]*\w+[?!:]?").unwrap());
+
+static ENDLESS_DEF_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^def\s+[\w.]+[?!]?\s*(?:\([^)]*\))?\s*=[^=~]").unwrap());
+
+/// Openers that only start a block at the beginning of a statement; after an
+/// expression they are modifiers (`return if done`)
+const STATEMENT_OPENERS: &[&str] = &["if", "unless", "while", "until"];
+
+const OPENERS: &[&str] = &["class", "module", "def", "case", "begin", "for", "do"];
+
+fn line_delta(code: &str) -> i32 {
+    let mut delta = 0;
+    let mut loop_header = false;
+    let trimmed = code.trim_start();
+
+    for m in WORD_PATTERN.find_iter(code) {
+        let word = m.as_str();
+        let before = code[..m.start()].trim_end();
+        let statement_start = before.is_empty()
+            || before.ends_with([';', '=', '(', '[', '{', ',', '|', '&'])
+            || before.ends_with("return");
+
+        match word {
+            "end" => delta -= 1,
+            w if w.starts_with("end.") => delta -= 1,
+            "def" if ENDLESS_DEF_PATTERN.is_match(trimmed) => {}
+            // `while x do` opens a single block
+            "do" if loop_header => loop_header = false,
+            w if OPENERS.contains(&w) && (w == "do" || statement_start) => delta += 1,
+            w if STATEMENT_OPENERS.contains(&w) && statement_start => {
+                delta += 1;
+                loop_header = matches!(w, "while" | "until");
+            }
+            _ => {}
+        }
+        if word == "for" && statement_start {
+            loop_header = true;
+        }
+    }
+
+    delta
+}
+
+/// Replace string contents and drop comments so keywords inside them don't
+/// count. `open_quote` carries a string that continues onto the next line.
+fn strip_strings(line: &str, open_quote: &mut Option<char>) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = *open_quote {
+            if c == '\\' {
+                chars.next();
+            } else if c == q {
+                *open_quote = None;
+                out.push(c);
+            }
+            continue;
+        }
+        match c {
+            '#' => break,
+            '"' | '\'' | '`' => {
+                *open_quote = Some(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
 fn get_visibility_at_line(lines: &[&str], target_line: usize) -> Visibility {
     for i in (0..target_line.saturating_sub(1)).rev() {
         let trimmed = lines[i].trim();
@@ -173,3 +299,51 @@ fn get_visibility_at_line(lines: &[&str], target_line: usize) -> Visibility {
     }
     Visibility::Public
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_lines_match_blocks() {
+        let content = r#"class Report
+  def render(rows)
+    sql = <<~SQL
+      SELECT * FROM t WHERE x = 'end'
+    SQL
+    rows.each do |row|
+      next if row.nil?
+      puts "if #{row} end" # end
+    end
+    while busy? do
+      sleep 1
+    end
+    total = if rows.empty? then 0 else rows.size end
+  end
+
+  def empty?; rows.empty?; end
+  def size = rows.size
+end
+"#;
+        let symbols = RubyParser.parse_symbols(content);
+        let ends: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.line_range.end))
+            .collect();
+
+        assert_eq!(
+            ends,
+            vec![("Report", 18), ("render", 14), ("empty?", 16), ("size", 17)]
+        );
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let content =
+            "require 'json'\nrequire_relative 'support/helper'\nrequire_relative '../lib/app'\n";
+        assert_eq!(
+            RubyParser.parse_imports(content),
+            vec!["json", "./support/helper", "../lib/app"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{push_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -23,11 +23,17 @@ static CONST_PATTERN: Lazy<Regex> =
 static TYPE_ALIAS_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^[ \t]*(pub(?:\([^)]+\))?\s+)?type\s+(\w+)").unwrap());
 
+static IMPL_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:unsafe\s+)?impl\b([^{;]*)\{").unwrap());
+
 static USE_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub\s+)?use\s+(?:crate::)?(\w+)").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]+\))?\s+)?use\s+([^;]+);").unwrap());
+
+static INLINE_MOD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]+\))?\s+)?mod\s+(\w+)\s*\{").unwrap());
 
-static MOD_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub\s+)?mod\s+(\w+)\s*;").unwrap());
+static EXTERN_CRATE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub\s+)?extern\s+crate\s+(\w+)").unwrap());
 
 impl LanguageParser for RustParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
@@ -160,6 +166,28 @@ impl LanguageParser for RustParser {
             ));
         }
 
+        // Functions inside `impl` blocks are methods of the implementing type
+        for cap in IMPL_PATTERN.captures_iter(content) {
+            let Some(target) = cap.get(1).and_then(|m| impl_target(m.as_str())) else {
+                continue;
+            };
+            let whole = cap.get(0).unwrap();
+            let start = line_number_at_offset(content, whole.start());
+            let Some(end) = find_brace_end(content, whole.end() - 1) else {
+                continue;
+            };
+
+            for sym in symbols.iter_mut() {
+                if sym.kind == SymbolKind::Function
+                    && sym.line_range.start > start
+                    && sym.line_range.start <= end
+                {
+                    sym.kind = SymbolKind::Method;
+                    sym.parent = Some(target.clone());
+                }
+            }
+        }
+
         symbols.sort_by_key(|s| s.line_range.start);
         symbols
     }
@@ -167,28 +195,171 @@ impl LanguageParser for RustParser {
     fn parse_imports(&self, content: &str) -> Vec<String> {
         let mut imports = Vec::new();
 
+        // Inline `mod name { ... }` blocks, so paths inside them can be
+        // rewritten relative to the file's own module
+        let inline_mods: Vec<(&str, usize, usize)> = INLINE_MOD_PATTERN
+            .captures_iter(content)
+            .filter_map(|cap| {
+                let start = cap.get(0).unwrap().start();
+                let end_line = find_brace_end(content, start)?;
+                Some((
+                    cap.get(1)?.as_str(),
+                    line_number_at_offset(content, start),
+                    end_line,
+                ))
+            })
+            .collect();
+
         for cap in USE_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let module = m.as_str().to_string();
-                if !imports.contains(&module) {
-                    imports.push(module);
-                }
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+            let scope: Vec<&str> = inline_mods
+                .iter()
+                .filter(|(_, start, end)| *start < line && line <= *end)
+                .map(|(name, _, _)| *name)
+                .collect();
+            let tree: String = cap[1]
+                .lines()
+                .map(|line| line.split("//").next().unwrap_or(""))
+                .collect();
+            for path in expand_use_tree("", &tree) {
+                let path = scope_use_path(&path, &scope);
+                push_unique(&mut imports, path);
             }
         }
 
-        for cap in MOD_PATTERN.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                let module = m.as_str().to_string();
-                if !imports.contains(&module) {
-                    imports.push(module);
-                }
-            }
+        for cap in EXTERN_CRATE_PATTERN.captures_iter(content) {
+            push_unique(&mut imports, cap[1].to_string());
         }
 
         imports
     }
 }
 
+/// Flatten a `use` tree into full paths: `a::{b, c::{self, d as e}, f::*}`
+/// gives `a::b`, `a::c`, `a::c::d` and `a::f`
+fn expand_use_tree(prefix: &str, tree: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+
+    for item in split_top_level(tree) {
+        let item = item.trim().trim_start_matches("::");
+        if item.is_empty() {
+            continue;
+        }
+
+        if let Some(open) = item.find('{') {
+            let head = item[..open].trim().trim_end_matches("::");
+            let inner = item[open + 1..].trim_end().trim_end_matches('}');
+            paths.extend(expand_use_tree(&join_use_path(prefix, head), inner));
+            continue;
+        }
+
+        let item = item.split(" as ").next().unwrap_or(item).trim();
+        let item = item.trim_end_matches('*').trim_end_matches("::");
+        let path = match item {
+            "self" | "" => prefix.to_string(),
+            item => join_use_path(prefix, item),
+        };
+        if !path.is_empty() {
+            paths.push(path);
+        }
+    }
+
+    paths
+}
+
+/// Rewrite a `self::`/`super::` path written inside the inline modules
+/// `scope` (outermost first) so it is relative to the file's module
+fn scope_use_path(path: &str, scope: &[&str]) -> String {
+    if scope.is_empty() {
+        return path.to_string();
+    }
+
+    let segments: Vec<&str> = path.split("::").collect();
+    let supers = segments.iter().take_while(|s| **s == "super").count();
+    let (kept, rest) = if segments[0] == "self" {
+        (scope.len(), &segments[1..])
+    } else if supers > 0 {
+        (scope.len().saturating_sub(supers), &segments[supers..])
+    } else {
+        return path.to_string();
+    };
+
+    let mut scoped: Vec<&str> = Vec::new();
+    if supers > scope.len() {
+        scoped.extend(std::iter::repeat_n("super", supers - scope.len()));
+    } else {
+        scoped.push("self");
+        scoped.extend(&scope[..kept]);
+    }
+    scoped.extend(rest);
+    scoped.join("::")
+}
+
+fn join_use_path(prefix: &str, path: &str) -> String {
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}::{}", prefix, path),
+    }
+}
+
+/// Split on commas outside braces
+fn split_top_level(tree: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in tree.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&tree[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&tree[start..]);
+    items
+}
+
+/// Type name an `impl` header applies to: `<T> Trait for Foo<T>` gives `Foo`
+fn impl_target(header: &str) -> Option<String> {
+    let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    let header = header.split(" where ").next().unwrap_or(&header);
+    let target = match header.rsplit_once(" for ") {
+        Some((_, ty)) => ty,
+        None => skip_generics(header.trim_start()),
+    };
+    let target = target
+        .trim()
+        .trim_start_matches('&')
+        .trim_start_matches("mut ");
+    let name = target.split('<').next()?.rsplit("::").next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Drop a leading `<...>` generic parameter list
+fn skip_generics(s: &str) -> &str {
+    if !s.starts_with('<') {
+        return s;
+    }
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[i + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    s
+}
+
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
@@ -218,3 +389,42 @@ fn find_brace_end(content: &str, start_offset: usize) -> Option<usize> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imports_expand_use_trees() {
+        let content = r#"use std::collections::{HashMap, hash_map::{self, Entry as E}};
+pub(crate) use crate::analyze::graph::FileGraph;
+use super::{
+    lang::*, // glob
+    types,
+};
+extern crate serde;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::parent;
+}
+"#;
+        let imports = RustParser.parse_imports(content);
+
+        assert_eq!(
+            imports,
+            vec![
+                "std::collections::HashMap",
+                "std::collections::hash_map",
+                "std::collections::hash_map::Entry",
+                "crate::analyze::graph::FileGraph",
+                "super::lang",
+                "super::types",
+                "self",
+                "super::parent",
+                "serde",
+            ]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Declaration signatures shared by every parser backend.
+//!
+//! Reads from each symbol's start line up to the body opener, so parameters,
+//! return types, generics and where-clauses split over several lines are kept.
+//! Attributes and decorators directly above the declaration are prepended. The
+//! result is normalised to a single line; renderers truncate it as needed.
+
+use crate::analyze::lang::doc::is_attribute;
+use crate::types::{Language, Symbol, SymbolKind};
+
+/// Give up on declarations that don't reach a body opener within this many lines
+const MAX_LINES: usize = 40;
+
+/// Replace the signature of every declaration with its full header
+pub fn attach_signatures(language: Language, content: &str, symbols: &mut [Symbol]) {
+    let lines: Vec<&str> = content.lines().collect();
+
+    for sym in symbols.iter_mut() {
+        if !has_signature(sym.kind) {
+            continue;
+        }
+        let idx = sym.line_range.start.saturating_sub(1);
+        if idx >= lines.len() {
+            continue;
+        }
+        if let Some(header) = declaration(language, &lines, idx) {
+            let attrs = attributes(&lines, idx);
+            let signature = if attrs.is_empty() {
+                header
+            } else {
+                format!("{} {}", attrs.join(" "), header)
+            };
+            sym.signature = Some(signature);
+        }
+    }
+}
+
+fn has_signature(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function
+            | SymbolKind::Method
+            | SymbolKind::Class
+            | SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::Interface
+    )
+}
+
+/// Single-line attributes and decorators directly above the line at `idx`
+fn attributes<'a>(lines: &[&'a str], idx: usize) -> Vec<&'a str> {
+    let mut start = idx;
+    while start > 0 && is_attribute(lines[start - 1].trim()) {
+        start -= 1;
+    }
+    lines[start..idx].iter().map(|l| l.trim()).collect()
+}
+
+/// Declaration text from the line at `idx` up to (not including) its body
+fn declaration(language: Language, lines: &[&str], idx: usize) -> Option<String> {
+    let line_comment = match language {
+        Language::Python | Language::Ruby => "#",
+        _ => "//",
+    };
+    // Rust uses `'` for lifetimes and Swift has no character literals
+    let single_quote_strings = !matches!(language, Language::Rust | Language::Swift);
+    // `<...>` may hold `{` (TS object types) or bounds; C and C++ use `<` as an operator
+    let generics = matches!(
+        language,
+        Language::TypeScript
+            | Language::Java
+            | Language::Kotlin
+            | Language::CSharp
+            | Language::Rust
+            | Language::Swift
+    );
+
+    let mut out = String::new();
+    let mut depth = 0i32;
+    let mut angle = 0i32;
+    let mut in_block_comment = false;
+
+    for (n, line) in lines.iter().enumerate().skip(idx).take(MAX_LINES) {
+        let mut quote: Option<char> = None;
+        let mut chars = line.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            let rest = &line[i..];
+
+            if in_block_comment {
+                if rest.starts_with("*/") {
+                    in_block_comment = false;
+                    chars.next();
+                }
+                continue;
+            }
+
+            if let Some(q) = quote {
+                out.push(c);
+                if c == '\\' {
+                    if let Some((_, escaped)) = chars.next() {
+                        out.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+                continue;
+            }
+
+            if rest.starts_with(line_comment) {
+                break;
+            }
+            if line_comment == "//" && rest.starts_with("/*") {
+                in_block_comment = true;
+                chars.next();
+                continue;
+            }
+
+            let nested = depth > 0 || angle > 0;
+            match c {
+                '"' => quote = Some('"'),
+                '\'' if single_quote_strings => quote = Some('\''),
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                // A TS return type such as `(): { a: string } {` is an object type, not the body
+                '{' if nested
+                    || (language == Language::TypeScript && out.trim_end().ends_with(':')) =>
+                {
+                    depth += 1
+                }
+                '}' if nested => depth -= 1,
+                '<' if generics && !out.trim_end().ends_with("operator") => angle += 1,
+                // `->` and `=>` close nothing
+                '>' if angle > 0 && !out.ends_with('-') && !out.ends_with('=') => angle -= 1,
+                _ if nested => {}
+                '{' | ';' if language != Language::Python => return normalize(&out),
+                ':' if language == Language::Python => return normalize(&out),
+                // Kotlin expression body: `fun f() = 1`
+                '=' if language == Language::Kotlin && !rest.starts_with("==") => {
+                    return normalize(&out)
+                }
+                '=' if rest.starts_with("=>") => {
+                    if matches!(language, Language::JavaScript | Language::TypeScript) {
+                        out.push_str("=>");
+                    }
+                    return normalize(&out);
+                }
+                _ => {}
+            }
+            out.push(c);
+        }
+
+        // Ruby has no body opener; the header ends with its line
+        if language == Language::Ruby && depth <= 0 {
+            return normalize(&out);
+        }
+        // Kotlin classes may have no body; the header ends with its line
+        // unless a supertype list, where-clause or body follows
+        if language == Language::Kotlin && depth <= 0 && angle <= 0 {
+            let next = lines.get(n + 1).map(|l| l.trim_start()).unwrap_or("");
+            let open = out.trim_end().ends_with([',', ':']);
+            if !open && !next.starts_with(['{', ':']) && !next.starts_with("where") {
+                return normalize(&out);
+            }
+        }
+        out.push(' ');
+    }
+
+    None
+}
+
+/// Collapse whitespace and tidy brackets left over from line breaks
+fn normalize(text: &str) -> Option<String> {
+    let mut sig = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    for (from, to) in [
+        ("( ", "("),
+        ("[ ", "["),
+        (" )", ")"),
+        (" ]", "]"),
+        (",)", ")"),
+    ] {
+        sig = sig.replace(from, to);
+    }
+    let sig = sig.trim_end_matches(',').trim().to_string();
+    (!sig.is_empty()).then_some(sig)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Visibility;
+
+    fn signature_at(language: Language, content: &str, line: usize) -> Option<String> {
+        let mut symbols = vec![Symbol::new(
+            SymbolKind::Function,
+            "f".to_string(),
+            line,
+            Visibility::Public,
+        )];
+        attach_signatures(language, content, &mut symbols);
+        symbols.remove(0).signature
+    }
+
+    #[test]
+    fn test_multi_line_signatures() {
+        let rust = "/// Docs\n#[inline]\npub fn foo<'a, T>(\n    a: &'a str, // input\n    b: T,\n) -> Result<T>\nwhere\n    T: Clone,\n{\n}\n";
+        assert_eq!(
+            signature_at(Language::Rust, rust, 3).as_deref(),
+            Some("#[inline] pub fn foo<'a, T>(a: &'a str, b: T) -> Result<T> where T: Clone")
+        );
+
+        let python = "@app.route(\"/\")\ndef index(\n    a: int = 1,\n    b: str = \":\",\n) -> Dict[str, int]:\n    pass\n";
+        assert_eq!(
+            signature_at(Language::Python, python, 2).as_deref(),
+            Some("@app.route(\"/\") def index(a: int = 1, b: str = \":\") -> Dict[str, int]")
+        );
+
+        let ts = "export const App = async (\n  props: Props,\n): Promise<void> => {\n};\n";
+        assert_eq!(
+            signature_at(Language::TypeScript, ts, 1).as_deref(),
+            Some("export const App = async (props: Props): Promise<void> =>")
+        );
+    }
+
+    #[test]
+    fn test_braces_inside_generics_and_return_types() {
+        let ts = "function f<T extends { id: string }>(x: T) {\n}\n";
+        assert_eq!(
+            signature_at(Language::TypeScript, ts, 1).as_deref(),
+            Some("function f<T extends { id: string }>(x: T)")
+        );
+
+        let ts = "function f(): { a: string } {\n}\n";
+        assert_eq!(
+            signature_at(Language::TypeScript, ts, 1).as_deref(),
+            Some("function f(): { a: string }")
+        );
+
+        let ts = "function f(cb: Array<() => void>): Promise<{ a: string }> {\n}\n";
+        assert_eq!(
+            signature_at(Language::TypeScript, ts, 1).as_deref(),
+            Some("function f(cb: Array<() => void>): Promise<{ a: string }>")
+        );
+
+        let rust = "fn f<F: Fn() -> u8>(f: F) -> Vec<u8> {\n}\n";
+        assert_eq!(
+            signature_at(Language::Rust, rust, 1).as_deref(),
+            Some("fn f<F: Fn() -> u8>(f: F) -> Vec<u8>")
+        );
+    }
+
+    #[test]
+    fn test_kotlin_declarations() {
+        let kotlin = "data class Invoice(\n    val id: String,\n)\n\nenum class Status { OPEN }\n";
+        assert_eq!(
+            signature_at(Language::Kotlin, kotlin, 1).as_deref(),
+            Some("data class Invoice(val id: String)")
+        );
+
+        let kotlin = "fun <T> List<T>.total(): Int = size\n";
+        assert_eq!(
+            signature_at(Language::Kotlin, kotlin, 1).as_deref(),
+            Some("fun <T> List<T>.total(): Int")
+        );
+
+        let kotlin = "class Repo(db: Db)\n    : Base(db) {\n}\n";
+        assert_eq!(
+            signature_at(Language::Kotlin, kotlin, 1).as_deref(),
+            Some("class Repo(db: Db) : Base(db)")
+        );
+    }
+
+    #[test]
+    fn test_bodiless_declarations() {
+        let c = "int add(int a,\n        int b);\n";
+        assert_eq!(
+            signature_at(Language::C, c, 1).as_deref(),
+            Some("int add(int a, int b)")
+        );
+
+        let ruby = "def greet(name,\n          greeting: \"hi\")\n  puts name\nend\n";
+        assert_eq!(
+            signature_at(Language::Ruby, ruby, 1).as_deref(),
+            Some("def greet(name, greeting: \"hi\")")
+        );
+    }
+}
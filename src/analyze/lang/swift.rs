@@ -1,4 +1,4 @@
-use crate::analyze::lang::LanguageParser;
+use crate::analyze::lang::{collect_unique, LanguageParser};
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -53,6 +53,14 @@ static INIT_PATTERN: Lazy<Regex> = Lazy::new(|| {
         .unwrap()
 });
 
+// import Foundation, @testable import App, import struct Foo.Bar
+static IMPORT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:@\w+[ \t]+)*import[ \t]+(?:(?:typealias|struct|class|enum|protocol|let|var|func)[ \t]+)?(\w+)",
+    )
+    .unwrap()
+});
+
 impl LanguageParser for SwiftParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -190,6 +198,10 @@ impl LanguageParser for SwiftParser {
         symbols.dedup_by(|a, b| a.name == b.name && a.line_range.start == b.line_range.start);
         symbols
     }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        collect_unique(&IMPORT_PATTERN, content)
+    }
 }
 
 fn parse_visibility(modifier: Option<&str>) -> Visibility {
@@ -242,3 +254,17 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "import Foundation\n@testable import MyApp\nimport struct Models.User\nimport Foundation\n";
+        assert_eq!(
+            SwiftParser.parse_imports(content),
+            vec!["Foundation", "MyApp", "Models"]
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Tree-sitter parsing backend.
+//!
+//! Enabled with the `tree-sitter` cargo feature. Symbols come from a real syntax
+//! tree, so names inside strings and comments are ignored, methods nested in
+//! impl blocks and classes are found, and line ranges are the exact extent of
+//! each declaration. Imports are still extracted by the regex parser each
+//! backend wraps.
+
+mod rules;
+
+use crate::analyze::lang::LanguageParser;
+use crate::types::{Language, Symbol, SymbolKind, Visibility};
+use tree_sitter::{Node, Parser};
+
+use rules::Rules;
+
+/// A declaration recognised by a language's rules
+struct Found<'tree> {
+    kind: SymbolKind,
+    name: String,
+    visibility: Visibility,
+    node: Node<'tree>,
+    /// Owning type when it isn't the enclosing node, as for Go receivers
+    parent: Option<String>,
+}
+
+pub struct TreeSitterParser {
+    /// Grammars to try in order; the first that parses without errors wins
+    grammars: Vec<tree_sitter::Language>,
+    rules: &'static Rules,
+    fallback: Box<dyn LanguageParser>,
+}
+
+impl TreeSitterParser {
+    /// Wrap a regex parser with the tree-sitter grammar for `language`
+    pub fn new(language: Language, fallback: Box<dyn LanguageParser>) -> Option<Self> {
+        let (grammars, rules): (Vec<tree_sitter::Language>, &'static Rules) = match language {
+            Language::Rust => (vec![tree_sitter_rust::LANGUAGE.into()], &rules::RUST),
+            Language::Python => (vec![tree_sitter_python::LANGUAGE.into()], &rules::PYTHON),
+            Language::JavaScript => (
+                vec![tree_sitter_javascript::LANGUAGE.into()],
+                &rules::JAVASCRIPT,
+            ),
+            Language::TypeScript => (
+                vec![
+                    tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+                    tree_sitter_typescript::LANGUAGE_TSX.into(),
+                ],
+                &rules::JAVASCRIPT,
+            ),
+            Language::Go => (vec![tree_sitter_go::LANGUAGE.into()], &rules::GO),
+            Language::Php => (vec![tree_sitter_php::LANGUAGE_PHP.into()], &rules::PHP),
+            Language::Java => (vec![tree_sitter_java::LANGUAGE.into()], &rules::JAVA),
+            Language::CSharp => (vec![tree_sitter_c_sharp::LANGUAGE.into()], &rules::CSHARP),
+            Language::C => (vec![tree_sitter_c::LANGUAGE.into()], &rules::C),
+            Language::Cpp => (vec![tree_sitter_cpp::LANGUAGE.into()], &rules::CPP),
+            Language::Ruby => (vec![tree_sitter_ruby::LANGUAGE.into()], &rules::RUBY),
+            Language::Dart => (vec![tree_sitter_dart::LANGUAGE.into()], &rules::DART),
+            Language::Swift => (vec![tree_sitter_swift::LANGUAGE.into()], &rules::SWIFT),
+            Language::Kotlin | Language::Unknown => return None,
+        };
+
+        Some(Self {
+            grammars,
+            rules,
+            fallback,
+        })
+    }
+
+    fn parse_tree(&self, content: &str) -> Option<tree_sitter::Tree> {
+        let mut parser = Parser::new();
+        let mut first = None;
+
+        for grammar in &self.grammars {
+            if parser.set_language(grammar).is_err() {
+                continue;
+            }
+            let Some(tree) = parser.parse(content, None) else {
+                continue;
+            };
+            if !tree.root_node().has_error() {
+                return Some(tree);
+            }
+            first.get_or_insert(tree);
+        }
+
+        first
+    }
+}
+
+impl LanguageParser for TreeSitterParser {
+    fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
+        let Some(tree) = self.parse_tree(content) else {
+            return self.fallback.parse_symbols(content);
+        };
+
+        let mut symbols = Vec::new();
+        collect_symbols(
+            tree.root_node(),
+            content,
+            self.rules,
+            false,
+            None,
+            &mut symbols,
+        );
+
+        symbols.sort_by_key(|s| s.line_range.start);
+        symbols
+    }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        self.fallback.parse_imports(content)
+    }
+}
+
+/// Walk the tree collecting declarations. Functions found inside one of the
+/// language's container nodes (impl blocks, classes) become methods, and every
+/// declaration records the nearest enclosing type or module as its parent.
+/// Function bodies are not descended into, so locals and closures stay out of
+/// outlines.
+fn collect_symbols(
+    node: Node,
+    src: &str,
+    rules: &Rules,
+    in_container: bool,
+    parent: Option<&str>,
+    symbols: &mut Vec<Symbol>,
+) {
+    let mut found = Vec::new();
+    let mut cursor = node.walk();
+
+    for child in node.named_children(&mut cursor) {
+        found.clear();
+        (rules.classify)(child, src, &mut found);
+
+        let mut is_function = false;
+        let mut child_parent = impl_target(child, src);
+        for mut f in found.drain(..) {
+            let kind = match f.kind {
+                SymbolKind::Function if in_container => SymbolKind::Method,
+                kind => kind,
+            };
+            is_function |= matches!(kind, SymbolKind::Function | SymbolKind::Method);
+            if f.parent.is_none() {
+                f.parent = parent.map(String::from);
+            }
+            let sym = to_symbol(f, kind);
+            if sym.is_container() {
+                child_parent = Some(sym.name.clone());
+            }
+            symbols.push(sym);
+        }
+
+        if is_function {
+            continue;
+        }
+
+        let child_in_container = in_container || rules.containers.contains(&child.kind());
+        let child_parent = child_parent.as_deref().or(parent);
+        collect_symbols(child, src, rules, child_in_container, child_parent, symbols);
+    }
+}
+
+/// Type a Rust `impl` block applies to, without generics or path
+fn impl_target(node: Node, src: &str) -> Option<String> {
+    if node.kind() != "impl_item" {
+        return None;
+    }
+    let ty = &src[node.child_by_field_name("type")?.byte_range()];
+    let ty = ty.split('<').next()?.rsplit("::").next()?.trim();
+    Some(ty.to_string())
+}
+
+fn to_symbol(found: Found, kind: SymbolKind) -> Symbol {
+    let start = found.node.start_position().row + 1;
+    let end_pos = found.node.end_position();
+    // A node ending at column 0 stops at the previous line's newline
+    let end = if end_pos.column == 0 && end_pos.row + 1 > start {
+        end_pos.row
+    } else {
+        end_pos.row + 1
+    };
+
+    let mut sym =
+        Symbol::new(kind, found.name, start, found.visibility).with_line_range(start, end);
+    if let Some(parent) = found.parent {
+        sym = sym.with_parent(parent);
+    }
+    sym
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::lang::{get_parser, get_regex_parser};
+
+    fn symbols(language: Language, content: &str) -> Vec<Symbol> {
+        get_parser(language).unwrap().parse_symbols(content)
+    }
+
+    fn kinds_and_ranges(symbols: &[Symbol]) -> Vec<(&str, SymbolKind, usize, usize)> {
+        symbols
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.kind,
+                    s.line_range.start,
+                    s.line_range.end,
+                )
+            })
+            .collect()
+    }
+
+    /// Tree-sitter symbols for `content`, checked against the regex parser's
+    /// names and line ranges
+    fn symbols_matching_regex(language: Language, content: &str) -> Vec<Symbol> {
+        let syms = symbols(language, content);
+        let regex = get_regex_parser(language).unwrap().parse_symbols(content);
+        let ranges = |symbols: &[Symbol]| -> Vec<(String, usize, usize)> {
+            symbols
+                .iter()
+                .map(|s| (s.name.clone(), s.line_range.start, s.line_range.end))
+                .collect()
+        };
+        assert_eq!(ranges(&syms), ranges(&regex), "{:?}", language);
+        syms
+    }
+
+    #[test]
+    fn test_rust_methods_and_exact_ranges() {
+        let content = r#"pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Self {
+        let s = "fn not_a_fn() { {";
+        Foo
+    }
+}
+
+// fn commented_out() {}
+fn helper() {}
+"#;
+        let syms = symbols(Language::Rust, content);
+        let names: Vec<_> = syms.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Foo", "new", "helper"]);
+
+        assert_eq!(syms[1].kind, SymbolKind::Method);
+        assert_eq!(syms[1].visibility, Visibility::Public);
+        assert_eq!((syms[1].line_range.start, syms[1].line_range.end), (4, 7));
+        assert_eq!(syms[2].kind, SymbolKind::Function);
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_python_class_methods() {
+        let content =
+            "class Foo:\n    def bar(self):\n        pass\n\n    def _baz(self):\n        pass\n";
+        let syms = symbols(Language::Python, content);
+
+        assert_eq!(syms.len(), 3);
+        assert_eq!(syms[0].kind, SymbolKind::Class);
+        assert_eq!(syms[0].line_range.end, 6);
+        assert_eq!(syms[1].kind, SymbolKind::Method);
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_typescript_exports() {
+        let content = "export interface Props { a: string }\nexport const App = () => {\n  return 1;\n};\nfunction local() {}\n";
+        let syms = symbols(Language::TypeScript, content);
+        let names: Vec<_> = syms
+            .iter()
+            .map(|s| (s.name.as_str(), s.visibility))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("Props", Visibility::Public),
+                ("App", Visibility::Public),
+                ("local", Visibility::Private)
+            ]
+        );
+        assert_eq!(syms[1].line_range.end, 4);
+    }
+
+    #[test]
+    fn test_go_methods_and_structs() {
+        let content = "package main\n\ntype Server struct {\n\taddr string\n}\n\nfunc (s *Server) Start() error {\n\treturn nil\n}\n\nfunc helper() {}\n";
+        let syms = symbols_matching_regex(Language::Go, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("Server", SymbolKind::Struct, 3, 5),
+                ("Start", SymbolKind::Method, 7, 9),
+                ("helper", SymbolKind::Function, 11, 11),
+            ]
+        );
+        assert_eq!(syms[1].parent.as_deref(), Some("Server"));
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_php_class_methods() {
+        let content = "<?php\nnamespace App;\nclass User\n{\n    public function name(): string\n    {\n        return $this->name;\n    }\n    private function secret() {}\n}\nfunction helper() {}\n";
+        let syms = symbols_matching_regex(Language::Php, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("User", SymbolKind::Class, 3, 10),
+                ("name", SymbolKind::Method, 5, 8),
+                ("secret", SymbolKind::Method, 9, 9),
+                ("helper", SymbolKind::Function, 11, 11),
+            ]
+        );
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_java_class_methods() {
+        let content = "package app;\npublic class User {\n    public String name() {\n        return name;\n    }\n    private void reset() {\n    }\n}\n";
+        let syms = symbols_matching_regex(Language::Java, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("User", SymbolKind::Class, 2, 8),
+                ("name", SymbolKind::Method, 3, 5),
+                ("reset", SymbolKind::Method, 6, 7),
+            ]
+        );
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_csharp_namespace_class_methods() {
+        let content = "namespace App\n{\n    public class User\n    {\n        public string Name()\n        {\n            return name;\n        }\n    }\n}\n";
+        let syms = symbols_matching_regex(Language::CSharp, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("App", SymbolKind::Module, 1, 10),
+                ("User", SymbolKind::Class, 3, 9),
+                ("Name", SymbolKind::Method, 5, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_c_structs_and_static_functions() {
+        let content = "#include <stdio.h>\n\nstruct point {\n    int x;\n};\n\nint add(int a, int b)\n{\n    return a + b;\n}\n\nstatic void helper(void) {}\n";
+        let syms = symbols_matching_regex(Language::C, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("point", SymbolKind::Struct, 3, 5),
+                ("add", SymbolKind::Function, 7, 10),
+                ("helper", SymbolKind::Function, 12, 12),
+            ]
+        );
+        assert_eq!(syms[1].visibility, Visibility::Public);
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_cpp_out_of_line_methods() {
+        let content = "namespace geo {\n\nclass Shape {\npublic:\n    virtual double area() const;\n};\n\ndouble Shape::area() const {\n    return 0;\n}\n\n}\n";
+        let syms = symbols_matching_regex(Language::Cpp, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("geo", SymbolKind::Module, 1, 12),
+                ("Shape", SymbolKind::Class, 3, 6),
+                ("area", SymbolKind::Method, 5, 5),
+                ("Shape::area", SymbolKind::Method, 8, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ruby_modules_classes_methods() {
+        let content = "module Billing\nend\n\nclass Invoice\n  def total\n    items.sum\n  end\n\n  def self.build\n    new\n  end\nend\n";
+        let syms = symbols_matching_regex(Language::Ruby, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("Billing", SymbolKind::Module, 1, 2),
+                ("Invoice", SymbolKind::Class, 4, 12),
+                ("total", SymbolKind::Method, 5, 7),
+                ("self.build", SymbolKind::Function, 9, 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dart_class_methods() {
+        let content = "class Counter {\n  void increment() {\n    value++;\n  }\n}\n\nint _helper() {\n  return 1;\n}\n";
+        let syms = symbols(Language::Dart, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("Counter", SymbolKind::Class, 1, 5),
+                ("increment", SymbolKind::Method, 2, 4),
+                ("_helper", SymbolKind::Function, 7, 9),
+            ]
+        );
+        assert_eq!(syms[2].visibility, Visibility::Private);
+
+        // The regex parser only sees top-level Dart functions
+        let regex = get_regex_parser(Language::Dart)
+            .unwrap()
+            .parse_symbols(content);
+        let top_level: Vec<_> = syms.iter().filter(|s| s.parent.is_none()).collect();
+        assert_eq!(regex.len(), top_level.len());
+        for (r, t) in regex.iter().zip(top_level) {
+            assert_eq!((&r.name, r.line_range), (&t.name, t.line_range));
+        }
+    }
+
+    #[test]
+    fn test_swift_struct_methods() {
+        let content = "struct Point {\n    func length() -> Int {\n        return 0\n    }\n}\nprivate func helper() {}\n";
+        let syms = symbols_matching_regex(Language::Swift, content);
+
+        assert_eq!(
+            kinds_and_ranges(&syms),
+            vec![
+                ("Point", SymbolKind::Struct, 1, 5),
+                ("length", SymbolKind::Method, 2, 4),
+                ("helper", SymbolKind::Function, 6, 6),
+            ]
+        );
+        assert_eq!(syms[0].visibility, Visibility::Internal);
+        assert_eq!(syms[2].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_every_language_grammar_loads() {
+        let languages = [
+            Language::Rust,
+            Language::Python,
+            Language::JavaScript,
+            Language::TypeScript,
+            Language::Go,
+            Language::Php,
+            Language::Java,
+            Language::CSharp,
+            Language::C,
+            Language::Cpp,
+            Language::Ruby,
+            Language::Dart,
+            Language::Swift,
+        ];
+        for language in languages {
+            let fallback = get_regex_parser(language).unwrap();
+            let parser = TreeSitterParser::new(language, fallback).unwrap();
+            assert!(parser.parse_tree("x").is_some(), "{:?}", language);
+        }
+    }
+}
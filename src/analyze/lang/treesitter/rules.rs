@@ -0,0 +1,649 @@
+//! Per-language mapping from syntax nodes to symbols.
+
+use super::Found;
+use crate::types::{SymbolKind, Visibility};
+use tree_sitter::Node;
+
+pub struct Rules {
+    /// Node kinds whose nested functions are methods
+    pub containers: &'static [&'static str],
+    /// Push the symbols declared by a node, if any
+    pub classify: for<'t> fn(Node<'t>, &str, &mut Vec<Found<'t>>),
+}
+
+pub static RUST: Rules = Rules {
+    containers: &["impl_item", "trait_item"],
+    classify: classify_rust,
+};
+
+pub static PYTHON: Rules = Rules {
+    containers: &["class_definition"],
+    classify: classify_python,
+};
+
+pub static JAVASCRIPT: Rules = Rules {
+    containers: &[],
+    classify: classify_javascript,
+};
+
+pub static GO: Rules = Rules {
+    containers: &[],
+    classify: classify_go,
+};
+
+pub static PHP: Rules = Rules {
+    containers: &[],
+    classify: classify_php,
+};
+
+pub static JAVA: Rules = Rules {
+    containers: &[],
+    classify: classify_java,
+};
+
+pub static CSHARP: Rules = Rules {
+    containers: &[],
+    classify: classify_csharp,
+};
+
+pub static C: Rules = Rules {
+    containers: &[],
+    classify: classify_c,
+};
+
+pub static CPP: Rules = Rules {
+    containers: &["class_specifier", "struct_specifier"],
+    classify: classify_cpp,
+};
+
+pub static RUBY: Rules = Rules {
+    containers: &[],
+    classify: classify_ruby,
+};
+
+pub static DART: Rules = Rules {
+    containers: &[],
+    classify: classify_dart,
+};
+
+pub static SWIFT: Rules = Rules {
+    containers: &["class_declaration", "protocol_declaration"],
+    classify: classify_swift,
+};
+
+fn text<'a>(node: Node, src: &'a str) -> &'a str {
+    &src[node.byte_range()]
+}
+
+fn field_text(node: Node, field: &str, src: &str) -> Option<String> {
+    node.child_by_field_name(field)
+        .map(|n| text(n, src).to_string())
+}
+
+fn has_child(node: Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|c| c.kind() == kind);
+    found
+}
+
+/// Text of a node's modifier children (`public static`, `private`, ...)
+fn modifiers(node: Node, src: &str) -> String {
+    let mut cursor = node.walk();
+    let parts: Vec<&str> = node
+        .children(&mut cursor)
+        .filter(|c| {
+            matches!(
+                c.kind(),
+                "modifiers"
+                    | "modifier"
+                    | "visibility_modifier"
+                    | "accessibility_modifier"
+                    | "static_modifier"
+                    | "storage_class_specifier"
+            )
+        })
+        .map(|c| text(c, src))
+        .collect();
+    parts.join(" ")
+}
+
+fn push<'t>(
+    out: &mut Vec<Found<'t>>,
+    node: Node<'t>,
+    kind: SymbolKind,
+    name: Option<String>,
+    visibility: Visibility,
+) {
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        out.push(Found {
+            kind,
+            name,
+            visibility,
+            node,
+            parent: None,
+        });
+    }
+}
+
+fn underscore_private(name: &str) -> Visibility {
+    if name.starts_with('_') {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+fn classify_rust<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "function_item" | "function_signature_item" => SymbolKind::Function,
+        "struct_item" | "union_item" => SymbolKind::Struct,
+        "enum_item" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Trait,
+        "const_item" | "static_item" => SymbolKind::Const,
+        "type_item" => SymbolKind::Type,
+        "mod_item" if node.child_by_field_name("body").is_some() => SymbolKind::Module,
+        _ => return,
+    };
+    let visibility = if has_child(node, "visibility_modifier") {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    };
+    push(out, node, kind, field_text(node, "name", src), visibility);
+}
+
+fn classify_python<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "function_definition" => SymbolKind::Function,
+        "class_definition" => SymbolKind::Class,
+        _ => return,
+    };
+    let Some(name) = field_text(node, "name", src) else {
+        return;
+    };
+    let is_dunder = name.starts_with("__") && name.ends_with("__");
+    let visibility = if is_dunder {
+        Visibility::Public
+    } else {
+        underscore_private(&name)
+    };
+    push(out, node, kind, Some(name), visibility);
+}
+
+/// JS/TS declarations are public when exported
+fn exported(node: Node) -> Visibility {
+    let is_exported = std::iter::successors(node.parent(), |n| n.parent())
+        .take(2)
+        .any(|n| n.kind() == "export_statement");
+    if is_exported {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
+fn classify_javascript<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "function_declaration" | "generator_function_declaration" => SymbolKind::Function,
+        "class_declaration" | "abstract_class_declaration" => SymbolKind::Class,
+        "interface_declaration" => SymbolKind::Interface,
+        "type_alias_declaration" => SymbolKind::Type,
+        "enum_declaration" => SymbolKind::Enum,
+        "internal_module" | "module" => SymbolKind::Module,
+        "method_definition" | "abstract_method_signature" => {
+            let name = field_text(node, "name", src);
+            let modifiers = modifiers(node, src);
+            let visibility = if modifiers.contains("private")
+                || name.as_deref().is_some_and(|n| n.starts_with('#'))
+            {
+                Visibility::Private
+            } else if modifiers.contains("protected") {
+                Visibility::Protected
+            } else {
+                Visibility::Public
+            };
+            push(out, node, SymbolKind::Method, name, visibility);
+            return;
+        }
+        "variable_declarator" => {
+            let is_function = node.child_by_field_name("value").is_some_and(|v| {
+                matches!(
+                    v.kind(),
+                    "arrow_function" | "function_expression" | "function" | "generator_function"
+                )
+            });
+            if !is_function {
+                return;
+            }
+            let visibility = exported(node.parent().unwrap_or(node));
+            push(
+                out,
+                node,
+                SymbolKind::Function,
+                field_text(node, "name", src),
+                visibility,
+            );
+            return;
+        }
+        _ => return,
+    };
+    push(
+        out,
+        node,
+        kind,
+        field_text(node, "name", src),
+        exported(node),
+    );
+}
+
+fn classify_go<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "function_declaration" => SymbolKind::Function,
+        "method_declaration" => SymbolKind::Method,
+        "type_spec" => match node.child_by_field_name("type").map(|t| t.kind()) {
+            Some("struct_type") => SymbolKind::Struct,
+            Some("interface_type") => SymbolKind::Interface,
+            _ => SymbolKind::Type,
+        },
+        "const_spec" => SymbolKind::Const,
+        _ => return,
+    };
+    let Some(name) = field_text(node, "name", src) else {
+        return;
+    };
+    let visibility = if name.starts_with(|c: char| c.is_uppercase()) {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    };
+    push(out, node, kind, Some(name), visibility);
+
+    // `(s *Server[T])` belongs to `Server`
+    if let Some(receiver) = field_text(node, "receiver", src) {
+        let ty = receiver
+            .trim_matches(['(', ')'])
+            .split_whitespace()
+            .last()
+            .unwrap_or("")
+            .trim_start_matches('*');
+        let ty = ty.split('[').next().unwrap_or(ty);
+        if let Some(found) = out.last_mut().filter(|_| !ty.is_empty()) {
+            found.parent = Some(ty.to_string());
+        }
+    }
+}
+
+fn classify_php<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "class_declaration" => SymbolKind::Class,
+        "interface_declaration" => SymbolKind::Interface,
+        "trait_declaration" => SymbolKind::Trait,
+        "enum_declaration" => SymbolKind::Enum,
+        "function_definition" => SymbolKind::Function,
+        "method_declaration" => SymbolKind::Method,
+        "const_declaration" => {
+            let visibility = member_visibility(&modifiers(node, src), Visibility::Public);
+            let mut cursor = node.walk();
+            for element in node.named_children(&mut cursor) {
+                if element.kind() == "const_element" {
+                    let name = element.named_child(0).map(|n| text(n, src).to_string());
+                    push(out, element, SymbolKind::Const, name, visibility);
+                }
+            }
+            return;
+        }
+        _ => return,
+    };
+    let visibility = member_visibility(&modifiers(node, src), Visibility::Public);
+    push(out, node, kind, field_text(node, "name", src), visibility);
+}
+
+/// Visibility from Java/C#/PHP-style modifier keywords
+fn member_visibility(modifiers: &str, default: Visibility) -> Visibility {
+    let words: Vec<&str> = modifiers.split_whitespace().collect();
+    if words.contains(&"public") {
+        Visibility::Public
+    } else if words.contains(&"protected") {
+        Visibility::Protected
+    } else if words.contains(&"private") {
+        Visibility::Private
+    } else if words.contains(&"internal") {
+        Visibility::Internal
+    } else {
+        default
+    }
+}
+
+fn classify_java<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "class_declaration" | "record_declaration" => SymbolKind::Class,
+        "interface_declaration" | "annotation_type_declaration" => SymbolKind::Interface,
+        "enum_declaration" => SymbolKind::Enum,
+        "method_declaration" | "constructor_declaration" => SymbolKind::Method,
+        _ => return,
+    };
+    let visibility = member_visibility(&modifiers(node, src), Visibility::Internal);
+    push(out, node, kind, field_text(node, "name", src), visibility);
+}
+
+fn classify_csharp<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "namespace_declaration" | "file_scoped_namespace_declaration" => SymbolKind::Module,
+        "class_declaration" => SymbolKind::Class,
+        "interface_declaration" => SymbolKind::Interface,
+        "enum_declaration" => SymbolKind::Enum,
+        "struct_declaration" | "record_declaration" | "record_struct_declaration" => {
+            SymbolKind::Struct
+        }
+        "method_declaration" | "constructor_declaration" => SymbolKind::Method,
+        "property_declaration" => SymbolKind::Const,
+        _ => return,
+    };
+    let visibility = if kind == SymbolKind::Module {
+        Visibility::Public
+    } else {
+        member_visibility(&modifiers(node, src), Visibility::Private)
+    };
+    push(out, node, kind, field_text(node, "name", src), visibility);
+}
+
+/// Follow a C/C++ declarator chain (pointers, references) to the function
+/// declarator and return its name node
+fn function_name<'t>(declarator: Node<'t>) -> Option<Node<'t>> {
+    let mut current = declarator;
+    loop {
+        match current.kind() {
+            "function_declarator" => {
+                return current.child_by_field_name("declarator").filter(|n| {
+                    matches!(
+                        n.kind(),
+                        "identifier"
+                            | "field_identifier"
+                            | "qualified_identifier"
+                            | "destructor_name"
+                            | "operator_name"
+                            | "template_function"
+                    )
+                });
+            }
+            "pointer_declarator" | "reference_declarator" | "parenthesized_declarator" => {
+                current = current
+                    .child_by_field_name("declarator")
+                    .or_else(|| current.named_child(current.named_child_count().checked_sub(1)?))?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn classify_c<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    classify_c_family(node, src, out, false);
+}
+
+fn classify_cpp<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    classify_c_family(node, src, out, true);
+}
+
+fn classify_c_family<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>, cpp: bool) {
+    match node.kind() {
+        "function_definition" | "declaration" | "field_declaration" => {
+            let Some(name_node) = node
+                .child_by_field_name("declarator")
+                .and_then(function_name)
+            else {
+                return;
+            };
+            let name = text(name_node, src).to_string();
+            let in_class = node.kind() == "field_declaration"
+                || node
+                    .parent()
+                    .is_some_and(|p| p.kind() == "field_declaration_list");
+            let kind = if in_class || name.contains("::") {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            };
+            let visibility = if in_class {
+                access_visibility(node, src)
+            } else if modifiers(node, src).contains("static") {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            };
+            push(out, node, kind, Some(name), visibility);
+        }
+        "struct_specifier" | "union_specifier" | "class_specifier" | "enum_specifier" => {
+            if node.child_by_field_name("body").is_none() {
+                return;
+            }
+            let kind = match node.kind() {
+                "class_specifier" => SymbolKind::Class,
+                "enum_specifier" => SymbolKind::Enum,
+                _ => SymbolKind::Struct,
+            };
+            push(
+                out,
+                node,
+                kind,
+                field_text(node, "name", src),
+                Visibility::Public,
+            );
+        }
+        "namespace_definition" if cpp => {
+            push(
+                out,
+                node,
+                SymbolKind::Module,
+                field_text(node, "name", src),
+                Visibility::Public,
+            );
+        }
+        "type_definition" => {
+            let name = node
+                .child_by_field_name("declarator")
+                .map(|d| text(d, src).to_string());
+            push(out, node, SymbolKind::Type, name, Visibility::Public);
+        }
+        _ => {}
+    }
+}
+
+/// C++ member access from the nearest preceding access specifier
+fn access_visibility(node: Node, src: &str) -> Visibility {
+    let mut sibling = node.prev_named_sibling();
+    while let Some(s) = sibling {
+        if s.kind() == "access_specifier" {
+            return match text(s, src).trim_end_matches(':').trim() {
+                "public" => Visibility::Public,
+                "protected" => Visibility::Protected,
+                _ => Visibility::Private,
+            };
+        }
+        sibling = s.prev_named_sibling();
+    }
+
+    let is_struct = node
+        .parent()
+        .and_then(|list| list.parent())
+        .is_some_and(|p| p.kind() == "struct_specifier");
+    if is_struct {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
+fn classify_ruby<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    match node.kind() {
+        "class" => push(
+            out,
+            node,
+            SymbolKind::Class,
+            field_text(node, "name", src),
+            Visibility::Public,
+        ),
+        "module" => push(
+            out,
+            node,
+            SymbolKind::Module,
+            field_text(node, "name", src),
+            Visibility::Public,
+        ),
+        "method" => push(
+            out,
+            node,
+            SymbolKind::Method,
+            field_text(node, "name", src),
+            ruby_visibility(node, src),
+        ),
+        "singleton_method" => push(
+            out,
+            node,
+            SymbolKind::Function,
+            field_text(node, "name", src).map(|n| format!("self.{}", n)),
+            ruby_visibility(node, src),
+        ),
+        "call" => {
+            let method = field_text(node, "method", src);
+            if !matches!(
+                method.as_deref(),
+                Some("attr_accessor" | "attr_reader" | "attr_writer")
+            ) {
+                return;
+            }
+            let Some(args) = node.child_by_field_name("arguments") else {
+                return;
+            };
+            let mut cursor = args.walk();
+            for arg in args.named_children(&mut cursor) {
+                let name = text(arg, src).trim_start_matches(':').to_string();
+                push(out, arg, SymbolKind::Const, Some(name), Visibility::Public);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Ruby methods take the visibility of the last bare `private`/`protected`/
+/// `public` call before them in the same body
+fn ruby_visibility(node: Node, src: &str) -> Visibility {
+    let mut sibling = node.prev_named_sibling();
+    while let Some(s) = sibling {
+        if s.kind() == "identifier" {
+            match text(s, src) {
+                "private" => return Visibility::Private,
+                "protected" => return Visibility::Internal,
+                "public" => return Visibility::Public,
+                _ => {}
+            }
+        }
+        sibling = s.prev_named_sibling();
+    }
+    Visibility::Public
+}
+
+/// Name of a Dart function, method, getter, setter or constructor from its signature
+fn dart_name(node: Node, src: &str) -> Option<String> {
+    if let Some(name) = field_text(node, "name", src) {
+        return Some(name);
+    }
+    let mut cursor = node.walk();
+    let name = node
+        .named_children(&mut cursor)
+        .filter(|c| c.kind().ends_with("signature"))
+        .find_map(|c| dart_name(c, src));
+    name
+}
+
+fn classify_dart<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "class_declaration" | "class_definition" => SymbolKind::Class,
+        "mixin_declaration" => SymbolKind::Trait,
+        "extension_declaration" => SymbolKind::Module,
+        "enum_declaration" => SymbolKind::Enum,
+        "type_alias" => {
+            let mut cursor = node.walk();
+            let name = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "type_identifier")
+                .map(|n| text(n, src).to_string());
+            let visibility = name
+                .as_deref()
+                .map_or(Visibility::Public, underscore_private);
+            push(out, node, SymbolKind::Type, name, visibility);
+            return;
+        }
+        "function_declaration" => SymbolKind::Function,
+        "method_declaration" => {
+            let is_static = text(node, src).trim_start().starts_with("static ");
+            if is_static {
+                SymbolKind::Function
+            } else {
+                SymbolKind::Method
+            }
+        }
+        "declaration" => {
+            // Abstract members and constructors without bodies
+            let mut cursor = node.walk();
+            let signature = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind().ends_with("signature"));
+            match signature {
+                Some(_) => SymbolKind::Method,
+                None => return,
+            }
+        }
+        _ => return,
+    };
+    let Some(name) = dart_name(node, src) else {
+        return;
+    };
+    let visibility = underscore_private(&name);
+    push(out, node, kind, Some(name), visibility);
+}
+
+fn classify_swift<'t>(node: Node<'t>, src: &str, out: &mut Vec<Found<'t>>) {
+    let kind = match node.kind() {
+        "class_declaration" => {
+            match node
+                .child_by_field_name("declaration_kind")
+                .map(|k| text(k, src))
+            {
+                Some("struct") => SymbolKind::Struct,
+                Some("enum") => SymbolKind::Enum,
+                Some("extension") => SymbolKind::Module,
+                _ => SymbolKind::Class,
+            }
+        }
+        "protocol_declaration" => SymbolKind::Trait,
+        "function_declaration" => SymbolKind::Function,
+        "protocol_function_declaration" => SymbolKind::Method,
+        "init_declaration" => {
+            let visibility = swift_visibility(node, src);
+            push(
+                out,
+                node,
+                SymbolKind::Method,
+                Some("init".to_string()),
+                visibility,
+            );
+            return;
+        }
+        "typealias_declaration" => SymbolKind::Type,
+        _ => return,
+    };
+    let visibility = swift_visibility(node, src);
+    push(out, node, kind, field_text(node, "name", src), visibility);
+}
+
+fn swift_visibility(node: Node, src: &str) -> Visibility {
+    let modifiers = modifiers(node, src);
+    let words: Vec<&str> = modifiers.split_whitespace().collect();
+    if words.contains(&"public") || words.contains(&"open") {
+        Visibility::Public
+    } else if words.contains(&"private") || words.contains(&"fileprivate") {
+        Visibility::Private
+    } else {
+        Visibility::Internal
+    }
+}
@@ -0,0 +1,226 @@
+//! Architecture layer rules.
+//!
+//! Layers are declared with `[[layer]]` entries in agentlens.toml. Each names
+//! the directories (paths or globs) it covers and which other layers its files
+//! may import: `forbid` lists layers it must not depend on, `allow` restricts
+//! it to the listed layers. Rules are checked against every import edge of the
+//! file graph. Imports within a layer, and of files outside every layer, are
+//! always allowed.
+
+use crate::analyze::{FileGraph, ModuleInfo};
+use crate::config::LayerConfig;
+use anyhow::{bail, Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// An import breaking a layer rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerViolation {
+    /// Importing file
+    pub file: String,
+    /// Imported file
+    pub import: String,
+    pub layer: String,
+    pub target_layer: String,
+    /// The rule broken, e.g. "`domain` must not import `infra`"
+    pub rule: String,
+}
+
+/// Layer rules, validated and with their paths compiled
+#[derive(Debug, Default)]
+pub struct LayerRules {
+    layers: Vec<(LayerConfig, GlobSet)>,
+}
+
+/// Violations of the configured layer rules
+#[derive(Debug, Clone, Default)]
+pub struct LayerReport {
+    /// Sorted by importing file, then imported file
+    pub violations: Vec<LayerViolation>,
+}
+
+impl LayerRules {
+    /// Compile `layers`, failing on invalid globs and rules naming unknown layers
+    pub fn new(layers: &[LayerConfig]) -> Result<Self> {
+        let mut compiled = Vec::new();
+
+        for layer in layers {
+            if layers.iter().filter(|l| l.name == layer.name).count() > 1 {
+                bail!("layer `{}` is declared more than once", layer.name);
+            }
+            for other in layer.forbid.iter().chain(layer.allow.iter().flatten()) {
+                if !layers.iter().any(|l| &l.name == other) {
+                    bail!("layer `{}` refers to unknown layer `{}`", layer.name, other);
+                }
+            }
+
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &layer.paths {
+                let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| {
+                        format!("invalid path `{}` in layer `{}`", pattern, layer.name)
+                    })?;
+                builder.add(glob);
+            }
+            compiled.push((layer.clone(), builder.build()?));
+        }
+
+        Ok(Self { layers: compiled })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The first declared layer covering `file` or one of its directories
+    pub fn layer_of(&self, file: &str) -> Option<&LayerConfig> {
+        self.layers
+            .iter()
+            .find(|(_, globs)| {
+                Path::new(file)
+                    .ancestors()
+                    .take_while(|p| !p.as_os_str().is_empty())
+                    .any(|p| globs.is_match(p))
+            })
+            .map(|(layer, _)| layer)
+    }
+
+    pub fn check(&self, graph: &FileGraph) -> LayerReport {
+        let mut violations = Vec::new();
+        if self.is_empty() {
+            return LayerReport { violations };
+        }
+
+        for (file, imports) in &graph.imports {
+            let Some(layer) = self.layer_of(file) else {
+                continue;
+            };
+            // External packages aren't part of any layer
+            for import in imports.iter().filter(|i| graph.imports.contains_key(*i)) {
+                let Some(target) = self.layer_of(import) else {
+                    continue;
+                };
+                if target.name == layer.name {
+                    continue;
+                }
+                let rule = if layer.forbid.contains(&target.name) {
+                    format!("`{}` must not import `{}`", layer.name, target.name)
+                } else if let Some(allow) = layer
+                    .allow
+                    .as_ref()
+                    .filter(|allow| !allow.contains(&target.name))
+                {
+                    if allow.is_empty() {
+                        format!("`{}` must not import other layers", layer.name)
+                    } else {
+                        let allowed: Vec<String> =
+                            allow.iter().map(|a| format!("`{}`", a)).collect();
+                        format!("`{}` may only import {}", layer.name, allowed.join(", "))
+                    }
+                } else {
+                    continue;
+                };
+                violations.push(LayerViolation {
+                    file: file.clone(),
+                    import: import.clone(),
+                    layer: layer.name.clone(),
+                    target_layer: target.name.clone(),
+                    rule,
+                });
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.file, &a.import).cmp(&(&b.file, &b.import)));
+        LayerReport { violations }
+    }
+}
+
+impl LayerReport {
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violations by imports in `module`'s files
+    pub fn for_module(&self, module: &ModuleInfo) -> Vec<&LayerViolation> {
+        self.violations
+            .iter()
+            .filter(|v| module.files.contains(&v.file))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(name: &str, path: &str, allow: Option<&[&str]>, forbid: &[&str]) -> LayerConfig {
+        LayerConfig {
+            name: name.to_string(),
+            paths: vec![path.to_string()],
+            allow: allow.map(|a| a.iter().map(|s| s.to_string()).collect()),
+            forbid: forbid.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_check_layer_rules() {
+        let rules = LayerRules::new(&[
+            layer("domain", "src/domain", None, &["infra"]),
+            layer("infra", "src/infra", None, &[]),
+            layer("api", "src/api", None, &[]),
+            layer("ui", "src/ui/*", Some(&["api"]), &[]),
+        ])
+        .unwrap();
+
+        let mut graph = FileGraph::new();
+        graph.add_file(
+            "src/domain/user.rs",
+            vec!["src/infra/db.rs".to_string(), "serde".to_string()],
+        );
+        graph.add_file("src/infra/db.rs", vec!["src/domain/user.rs".to_string()]);
+        graph.add_file("src/api/routes.ts", vec![]);
+        graph.add_file(
+            "src/ui/pages/home.ts",
+            vec![
+                "src/api/routes.ts".to_string(),
+                "src/domain/user.rs".to_string(),
+                "src/util.ts".to_string(),
+            ],
+        );
+        graph.add_file("src/util.ts", vec!["src/infra/db.rs".to_string()]);
+
+        let report = rules.check(&graph);
+
+        assert_eq!(rules.layer_of("src/ui/pages/home.ts").unwrap().name, "ui");
+        assert!(rules.layer_of("src/util.ts").is_none());
+        let found: Vec<(&str, &str, &str)> = report
+            .violations
+            .iter()
+            .map(|v| (v.file.as_str(), v.import.as_str(), v.rule.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "src/domain/user.rs",
+                    "src/infra/db.rs",
+                    "`domain` must not import `infra`"
+                ),
+                (
+                    "src/ui/pages/home.ts",
+                    "src/domain/user.rs",
+                    "`ui` may only import `api`"
+                ),
+            ]
+        );
+
+        assert!(LayerRules::new(&[layer("ui", "src/ui", Some(&["apii"]), &[])]).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Project layout discovered from build manifests.
+//!
+//! Reads the manifests that declare where an ecosystem's units live:
+//! - PHP: PSR-4 roots from composer.json `autoload` / `autoload-dev`
+//! - C#: directories containing a `.csproj`
+//! - Dart: directories containing a `pubspec.yaml`
+//! - Swift: SwiftPM targets declared in `Package.swift`
+//!
+//! Workspace manifests (Cargo, npm/pnpm, go.work, uv) additionally yield the
+//! workspace's member packages; see [`crate::analyze::workspace`].
+
+use crate::analyze::resolve::{join, parent_dir};
+use crate::analyze::workspace::{detect_packages, WorkspacePackage};
+use crate::analyze::BoundaryType;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+static SWIFT_TARGET_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.(target|executableTarget|testTarget|macro|plugin)\s*\(").unwrap());
+
+static SWIFT_NAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\bname\s*:\s*"([^"]+)""#).unwrap());
+
+static SWIFT_PATH_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\bpath\s*:\s*"([^"]+)""#).unwrap());
+
+/// A module root declared by a build manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestBoundary {
+    /// Directory relative to project root
+    pub path: String,
+    /// Ecosystem the boundary comes from
+    pub boundary_type: BoundaryType,
+    /// Manifest file that declared it
+    pub manifest: String,
+}
+
+/// Module roots declared by the project's build manifests
+#[derive(Debug, Clone, Default)]
+pub struct ProjectLayout {
+    pub boundaries: Vec<ManifestBoundary>,
+    /// Workspace member packages (empty for single-package projects)
+    pub packages: Vec<WorkspacePackage>,
+}
+
+impl ProjectLayout {
+    /// Build the layout from manifest contents keyed by relative path
+    pub fn from_contents(manifests: &BTreeMap<String, String>) -> Self {
+        let mut layout = Self::default();
+
+        for (manifest, content) in manifests {
+            layout.add_manifest(manifest, content);
+        }
+
+        layout.boundaries.sort_by(|a, b| a.path.cmp(&b.path));
+        layout.boundaries.dedup_by(|a, b| a.path == b.path);
+        layout.packages = detect_packages(manifests);
+        layout
+    }
+
+    /// Find the workspace package owning a path (the deepest package containing it)
+    pub fn package_for(&self, path: &str) -> Option<&WorkspacePackage> {
+        self.packages
+            .iter()
+            .filter(|p| {
+                p.path.is_empty()
+                    || path == p.path
+                    || path
+                        .strip_prefix(p.path.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|p| p.path.len())
+    }
+
+    /// Add boundaries declared by a single manifest
+    pub fn add_manifest(&mut self, manifest: &str, content: &str) {
+        let path = Path::new(manifest);
+        let dir = parent_dir(manifest).to_string();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+        let boundaries: Vec<(String, BoundaryType)> = match (file_name, extension) {
+            ("composer.json", _) => parse_psr4_roots(content)
+                .into_iter()
+                .filter_map(|(_, root)| join_path(&dir, &root).map(|p| (p, BoundaryType::PhpPsr4)))
+                .collect(),
+            ("pubspec.yaml", _) => vec![(dir.clone(), BoundaryType::DartPackage)],
+            ("Package.swift", _) => parse_swift_targets(content)
+                .into_iter()
+                .filter_map(|target| {
+                    join_path(&dir, &target).map(|p| (p, BoundaryType::SwiftTarget))
+                })
+                .collect(),
+            (_, "csproj") => vec![(dir.clone(), BoundaryType::DotNetProject)],
+            _ => Vec::new(),
+        };
+
+        for (path, boundary_type) in boundaries {
+            self.boundaries.push(ManifestBoundary {
+                path,
+                boundary_type,
+                manifest: manifest.to_string(),
+            });
+        }
+    }
+}
+
+/// Extract PSR-4 `(namespace prefix, directory)` pairs from composer.json
+/// (both autoload sections)
+pub(crate) fn parse_psr4_roots(content: &str) -> Vec<(String, String)> {
+    let json: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut roots = Vec::new();
+    for section in ["autoload", "autoload-dev"] {
+        let Some(map) = json
+            .get(section)
+            .and_then(|s| s.get("psr-4"))
+            .and_then(|p| p.as_object())
+        else {
+            continue;
+        };
+
+        for (prefix, value) in map {
+            match value {
+                serde_json::Value::String(dir) => roots.push((prefix.clone(), dir.clone())),
+                serde_json::Value::Array(dirs) => roots.extend(
+                    dirs.iter()
+                        .filter_map(|d| Some((prefix.clone(), d.as_str()?.to_string()))),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    roots
+}
+
+/// Extract target source directories from Package.swift.
+/// Targets default to `Sources/<name>` (or `Tests/<name>` for test targets).
+fn parse_swift_targets(content: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+
+    for cap in SWIFT_TARGET_PATTERN.captures_iter(content) {
+        let kind = cap.get(1).map(|m| m.as_str()).unwrap_or("target");
+        let args_start = cap.get(0).unwrap().end();
+        let args = &content[args_start..find_paren_end(content, args_start)];
+
+        let Some(name) = SWIFT_NAME_PATTERN.captures(args).and_then(|c| c.get(1)) else {
+            continue;
+        };
+
+        let dir = match SWIFT_PATH_PATTERN.captures(args).and_then(|c| c.get(1)) {
+            Some(path) => path.as_str().to_string(),
+            None if kind == "testTarget" => format!("Tests/{}", name.as_str()),
+            None => format!("Sources/{}", name.as_str()),
+        };
+        dirs.push(dir);
+    }
+
+    dirs
+}
+
+/// Offset of the `)` closing a call whose arguments start at `start`
+fn find_paren_end(content: &str, start: usize) -> usize {
+    let mut depth = 1;
+    for (i, b) in content.as_bytes()[start..].iter().enumerate() {
+        match b {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return start + i;
+                }
+            }
+            _ => {}
+        }
+    }
+    content.len()
+}
+
+/// Join a manifest-relative directory onto the manifest's directory.
+/// Returns `None` for the manifest's own directory (e.g. a PSR-4 root of `""`),
+/// which is already a module candidate, and for paths outside the project.
+fn join_path(base: &str, relative: &str) -> Option<String> {
+    join(base, relative).filter(|path| !path.is_empty() && path != base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composer_psr4_roots() {
+        let mut layout = ProjectLayout::default();
+        layout.add_manifest(
+            "backend/composer.json",
+            r#"{
+                "autoload": { "psr-4": { "App\\": "app/", "Domain\\": ["src/Domain", "lib/"] } },
+                "autoload-dev": { "psr-4": { "Tests\\": "tests/" } }
+            }"#,
+        );
+
+        let paths: Vec<_> = layout.boundaries.iter().map(|b| b.path.as_str()).collect();
+        assert!(paths.contains(&"backend/app"));
+        assert!(paths.contains(&"backend/src/Domain"));
+        assert!(paths.contains(&"backend/lib"));
+        assert!(paths.contains(&"backend/tests"));
+        assert!(layout
+            .boundaries
+            .iter()
+            .all(|b| b.boundary_type == BoundaryType::PhpPsr4));
+    }
+
+    #[test]
+    fn test_psr4_roots_skip_empty_and_outside_paths() {
+        let mut layout = ProjectLayout::default();
+        layout.add_manifest(
+            "composer.json",
+            r#"{ "autoload": { "psr-4": { "App\\": "", "Lib\\": "../lib", "Src\\": "./" } } }"#,
+        );
+        assert!(layout.boundaries.is_empty());
+
+        layout.add_manifest(
+            "backend/composer.json",
+            r#"{ "autoload": { "psr-4": { "App\\": "", "Shared\\": "../shared/" } } }"#,
+        );
+        let paths: Vec<_> = layout.boundaries.iter().map(|b| b.path.as_str()).collect();
+        assert_eq!(paths, vec!["shared"]);
+    }
+
+    #[test]
+    fn test_swift_package_targets() {
+        let content = r#"
+let package = Package(
+    name: "Kit",
+    targets: [
+        .target(name: "Core", dependencies: [.product(name: "Logging", package: "swift-log")]),
+        .executableTarget(name: "cli", dependencies: ["Core"], path: "Tools/cli"),
+        .testTarget(name: "CoreTests", dependencies: ["Core"]),
+    ]
+)
+"#;
+        let dirs = parse_swift_targets(content);
+        assert_eq!(dirs, vec!["Sources/Core", "Tools/cli", "Tests/CoreTests"]);
+    }
+
+    #[test]
+    fn test_csproj_and_pubspec() {
+        let layout = ProjectLayout::from_contents(&BTreeMap::new());
+        assert!(layout.boundaries.is_empty());
+
+        let mut layout = ProjectLayout::default();
+        layout.add_manifest("src/Billing/Billing.csproj", "<Project />");
+        layout.add_manifest("packages/ui/pubspec.yaml", "name: ui");
+        assert_eq!(layout.boundaries[0].path, "src/Billing");
+        assert_eq!(
+            layout.boundaries[0].boundary_type,
+            BoundaryType::DotNetProject
+        );
+        assert_eq!(layout.boundaries[1].path, "packages/ui");
+        assert_eq!(
+            layout.boundaries[1].boundary_type,
+            BoundaryType::DartPackage
+        );
+    }
+}
@@ -0,0 +1,638 @@
+//! Package data read from build manifests.
+//!
+//! One parser per manifest format, shared by workspace detection, import
+//! resolution and the dependency inventory so they all read a package's name
+//! and dependencies the same way:
+//! - Cargo.toml: `[package]`, `[dependencies]`, `[dev-dependencies]`,
+//!   `[build-dependencies]`, target-specific tables and `[workspace.dependencies]`
+//! - package.json: `dependencies`, `devDependencies`, `peerDependencies`,
+//!   `optionalDependencies`
+//! - go.mod: `module` and `require`
+//! - pyproject.toml: `[project]` (PEP 621), dependency groups and Poetry
+//! - requirements.txt
+//! - composer.json: `require`, `require-dev`
+//! - Gemfile: `gem` lines, with `group :development/:test` blocks as dev
+//! - pubspec.yaml: `dependencies`, `dev_dependencies`
+//! - Package.swift: `.package(...)` entries
+
+use crate::analyze::PackageEcosystem;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+static GEM_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^gem\s+["']([^"']+)["'](?:\s*,\s*["']([^"']+)["'])?"#).unwrap());
+
+static SWIFT_PACKAGE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)\.package\s*\((.*?)\)\s*[,\]]").unwrap());
+
+static SWIFT_ARG_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(name|url|from|exact|branch|revision|path)\s*:\s*"([^"]+)""#).unwrap()
+});
+
+/// Ruby keywords that open a block closed by `end`
+const RUBY_BLOCK_KEYWORDS: &[&str] = &["if", "unless", "case", "begin", "while", "until", "def"];
+
+/// What one build manifest declares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PackageManifest {
+    pub ecosystem: PackageEcosystem,
+    /// Package name (Go: module path), for manifests that declare a package
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Cargo `version.workspace = true`: the version comes from the workspace root
+    pub inherits_version: bool,
+    /// Dependencies of the package itself
+    pub dependencies: Vec<DeclaredDependency>,
+    /// Cargo `[workspace.dependencies]`, shared with members rather than used
+    /// by this package
+    pub workspace_dependencies: Vec<DeclaredDependency>,
+}
+
+/// A dependency as a manifest declares it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DeclaredDependency {
+    /// Name as declared (Cargo: the key code imports it under)
+    pub name: String,
+    /// Package the name refers to when renamed (Cargo `package = "..."`)
+    pub package: Option<String>,
+    /// Version requirement, if any
+    pub version: Option<String>,
+    /// Only needed for development, tests or builds
+    pub dev: bool,
+    /// Path, workspace or SDK dependency: project code or declared elsewhere,
+    /// not a third-party package
+    pub local: bool,
+}
+
+impl DeclaredDependency {
+    fn new(name: impl Into<String>, version: Option<String>, dev: bool) -> Self {
+        Self {
+            name: name.into(),
+            package: None,
+            version,
+            dev,
+            local: false,
+        }
+    }
+
+    fn local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// Name of the package depended on, after any rename
+    pub fn package(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl PackageManifest {
+    fn new(ecosystem: PackageEcosystem) -> Self {
+        Self {
+            ecosystem,
+            name: None,
+            version: None,
+            inherits_version: false,
+            dependencies: Vec::new(),
+            workspace_dependencies: Vec::new(),
+        }
+    }
+
+    fn with_dependencies(
+        ecosystem: PackageEcosystem,
+        dependencies: Vec<DeclaredDependency>,
+    ) -> Self {
+        Self {
+            dependencies,
+            ..Self::new(ecosystem)
+        }
+    }
+}
+
+/// Parse a manifest by its file name; `None` for other files and manifests
+/// that don't parse
+pub(crate) fn parse_manifest(path: &str, content: &str) -> Option<PackageManifest> {
+    match path.rsplit('/').next().unwrap_or(path) {
+        "Cargo.toml" => parse_cargo(content),
+        "package.json" => parse_npm(content),
+        "go.mod" => Some(parse_go_mod(content)),
+        "pyproject.toml" => parse_pyproject(content),
+        "requirements.txt" => Some(PackageManifest::with_dependencies(
+            PackageEcosystem::Python,
+            parse_requirements(content),
+        )),
+        "composer.json" => parse_composer(content),
+        "Gemfile" => Some(PackageManifest::with_dependencies(
+            PackageEcosystem::Gem,
+            parse_gemfile(content),
+        )),
+        "pubspec.yaml" => Some(PackageManifest::with_dependencies(
+            PackageEcosystem::Pub,
+            parse_pubspec(content),
+        )),
+        "Package.swift" => Some(PackageManifest::with_dependencies(
+            PackageEcosystem::Swift,
+            parse_swift_package(content),
+        )),
+        _ => None,
+    }
+}
+
+fn parse_cargo(content: &str) -> Option<PackageManifest> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut manifest = PackageManifest::new(PackageEcosystem::Cargo);
+
+    if let Some(package) = toml.get("package") {
+        manifest.name = package
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map(String::from);
+        match package.get("version") {
+            Some(toml::Value::String(v)) => manifest.version = Some(v.clone()),
+            // version.workspace = true
+            Some(toml::Value::Table(_)) => manifest.inherits_version = true,
+            _ => {}
+        }
+    }
+
+    // The package itself and each `[target.'cfg(..)']`
+    let mut parents = vec![&toml];
+    if let Some(targets) = toml.get("target").and_then(|t| t.as_table()) {
+        parents.extend(targets.values());
+    }
+    manifest.dependencies = parents.into_iter().flat_map(cargo_tables).collect();
+    if let Some(workspace) = toml.get("workspace") {
+        manifest.workspace_dependencies = cargo_tables(workspace);
+    }
+    Some(manifest)
+}
+
+/// Entries of the dependency tables under one Cargo.toml table
+fn cargo_tables(parent: &toml::Value) -> Vec<DeclaredDependency> {
+    let mut declared = Vec::new();
+    for (section, dev) in [
+        ("dependencies", false),
+        ("dev-dependencies", true),
+        ("build-dependencies", true),
+    ] {
+        let Some(table) = parent.get(section).and_then(|t| t.as_table()) else {
+            continue;
+        };
+        for (name, spec) in table {
+            let version = spec
+                .as_str()
+                .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                .map(str::to_string);
+            let mut dependency = DeclaredDependency::new(name.clone(), version, dev)
+                // Local crates and `workspace = true` entries are declared elsewhere
+                .local(spec.get("path").is_some() || spec.get("workspace").is_some());
+            dependency.package = spec
+                .get("package")
+                .and_then(|p| p.as_str())
+                .map(String::from);
+            declared.push(dependency);
+        }
+    }
+    declared
+}
+
+fn parse_npm(content: &str) -> Option<PackageManifest> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut manifest = PackageManifest::new(PackageEcosystem::Npm);
+    manifest.name = json.get("name").and_then(|n| n.as_str()).map(String::from);
+    manifest.version = json
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    for (section, dev) in [
+        ("dependencies", false),
+        ("peerDependencies", false),
+        ("optionalDependencies", false),
+        ("devDependencies", true),
+    ] {
+        let Some(map) = json.get(section).and_then(|s| s.as_object()) else {
+            continue;
+        };
+        for (name, version) in map {
+            let version = version.as_str().unwrap_or("");
+            // Workspace siblings and local packages are project code
+            let local = ["workspace:", "file:", "link:", "portal:"]
+                .iter()
+                .any(|p| version.starts_with(p));
+            manifest.dependencies.push(
+                DeclaredDependency::new(name.clone(), Some(version.to_string()), dev).local(local),
+            );
+        }
+    }
+    Some(manifest)
+}
+
+fn parse_go_mod(content: &str) -> PackageManifest {
+    let mut manifest = PackageManifest::new(PackageEcosystem::Go);
+    let mut in_require = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_require {
+            if line == ")" {
+                in_require = false;
+                continue;
+            }
+        } else if let Some(module) = line.strip_prefix("module ") {
+            manifest.name = Some(module.trim().trim_matches('"').to_string());
+            continue;
+        }
+
+        let require = if in_require {
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_require = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = require.split_whitespace();
+        if let Some(module) = parts.next() {
+            let version = parts.next().map(str::to_string);
+            manifest
+                .dependencies
+                .push(DeclaredDependency::new(module, version, false));
+        }
+    }
+    manifest
+}
+
+fn parse_pyproject(content: &str) -> Option<PackageManifest> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut manifest = PackageManifest::new(PackageEcosystem::Python);
+    let mut requirements = |value: Option<&toml::Value>, dev: bool| {
+        for requirement in value.and_then(|v| v.as_array()).into_iter().flatten() {
+            if let Some((name, version)) = requirement.as_str().and_then(parse_requirement) {
+                manifest
+                    .dependencies
+                    .push(DeclaredDependency::new(name, version, dev));
+            }
+        }
+    };
+
+    let project = toml.get("project");
+    requirements(project.and_then(|p| p.get("dependencies")), false);
+    // Extras are optional features of the package, installed for its users
+    if let Some(extras) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|o| o.as_table())
+    {
+        for group in extras.values() {
+            requirements(Some(group), false);
+        }
+    }
+    if let Some(groups) = toml.get("dependency-groups").and_then(|g| g.as_table()) {
+        for group in groups.values() {
+            requirements(Some(group), true);
+        }
+    }
+
+    let poetry = toml.get("tool").and_then(|t| t.get("poetry"));
+    let mut poetry_tables: Vec<(&toml::Value, bool)> = Vec::new();
+    if let Some(poetry) = poetry {
+        poetry_tables.extend(poetry.get("dependencies").map(|t| (t, false)));
+        poetry_tables.extend(poetry.get("dev-dependencies").map(|t| (t, true)));
+        if let Some(groups) = poetry.get("group").and_then(|g| g.as_table()) {
+            for group in groups.values() {
+                poetry_tables.extend(group.get("dependencies").map(|t| (t, true)));
+            }
+        }
+    }
+    for (table, dev) in poetry_tables {
+        for (name, spec) in table.as_table().into_iter().flatten() {
+            if name == "python" {
+                continue;
+            }
+            let version = spec
+                .as_str()
+                .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                .map(str::to_string);
+            manifest.dependencies.push(
+                DeclaredDependency::new(name.clone(), version, dev)
+                    .local(spec.get("path").is_some()),
+            );
+        }
+    }
+
+    manifest.name = project
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from);
+    manifest.version = project
+        .and_then(|p| p.get("version"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    Some(manifest)
+}
+
+fn parse_requirements(content: &str) -> Vec<DeclaredDependency> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(parse_requirement)
+        .map(|(name, version)| DeclaredDependency::new(name, version, false))
+        .collect()
+}
+
+/// Name and version specifier of a PEP 508 requirement (`requests[socks]>=2.0; python_version>"3"`)
+fn parse_requirement(requirement: &str) -> Option<(String, Option<String>)> {
+    let requirement = requirement.split(';').next().unwrap_or("").trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = requirement[end..].trim();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map(|(_, r)| r.trim()).unwrap_or(""),
+        None => rest,
+    };
+    let version = (!rest.is_empty() && !rest.starts_with('@')).then(|| rest.to_string());
+    Some((name.to_string(), version))
+}
+
+fn parse_composer(content: &str) -> Option<PackageManifest> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut manifest = PackageManifest::new(PackageEcosystem::Composer);
+    manifest.name = json.get("name").and_then(|n| n.as_str()).map(String::from);
+
+    for (section, dev) in [("require", false), ("require-dev", true)] {
+        let Some(map) = json.get(section).and_then(|s| s.as_object()) else {
+            continue;
+        };
+        for (name, version) in map {
+            // The PHP runtime and extensions aren't packages
+            if name == "php" || name.starts_with("ext-") || name.starts_with("lib-") {
+                continue;
+            }
+            let version = version.as_str().map(str::to_string);
+            manifest
+                .dependencies
+                .push(DeclaredDependency::new(name.clone(), version, dev));
+        }
+    }
+    Some(manifest)
+}
+
+fn parse_gemfile(content: &str) -> Vec<DeclaredDependency> {
+    let mut declared = Vec::new();
+    // Whether each block open until its `end` is a development/test group
+    let mut blocks: Vec<bool> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let keyword = line.split_whitespace().next().unwrap_or("");
+        if line.starts_with("group ") && line.ends_with(" do") {
+            blocks.push(line.contains(":development") || line.contains(":test"));
+        } else if line.ends_with(" do") || RUBY_BLOCK_KEYWORDS.contains(&keyword) {
+            blocks.push(false);
+        } else if line == "end" {
+            blocks.pop();
+        } else if let Some(cap) = GEM_PATTERN.captures(line) {
+            let dev = blocks.iter().any(|dev| *dev)
+                || line.contains("group: :development")
+                || line.contains("group: :test");
+            let version = cap.get(2).map(|m| m.as_str().to_string());
+            declared
+                .push(DeclaredDependency::new(&cap[1], version, dev).local(line.contains("path:")));
+        }
+    }
+    declared
+}
+
+fn parse_pubspec(content: &str) -> Vec<DeclaredDependency> {
+    let mut declared = Vec::new();
+    let mut section: Option<bool> = None;
+    // A dependency whose source is given on the following, deeper lines
+    let mut pending: Option<DeclaredDependency> = None;
+
+    for raw in content.lines() {
+        let line = raw.split('#').next().unwrap_or("").trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+
+        if indent == 0 {
+            declared.extend(pending.take());
+            section = match line {
+                "dependencies:" => Some(false),
+                "dev_dependencies:" => Some(true),
+                _ => None,
+            };
+            continue;
+        }
+        let Some(dev) = section else {
+            continue;
+        };
+
+        if indent <= 2 {
+            declared.extend(pending.take());
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            if value.is_empty() {
+                pending = Some(DeclaredDependency::new(name, None, dev));
+            } else {
+                declared.push(DeclaredDependency::new(name, Some(value.to_string()), dev));
+            }
+        } else if line.starts_with("sdk:") || line.starts_with("path:") {
+            // Flutter SDK packages and local packages aren't third-party
+            if let Some(dependency) = pending.take() {
+                declared.push(dependency.local(true));
+            }
+        }
+    }
+    declared.extend(pending);
+    declared
+}
+
+fn parse_swift_package(content: &str) -> Vec<DeclaredDependency> {
+    let mut declared = Vec::new();
+
+    for cap in SWIFT_PACKAGE_PATTERN.captures_iter(content) {
+        let args: BTreeMap<&str, &str> = SWIFT_ARG_PATTERN
+            .captures_iter(&cap[1])
+            .map(|arg| (arg.get(1).unwrap().as_str(), arg.get(2).unwrap().as_str()))
+            .collect();
+        let name = args.get("name").map(|n| n.to_string()).or_else(|| {
+            let location = args.get("url").or_else(|| args.get("path"))?;
+            let last = location.trim_end_matches('/').rsplit('/').next()?;
+            Some(last.trim_end_matches(".git").to_string())
+        });
+        let Some(name) = name else {
+            continue;
+        };
+        let version = ["from", "exact", "branch", "revision"]
+            .iter()
+            .find_map(|key| args.get(key).map(|v| v.to_string()));
+        declared
+            .push(DeclaredDependency::new(name, version, false).local(args.contains_key("path")));
+    }
+    declared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Third-party (name, version, dev) entries of a manifest
+    fn declared(path: &str, content: &str) -> Vec<(String, Option<String>, bool)> {
+        let manifest = parse_manifest(path, content).unwrap();
+        manifest
+            .dependencies
+            .into_iter()
+            .chain(manifest.workspace_dependencies)
+            .filter(|d| !d.local)
+            .map(|d| (d.name, d.version, d.dev))
+            .collect()
+    }
+
+    fn dep(name: &str, version: Option<&str>, dev: bool) -> (String, Option<String>, bool) {
+        (name.to_string(), version.map(str::to_string), dev)
+    }
+
+    #[test]
+    fn test_manifest_parsing() {
+        let cargo = r#"
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+serde-json = "1"
+local = { path = "../local" }
+
+[dev-dependencies]
+tempfile = "3"
+"#;
+        assert_eq!(
+            declared("Cargo.toml", cargo),
+            vec![
+                dep("serde", Some("1"), false),
+                dep("serde-json", Some("1"), false),
+                dep("tempfile", Some("3"), true),
+            ]
+        );
+
+        let requirements =
+            "# deps\nrequests[socks]>=2.31 ; python_version > '3.8'\n-r base.txt\nPyYAML\n";
+        assert_eq!(
+            declared("requirements.txt", requirements),
+            vec![
+                dep("requests", Some(">=2.31"), false),
+                dep("PyYAML", None, false)
+            ]
+        );
+
+        let gemfile = "source 'https://rubygems.org'\ngem 'rails', '~> 7.1'\ngroup :development, :test do\n  gem 'rspec-rails'\nend\n";
+        assert_eq!(
+            declared("Gemfile", gemfile),
+            vec![
+                dep("rails", Some("~> 7.1"), false),
+                dep("rspec-rails", None, true),
+            ]
+        );
+
+        let pubspec = "name: app\ndependencies:\n  flutter:\n    sdk: flutter\n  http: ^1.2.0\n  provider:\n    git: https://example.com/provider\ndev_dependencies:\n  test: any\n";
+        assert_eq!(
+            declared("pubspec.yaml", pubspec),
+            vec![
+                dep("http", Some("^1.2.0"), false),
+                dep("provider", None, false),
+                dep("test", Some("any"), true),
+            ]
+        );
+
+        let swift = r#"dependencies: [
+    .package(url: "https://github.com/apple/swift-argument-parser.git", from: "1.3.0"),
+    .package(path: "../Local"),
+]"#;
+        assert_eq!(
+            declared("Package.swift", swift),
+            vec![dep("swift-argument-parser", Some("1.3.0"), false)]
+        );
+
+        let go_mod = "module example.com/app\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgithub.com/lib/pq v1.10.9 // indirect\n\tgolang.org/x/sync v0.6.0\n)\n";
+        let manifest = parse_manifest("go.mod", go_mod).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("example.com/app"));
+        assert_eq!(
+            declared("go.mod", go_mod),
+            vec![
+                dep("github.com/spf13/cobra", Some("v1.8.0"), false),
+                dep("github.com/lib/pq", Some("v1.10.9"), false),
+                dep("golang.org/x/sync", Some("v0.6.0"), false),
+            ]
+        );
+
+        let npm = r#"{"name": "web", "dependencies": {"react": "^18", "@acme/ui": "workspace:*"}, "devDependencies": {"vitest": "1"}}"#;
+        assert_eq!(
+            declared("package.json", npm),
+            vec![
+                dep("react", Some("^18"), false),
+                dep("vitest", Some("1"), true)
+            ]
+        );
+
+        let composer = r#"{"require": {"php": ">=8.1", "ext-json": "*", "symfony/console": "^6.4"}, "require-dev": {"phpunit/phpunit": "^10"}}"#;
+        assert_eq!(
+            declared("composer.json", composer),
+            vec![
+                dep("symfony/console", Some("^6.4"), false),
+                dep("phpunit/phpunit", Some("^10"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pyproject_extras_are_not_dev() {
+        let pyproject = "[project]\nname = \"app\"\ndependencies = [\"httpx\"]\n\n[project.optional-dependencies]\nyaml = [\"PyYAML>=6\"]\n\n[dependency-groups]\ntest = [\"pytest\"]\n";
+        assert_eq!(
+            declared("pyproject.toml", pyproject),
+            vec![
+                dep("httpx", None, false),
+                dep("PyYAML", Some(">=6"), false),
+                dep("pytest", None, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gemfile_conditionals_keep_group_nesting() {
+        let gemfile = "group :test do\n  if ENV['CI']\n    gem 'simplecov'\n  end\n  gem 'rspec'\nend\nunless RUBY_PLATFORM =~ /win/\n  gem 'unicorn'\nend\ngem 'rails'\n";
+        assert_eq!(
+            declared("Gemfile", gemfile),
+            vec![
+                dep("simplecov", None, true),
+                dep("rspec", None, true),
+                dep("unicorn", None, false),
+                dep("rails", None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cargo_package_and_renames() {
+        let cargo = "[package]\nname = \"cli\"\nversion.workspace = true\n\n[dependencies]\ncore = { path = \"../core\", package = \"acme-core\" }\n\n[workspace.dependencies]\nserde = \"1\"\n";
+        let manifest = parse_manifest("crates/cli/Cargo.toml", cargo).unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("cli"));
+        assert!(manifest.inherits_version);
+        assert_eq!(manifest.dependencies[0].package(), "acme-core");
+        assert!(manifest.dependencies[0].local);
+        assert_eq!(manifest.workspace_dependencies[0].name, "serde");
+    }
+}
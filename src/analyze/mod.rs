@@ -1,10 +1,27 @@
+pub mod cache;
+pub mod cycles;
+pub mod dependencies;
 pub mod graph;
 pub mod lang;
+pub mod layers;
+mod layout;
+mod manifest;
 mod memory;
 pub mod module;
 mod parser;
+pub mod references;
+pub mod resolve;
+pub mod workspace;
 
+pub use cache::{AnalysisCache, CachedAnalysis};
+pub use cycles::{module_dependencies, Cycle, CycleReport};
+pub use dependencies::{Dependency, DependencyInventory};
 pub use graph::FileGraph;
+pub use layers::{LayerReport, LayerRules, LayerViolation};
+pub use layout::{ManifestBoundary, ProjectLayout};
 pub use memory::extract_memory_markers;
-pub use module::{detect_modules, path_to_slug, BoundaryType, ModuleInfo};
+pub use module::{detect_modules, path_to_slug, BoundaryType, ModuleInfo, ModuleOptions};
 pub use parser::{extract_imports, extract_symbols};
+pub use references::{Reference, ReferenceIndex, SymbolReferences};
+pub use resolve::ImportResolver;
+pub use workspace::{PackageEcosystem, WorkspacePackage};
@@ -2,11 +2,24 @@
 //!
 //! Detects semantic module boundaries based on:
 //! - Explicit markers: mod.rs, __init__.py, index.{js,ts,tsx,jsx}
+//! - Language units: Go package dirs, Java package dirs, Rust 2018 `foo.rs` + `foo/`
+//! - Build manifests: PSR-4 roots, .csproj projects, pubspec.yaml packages,
+//!   SwiftPM targets (see [`ProjectLayout`])
+//! - Workspace packages: members of Cargo/npm/pnpm/Go/uv workspaces, which also
+//!   tag every module beneath them with the owning package
 //! - Implicit boundaries: directories with 5+ source files
+//! - Configured roots: `[[module]]` entries in agentlens.toml, which override
+//!   the heuristics above for their whole subtree
+//!
+//! Modules nested deeper than the configured depth limit are collapsed into their
+//! ancestor at the limit.
 
-use crate::types::FileEntry;
+use crate::analyze::resolve::parent_dir;
+use crate::analyze::ProjectLayout;
+use crate::types::{FileEntry, Language};
+use globset::{GlobBuilder, GlobSetBuilder};
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
 /// Minimum number of files for implicit module detection
@@ -29,6 +42,8 @@ pub struct ModuleInfo {
     pub parent: Option<String>,
     /// Child module slugs
     pub children: Vec<String>,
+    /// Name of the workspace package this module belongs to
+    pub package: Option<String>,
 }
 
 /// How a module boundary was detected
@@ -42,8 +57,22 @@ pub enum BoundaryType {
     JsModule,
     /// Go: directory with .go files (package)
     GoPackage,
+    /// Java: directory with .java files (package)
+    JavaPackage,
+    /// PHP: PSR-4 autoload root from composer.json
+    PhpPsr4,
+    /// C#: directory containing a .csproj
+    DotNetProject,
+    /// Dart: directory containing a pubspec.yaml
+    DartPackage,
+    /// Swift: SwiftPM target from Package.swift
+    SwiftTarget,
     /// Implicit: directory with 5+ source files
     Implicit,
+    /// Workspace member package (Cargo, npm/pnpm, go.work, uv)
+    WorkspacePackage,
+    /// Declared in agentlens.toml via [[module]]
+    Configured,
     /// Root module (project root)
     Root,
 }
@@ -55,7 +84,14 @@ impl BoundaryType {
             BoundaryType::PythonPackage => "python",
             BoundaryType::JsModule => "js/ts",
             BoundaryType::GoPackage => "go",
+            BoundaryType::JavaPackage => "java",
+            BoundaryType::PhpPsr4 => "php/psr-4",
+            BoundaryType::DotNetProject => "csproj",
+            BoundaryType::DartPackage => "dart",
+            BoundaryType::SwiftTarget => "swift",
             BoundaryType::Implicit => "implicit",
+            BoundaryType::WorkspacePackage => "package",
+            BoundaryType::Configured => "config",
             BoundaryType::Root => "root",
         }
     }
@@ -72,6 +108,7 @@ impl ModuleInfo {
             boundary_type,
             parent: None,
             children: Vec::new(),
+            package: None,
         }
     }
 
@@ -98,66 +135,144 @@ pub fn path_to_slug(path: &str) -> String {
     path.replace(['/', '\\'], "-")
 }
 
+/// Options controlling module detection
+#[derive(Debug, Clone, Default)]
+pub struct ModuleOptions {
+    /// Maximum module nesting depth (0 = unlimited).
+    /// Deeper modules are collapsed into their ancestor at the limit.
+    pub max_depth: usize,
+    /// Explicit module roots from agentlens.toml (paths or globs like "services/*").
+    /// Each matching directory becomes a module owning its whole subtree.
+    pub roots: Vec<String>,
+}
+
 /// Detect all modules in a codebase
-pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
+pub fn detect_modules(
+    files: &[FileEntry],
+    layout: &ProjectLayout,
+    options: &ModuleOptions,
+) -> Vec<ModuleInfo> {
     let mut modules: HashMap<String, ModuleInfo> = HashMap::new();
     let mut file_to_module: HashMap<String, String> = HashMap::new();
 
+    // Configured roots take precedence over every heuristic below
+    let configured = match_configured_roots(files, &options.roots);
+    let in_configured = |path: &str| configured.iter().any(|root| is_within(path, root));
+
     // First pass: detect explicit module boundaries
     for file in files {
         if let Some((module_path, boundary_type)) = detect_explicit_boundary(file) {
+            if in_configured(&module_path) {
+                continue;
+            }
             let entry_point = Some(file.relative_path.clone());
             let module = ModuleInfo::new(&module_path, boundary_type, entry_point);
             modules.insert(module_path.clone(), module);
         }
     }
 
-    // Second pass: assign files to their nearest module
-    for file in files {
-        let dir = get_parent_dir(&file.relative_path);
-        let module_path = find_owning_module(&dir, &modules);
-        file_to_module.insert(file.relative_path.clone(), module_path);
+    // Language units: Go/Java package dirs and Rust 2018 `foo.rs` + `foo/` modules.
+    // Explicit markers win when both apply to the same directory.
+    for (module_path, boundary_type, entry_point) in detect_language_boundaries(files) {
+        if in_configured(&module_path) {
+            continue;
+        }
+        modules
+            .entry(module_path.clone())
+            .or_insert_with(|| ModuleInfo::new(&module_path, boundary_type, entry_point));
     }
 
-    // Third pass: detect implicit modules for directories with 5+ files
+    // Build manifests are authoritative for the directories they declare
+    for boundary in &layout.boundaries {
+        if in_configured(&boundary.path) {
+            continue;
+        }
+        let entry_point = manifest_entry_point(boundary.boundary_type, &boundary.manifest);
+        let module = ModuleInfo::new(&boundary.path, boundary.boundary_type, entry_point);
+        modules.insert(boundary.path.clone(), module);
+    }
+
+    // Workspace packages are top-level units; the project root stays the root module
+    for package in &layout.packages {
+        if package.path.is_empty() || in_configured(&package.path) {
+            continue;
+        }
+        let module = modules.entry(package.path.clone()).or_insert_with(|| {
+            ModuleInfo::new(&package.path, BoundaryType::WorkspacePackage, None)
+        });
+        module.boundary_type = BoundaryType::WorkspacePackage;
+        module.entry_point = Some(package.manifest.clone());
+    }
+
+    // Second pass: detect implicit modules for directories with 5+ files
     let mut dir_file_counts: HashMap<String, Vec<String>> = HashMap::new();
     for file in files {
-        let dir = get_parent_dir(&file.relative_path);
+        let dir = parent_dir(&file.relative_path).to_string();
         dir_file_counts
             .entry(dir)
             .or_default()
             .push(file.relative_path.clone());
     }
 
-    for (dir, dir_files) in &dir_file_counts {
-        // Skip if already an explicit module or if too few files
-        if modules.contains_key(dir) || dir_files.len() < IMPLICIT_MODULE_THRESHOLD {
-            continue;
-        }
+    let mut implicit_dirs: Vec<&String> = dir_file_counts
+        .iter()
+        .filter(|(dir, dir_files)| {
+            // Skip if already an explicit module or if too few files
+            if modules.contains_key(*dir) || dir_files.len() < IMPLICIT_MODULE_THRESHOLD {
+                return false;
+            }
 
-        // Skip if this directory is already covered by an explicit child module
-        let has_explicit_child = modules.keys().any(|m| m.starts_with(dir) && m != dir);
-        if has_explicit_child {
-            continue;
-        }
+            // Skip if this directory is already covered by an explicit child module
+            let has_explicit_child = modules.keys().any(|m| m.starts_with(*dir) && m != *dir);
+            !has_explicit_child && !in_configured(dir)
+        })
+        .map(|(dir, _)| dir)
+        .collect();
+    implicit_dirs.sort();
 
-        // Create implicit module
+    for dir in implicit_dirs {
         let module = ModuleInfo::new(dir, BoundaryType::Implicit, None);
         modules.insert(dir.clone(), module);
+    }
 
-        // Reassign files to this new module
-        for file_path in dir_files {
-            file_to_module.insert(file_path.clone(), dir.clone());
-        }
+    // Third pass: collapse modules deeper than the depth limit
+    if options.max_depth > 0 {
+        collapse_deep_modules(&mut modules, options.max_depth);
+    }
+
+    for root in &configured {
+        let module = ModuleInfo::new(root, BoundaryType::Configured, None);
+        modules.insert(root.clone(), module);
+    }
+
+    // Entry points living outside their module dir (Rust `foo.rs` for `foo/`)
+    // belong to the module they declare
+    let detached_entries: HashMap<String, String> = modules
+        .values()
+        .filter_map(|m| {
+            let entry = m.entry_point.as_ref()?;
+            (!is_within(parent_dir(entry), &m.path)).then(|| (entry.clone(), m.path.clone()))
+        })
+        .collect();
+
+    // Fourth pass: assign files to their nearest module
+    for file in files {
+        let module_path = match detached_entries.get(&file.relative_path) {
+            Some(module_path) => module_path.clone(),
+            None => find_owning_module(parent_dir(&file.relative_path), &modules),
+        };
+        file_to_module.insert(file.relative_path.clone(), module_path);
     }
 
-    // Fourth pass: add files to their modules
     for (file_path, module_path) in &file_to_module {
         if let Some(module) = modules.get_mut(module_path) {
             module.add_file(file_path.clone());
         }
     }
 
+    // Drop modules left without files (e.g. a configured glob matched an empty dir)
+    modules.retain(|_, m| !m.files.is_empty());
+
     // Create root module for any orphaned files
     let orphan_files: Vec<String> = files
         .iter()
@@ -195,10 +310,11 @@ pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
         }
     }
 
-    // Sort children alphabetically
+    // Sort children alphabetically and tag modules with their package
     for module in modules.values_mut() {
         module.children.sort();
         module.files.sort();
+        module.package = layout.package_for(&module.path).map(|p| p.name.clone());
     }
 
     // Convert to sorted vec
@@ -208,6 +324,148 @@ pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
     result
 }
 
+/// Match configured module roots against every directory that contains files
+fn match_configured_roots(files: &[FileEntry], patterns: &[String]) -> Vec<String> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        if let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+    let globs = match builder.build() {
+        Ok(set) => set,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut dirs: BTreeSet<String> = BTreeSet::new();
+    for file in files {
+        let mut current = Path::new(&file.relative_path).parent();
+        while let Some(dir) = current {
+            let dir_str = dir.to_str().unwrap_or("");
+            if dir_str.is_empty() || !dirs.insert(dir_str.to_string()) {
+                break;
+            }
+            current = dir.parent();
+        }
+    }
+
+    dirs.into_iter().filter(|d| globs.is_match(d)).collect()
+}
+
+/// Check if `path` is `root` or nested under it
+fn is_within(path: &str, root: &str) -> bool {
+    path == root
+        || path
+            .strip_prefix(root)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Nesting depth of a module in the module tree (top-level modules = 1)
+fn module_depth(path: &str, all_paths: &[String]) -> usize {
+    let mut depth = 1;
+    let mut current = path.to_string();
+    while let Some(parent) = find_parent_module(&current, all_paths) {
+        depth += 1;
+        current = parent;
+    }
+    depth
+}
+
+/// Drop modules nested deeper than `max_depth` so their files fall through
+/// to the ancestor module at the limit
+fn collapse_deep_modules(modules: &mut HashMap<String, ModuleInfo>, max_depth: usize) {
+    let all_paths: Vec<String> = modules.keys().filter(|p| !p.is_empty()).cloned().collect();
+
+    modules.retain(|path, _| path.is_empty() || module_depth(path, &all_paths) <= max_depth);
+}
+
+/// Detect module boundaries implied by a language's own unit of organisation
+fn detect_language_boundaries(files: &[FileEntry]) -> Vec<(String, BoundaryType, Option<String>)> {
+    let mut dirs_by_language: HashMap<(String, Language), Vec<&str>> = HashMap::new();
+    for file in files {
+        if matches!(
+            file.language,
+            Language::Go | Language::Java | Language::Kotlin
+        ) {
+            // Kotlin shares Java's package directories
+            let language = match file.language {
+                Language::Kotlin => Language::Java,
+                language => language,
+            };
+            dirs_by_language
+                .entry((parent_dir(&file.relative_path).to_string(), language))
+                .or_default()
+                .push(&file.relative_path);
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    for ((dir, language), dir_files) in dirs_by_language {
+        if dir.is_empty() {
+            continue;
+        }
+        let entry_names: Vec<String> = match language {
+            Language::Go => {
+                let dir_name = dir.rsplit('/').next().unwrap_or(&dir);
+                vec![
+                    "doc.go".to_string(),
+                    "main.go".to_string(),
+                    format!("{}.go", dir_name),
+                ]
+            }
+            _ => vec!["package-info.java".to_string()],
+        };
+        let entry_point = entry_names.iter().find_map(|name| {
+            dir_files
+                .iter()
+                .find(|f| f.ends_with(&format!("/{}", name)))
+                .map(|f| f.to_string())
+        });
+        let boundary_type = match language {
+            Language::Go => BoundaryType::GoPackage,
+            _ => BoundaryType::JavaPackage,
+        };
+        boundaries.push((dir, boundary_type, entry_point));
+    }
+
+    // Rust 2018: `x/foo.rs` declares the module whose children live in `x/foo/`
+    let rust_dirs: HashSet<String> = files
+        .iter()
+        .filter(|f| f.language == Language::Rust)
+        .map(|f| parent_dir(&f.relative_path).to_string())
+        .collect();
+    for file in files.iter().filter(|f| f.language == Language::Rust) {
+        let Some(module_path) = file.relative_path.strip_suffix(".rs") else {
+            continue;
+        };
+        let has_children = rust_dirs.iter().any(|d| is_within(d, module_path));
+        if has_children {
+            boundaries.push((
+                module_path.to_string(),
+                BoundaryType::RustModule,
+                Some(file.relative_path.clone()),
+            ));
+        }
+    }
+
+    boundaries.sort_by(|a, b| a.0.cmp(&b.0));
+    boundaries
+}
+
+/// Pick the entry point for a manifest-declared module
+fn manifest_entry_point(boundary_type: BoundaryType, manifest: &str) -> Option<String> {
+    match boundary_type {
+        // The project/package file itself is the best description of these units
+        BoundaryType::DotNetProject | BoundaryType::DartPackage => Some(manifest.to_string()),
+        _ => None,
+    }
+}
+
 /// Detect if a file represents an explicit module boundary
 fn detect_explicit_boundary(file: &FileEntry) -> Option<(String, BoundaryType)> {
     let filename = Path::new(&file.relative_path)
@@ -215,7 +473,7 @@ fn detect_explicit_boundary(file: &FileEntry) -> Option<(String, BoundaryType)>
         .and_then(|n| n.to_str())
         .unwrap_or("");
 
-    let dir = get_parent_dir(&file.relative_path);
+    let dir = parent_dir(&file.relative_path).to_string();
 
     match filename {
         // Rust: mod.rs marks a module, lib.rs marks crate root
@@ -241,15 +499,6 @@ fn detect_explicit_boundary(file: &FileEntry) -> Option<(String, BoundaryType)>
     }
 }
 
-/// Get the parent directory of a file path
-fn get_parent_dir(path: &str) -> String {
-    Path::new(path)
-        .parent()
-        .and_then(|p| p.to_str())
-        .unwrap_or("")
-        .to_string()
-}
-
 /// Find which module owns a directory (walks up the tree)
 fn find_owning_module(dir: &str, modules: &HashMap<String, ModuleInfo>) -> String {
     let mut current = dir.to_string();
@@ -312,7 +561,7 @@ pub fn get_module_files<'a>(
         .iter()
         .filter(|f| {
             // File must be in this module's path
-            let file_dir = get_parent_dir(&f.relative_path);
+            let file_dir = parent_dir(&f.relative_path);
             if !file_dir.starts_with(&module.path) && file_dir != module.path {
                 return false;
             }
@@ -335,11 +584,12 @@ mod tests {
         FileEntry {
             path: PathBuf::from(relative_path),
             relative_path: relative_path.to_string(),
-            extension: relative_path.split('.').last().map(|s| s.to_string()),
+            extension: relative_path.split('.').next_back().map(|s| s.to_string()),
             language: Language::Rust,
             size_bytes: 100,
             line_count: 50,
             is_large: false,
+            content_hash: String::new(),
         }
     }
 
@@ -362,7 +612,7 @@ mod tests {
             make_file("src/analyze/lang/rust.rs"),
         ];
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         // Should detect: src (from lib.rs), src/analyze (from mod.rs), src/analyze/lang (from mod.rs)
         assert!(modules.iter().any(|m| m.path == "src"));
@@ -395,7 +645,7 @@ mod tests {
             })
             .collect();
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         assert!(modules.iter().any(|m| m.path == "mypackage"));
         assert!(modules.iter().any(|m| m.path == "mypackage/utils"));
@@ -415,7 +665,7 @@ mod tests {
             make_file("src/utils/format.js"),
         ];
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         assert!(modules.iter().any(|m| m.path == "src"));
         assert!(modules.iter().any(|m| m.path == "src/components"));
@@ -432,7 +682,7 @@ mod tests {
             .map(|i| make_file(&format!("src/services/service{}.rs", i)))
             .collect();
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         let services = modules.iter().find(|m| m.path == "src/services");
         assert!(services.is_some());
@@ -446,7 +696,7 @@ mod tests {
             .map(|i| make_file(&format!("src/small/file{}.rs", i)))
             .collect();
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         let small = modules.iter().find(|m| m.path == "src/small");
         assert!(small.is_none());
@@ -460,7 +710,7 @@ mod tests {
             make_file("src/analyze/lang/mod.rs"),
         ];
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         let lang_module = modules
             .iter()
@@ -483,7 +733,7 @@ mod tests {
             make_file("src/analyze/parser.rs"),
         ];
 
-        let modules = detect_modules(&files);
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
 
         let analyze = modules.iter().find(|m| m.path == "src/analyze").unwrap();
         assert!(analyze.files.contains(&"src/analyze/mod.rs".to_string()));
@@ -493,4 +743,185 @@ mod tests {
         assert!(src.files.contains(&"src/lib.rs".to_string()));
         assert!(src.files.contains(&"src/main.rs".to_string()));
     }
+
+    #[test]
+    fn test_module_depth_collapses_deep_modules() {
+        let files = vec![
+            make_file("src/lib.rs"),
+            make_file("src/a/mod.rs"),
+            make_file("src/a/b/mod.rs"),
+            make_file("src/a/b/c/mod.rs"),
+            make_file("src/a/b/c/d/mod.rs"),
+            make_file("src/a/b/c/d/leaf.rs"),
+        ];
+
+        let options = ModuleOptions {
+            max_depth: 3,
+            ..Default::default()
+        };
+        let modules = detect_modules(&files, &ProjectLayout::default(), &options);
+
+        assert!(!modules.iter().any(|m| m.path == "src/a/b/c"));
+        let abc = modules.iter().find(|m| m.path == "src/a/b").unwrap();
+        assert!(abc.files.contains(&"src/a/b/c/d/leaf.rs".to_string()));
+        assert!(abc.files.contains(&"src/a/b/c/mod.rs".to_string()));
+    }
+
+    #[test]
+    fn test_configured_roots_override_heuristics() {
+        let files = vec![
+            make_file("services/billing/src/lib.rs"),
+            make_file("services/billing/src/api/mod.rs"),
+            make_file("services/auth/main.rs"),
+            make_file("tools/gen.rs"),
+        ];
+
+        let options = ModuleOptions {
+            max_depth: 0,
+            roots: vec!["services/*".to_string()],
+        };
+        let modules = detect_modules(&files, &ProjectLayout::default(), &options);
+
+        let billing = modules
+            .iter()
+            .find(|m| m.path == "services/billing")
+            .unwrap();
+        assert_eq!(billing.boundary_type, BoundaryType::Configured);
+        assert_eq!(billing.files.len(), 2);
+        assert!(modules.iter().any(|m| m.path == "services/auth"));
+        assert!(!modules.iter().any(|m| m.path == "services/billing/src/api"));
+
+        let root = modules.iter().find(|m| m.path.is_empty()).unwrap();
+        assert_eq!(root.files, vec!["tools/gen.rs".to_string()]);
+    }
+
+    fn make_lang_file(relative_path: &str, language: Language) -> FileEntry {
+        let mut file = make_file(relative_path);
+        file.language = language;
+        file
+    }
+
+    #[test]
+    fn test_detect_go_and_java_packages() {
+        let files = vec![
+            make_lang_file("cmd/server/main.go", Language::Go),
+            make_lang_file("internal/store/store.go", Language::Go),
+            make_lang_file("internal/store/query.go", Language::Go),
+            make_lang_file("src/main/java/com/acme/App.java", Language::Java),
+            make_lang_file("src/main/java/com/acme/package-info.java", Language::Java),
+        ];
+
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
+
+        let store = modules.iter().find(|m| m.path == "internal/store").unwrap();
+        assert_eq!(store.boundary_type, BoundaryType::GoPackage);
+        assert_eq!(
+            store.entry_point.as_deref(),
+            Some("internal/store/store.go")
+        );
+
+        let server = modules.iter().find(|m| m.path == "cmd/server").unwrap();
+        assert_eq!(server.entry_point.as_deref(), Some("cmd/server/main.go"));
+
+        let acme = modules
+            .iter()
+            .find(|m| m.path == "src/main/java/com/acme")
+            .unwrap();
+        assert_eq!(acme.boundary_type, BoundaryType::JavaPackage);
+        assert_eq!(acme.files.len(), 2);
+    }
+
+    #[test]
+    fn test_detect_rust_2018_modules() {
+        let files = vec![
+            make_file("src/lib.rs"),
+            make_file("src/net.rs"),
+            make_file("src/net/tcp.rs"),
+            make_file("src/net/udp.rs"),
+            make_file("src/util.rs"),
+        ];
+
+        let modules = detect_modules(&files, &ProjectLayout::default(), &ModuleOptions::default());
+
+        let net = modules.iter().find(|m| m.path == "src/net").unwrap();
+        assert_eq!(net.boundary_type, BoundaryType::RustModule);
+        assert_eq!(net.entry_point.as_deref(), Some("src/net.rs"));
+        assert_eq!(
+            net.files,
+            vec!["src/net.rs", "src/net/tcp.rs", "src/net/udp.rs"]
+        );
+        assert!(!modules.iter().any(|m| m.path == "src/util"));
+
+        let src = modules.iter().find(|m| m.path == "src").unwrap();
+        assert_eq!(src.files, vec!["src/lib.rs", "src/util.rs"]);
+    }
+
+    #[test]
+    fn test_manifest_boundaries() {
+        let mut layout = ProjectLayout::default();
+        layout.add_manifest("Billing/Billing.csproj", "<Project />");
+        layout.add_manifest(
+            "composer.json",
+            r#"{"autoload": {"psr-4": {"App\\": "app/"}}}"#,
+        );
+
+        let files = vec![
+            make_lang_file("Billing/Invoice.cs", Language::CSharp),
+            make_lang_file("Billing/Models/Line.cs", Language::CSharp),
+            make_lang_file("app/Http/Kernel.php", Language::Php),
+        ];
+
+        let modules = detect_modules(&files, &layout, &ModuleOptions::default());
+
+        let billing = modules.iter().find(|m| m.path == "Billing").unwrap();
+        assert_eq!(billing.boundary_type, BoundaryType::DotNetProject);
+        assert_eq!(
+            billing.entry_point.as_deref(),
+            Some("Billing/Billing.csproj")
+        );
+        assert_eq!(billing.files.len(), 2);
+
+        let app = modules.iter().find(|m| m.path == "app").unwrap();
+        assert_eq!(app.boundary_type, BoundaryType::PhpPsr4);
+        assert_eq!(app.boundary_type.as_str(), "php/psr-4");
+    }
+
+    #[test]
+    fn test_workspace_packages_tag_modules() {
+        let mut manifests = std::collections::BTreeMap::new();
+        manifests.insert(
+            "Cargo.toml".to_string(),
+            "[workspace]\nmembers = [\"crates/*\"]\n".to_string(),
+        );
+        manifests.insert(
+            "crates/core/Cargo.toml".to_string(),
+            "[package]\nname = \"core\"\nversion = \"0.1.0\"\n".to_string(),
+        );
+        let layout = ProjectLayout::from_contents(&manifests);
+
+        let files = vec![
+            make_file("crates/core/build.rs"),
+            make_file("crates/core/src/lib.rs"),
+            make_file("crates/core/src/parse/mod.rs"),
+            make_file("xtask.rs"),
+        ];
+
+        let modules = detect_modules(&files, &layout, &ModuleOptions::default());
+
+        let package = modules.iter().find(|m| m.path == "crates/core").unwrap();
+        assert_eq!(package.boundary_type, BoundaryType::WorkspacePackage);
+        assert_eq!(
+            package.files,
+            vec!["crates/core/build.rs", "crates/core/src/lib.rs"]
+        );
+
+        let parse = modules
+            .iter()
+            .find(|m| m.path == "crates/core/src/parse")
+            .unwrap();
+        assert_eq!(parse.package.as_deref(), Some("core"));
+
+        let root = modules.iter().find(|m| m.path.is_empty()).unwrap();
+        assert_eq!(root.package, None);
+    }
 }
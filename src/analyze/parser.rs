@@ -1,11 +1,15 @@
-use crate::analyze::lang::get_parser;
+use crate::analyze::lang::{assign_parents, attach_doc_comments, attach_signatures, get_parser};
 use crate::types::{FileEntry, Symbol};
 
 pub fn extract_symbols(file: &FileEntry, content: &str) -> Vec<Symbol> {
-    match get_parser(file.language) {
+    let mut symbols = match get_parser(file.language) {
         Some(parser) => parser.parse_symbols(content),
         None => Vec::new(),
-    }
+    };
+    attach_signatures(file.language, content, &mut symbols);
+    attach_doc_comments(file.language, content, &mut symbols);
+    assign_parents(&mut symbols);
+    symbols
 }
 
 pub fn extract_imports(file: &FileEntry, content: &str) -> Vec<String> {
@@ -0,0 +1,423 @@
+//! Symbol cross-reference index.
+//!
+//! Records which files mention each exported top-level symbol by name, so the
+//! blast radius of changing it can be read without searching the codebase.
+//! Only files of the same language family are searched (JavaScript with
+//! TypeScript, Java with Kotlin, C with C++). Methods are left out: names like `new` or `get`
+//! are too common to attribute by name alone.
+//!
+//! A name exported by more than one file is ambiguous, so a mention of it is
+//! attributed only to the definitions the mentioning file imports. A file
+//! that exports the name itself is taken to mean its own definition.
+//!
+//! Comments and string literals are skipped, so prose and log messages that
+//! happen to contain a name don't count as uses.
+
+use crate::analyze::FileGraph;
+use crate::scan::SourceFile;
+use crate::types::{Language, Symbol, SymbolKind, Visibility};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+static IDENTIFIER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z_$][A-Za-z0-9_$]*").unwrap());
+
+/// Mentions of a symbol in one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    pub file: String,
+    /// 1-based lines mentioning the symbol
+    pub lines: Vec<usize>,
+}
+
+/// An exported symbol and the other files referencing it
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolReferences {
+    pub symbol: String,
+    pub kind: SymbolKind,
+    /// File defining the symbol
+    pub file: String,
+    pub line: usize,
+    /// Sorted by file
+    pub used_by: Vec<Reference>,
+}
+
+/// Cross-references for every exported top-level symbol
+#[derive(Debug, Default)]
+pub struct ReferenceIndex {
+    /// Sorted by defining file, then line
+    symbols: Vec<SymbolReferences>,
+}
+
+impl ReferenceIndex {
+    pub fn build(
+        sources: &[SourceFile],
+        symbols: &HashMap<String, Vec<Symbol>>,
+        graph: &FileGraph,
+    ) -> Self {
+        let mut index = Self::default();
+        // Definitions of each exported name, by language family
+        let mut definitions: HashMap<(u8, &str), Vec<usize>> = HashMap::new();
+
+        for source in sources {
+            let file = &source.entry.relative_path;
+            let family = language_family(source.entry.language);
+            for symbol in symbols.get(file).into_iter().flatten() {
+                if !is_exported(symbol) {
+                    continue;
+                }
+                definitions
+                    .entry((family, symbol.name.as_str()))
+                    .or_default()
+                    .push(index.symbols.len());
+                index.symbols.push(SymbolReferences {
+                    symbol: symbol.name.clone(),
+                    kind: symbol.kind,
+                    file: file.clone(),
+                    line: symbol.line_range.start,
+                    used_by: Vec::new(),
+                });
+            }
+        }
+
+        for source in sources {
+            let file = &source.entry.relative_path;
+            let family = language_family(source.entry.language);
+            let imports: HashSet<&str> = graph
+                .imports
+                .get(file)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+
+            let syntax = Syntax::of(source.entry.language);
+            let mut state = StripState::default();
+            let mut mentions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+            for (number, line) in source.content.lines().enumerate() {
+                let code = syntax.strip(line, &mut state);
+                for name in IDENTIFIER_PATTERN.find_iter(&code).map(|m| m.as_str()) {
+                    if definitions.contains_key(&(family, name)) {
+                        let lines = mentions.entry(name.to_string()).or_default();
+                        if lines.last() != Some(&(number + 1)) {
+                            lines.push(number + 1);
+                        }
+                    }
+                }
+            }
+
+            for (name, lines) in mentions {
+                let defined = &definitions[&(family, name.as_str())];
+                if defined.iter().any(|&i| &index.symbols[i].file == file) {
+                    continue;
+                }
+                let targets: Vec<usize> = if defined.len() == 1 {
+                    defined.clone()
+                } else {
+                    defined
+                        .iter()
+                        .copied()
+                        .filter(|&i| imports.contains(index.symbols[i].file.as_str()))
+                        .collect()
+                };
+                for target in targets {
+                    index.symbols[target].used_by.push(Reference {
+                        file: file.clone(),
+                        lines: lines.clone(),
+                    });
+                }
+            }
+        }
+
+        for symbol in &mut index.symbols {
+            symbol.used_by.sort_by(|a, b| a.file.cmp(&b.file));
+        }
+        index
+            .symbols
+            .sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        index
+    }
+
+    /// Every exported symbol, including those nothing references
+    pub fn symbols(&self) -> &[SymbolReferences] {
+        &self.symbols
+    }
+
+    /// Exported symbols defined in `file`
+    pub fn for_file<'a>(&'a self, file: &'a str) -> impl Iterator<Item = &'a SymbolReferences> {
+        self.symbols.iter().filter(move |s| s.file == file)
+    }
+
+    /// Definitions of `name`, optionally only the one in `file`
+    pub fn find(&self, name: &str, file: Option<&str>) -> Vec<&SymbolReferences> {
+        self.symbols
+            .iter()
+            .filter(|s| s.symbol == name && file.is_none_or(|f| s.file == f))
+            .collect()
+    }
+}
+
+/// Symbols other files can refer to by name
+fn is_exported(symbol: &Symbol) -> bool {
+    symbol.parent.is_none()
+        && symbol.name.len() > 1
+        && !matches!(symbol.kind, SymbolKind::Method | SymbolKind::Module)
+        && matches!(symbol.visibility, Visibility::Public | Visibility::Internal)
+}
+
+/// Comment and string syntax of a language
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comments: bool,
+    /// Whether `'` opens a string (Rust lifetimes and Swift don't)
+    single_quote_strings: bool,
+    /// Rust `'x'` character literals, told apart from lifetimes
+    char_literals: bool,
+    /// Raw strings without escapes: Rust `r#"..."#`, Swift `#"..."#`
+    raw_strings: bool,
+}
+
+/// A block comment or string still open at the end of a line
+#[derive(Default)]
+struct StripState {
+    in_block_comment: bool,
+    quote: Option<char>,
+    /// `#`s closing the raw string in progress
+    raw_hashes: Option<usize>,
+}
+
+impl Syntax {
+    fn of(language: Language) -> Self {
+        let (line_comments, block_comments): (&'static [&'static str], bool) = match language {
+            Language::Python | Language::Ruby => (&["#"], false),
+            Language::Php => (&["//", "#"], true),
+            _ => (&["//"], true),
+        };
+        Self {
+            line_comments,
+            block_comments,
+            single_quote_strings: !matches!(language, Language::Rust | Language::Swift),
+            char_literals: language == Language::Rust,
+            raw_strings: matches!(language, Language::Rust | Language::Swift),
+        }
+    }
+
+    /// `line` without comments and string contents
+    fn strip(&self, line: &str, state: &mut StripState) -> String {
+        let mut out = String::with_capacity(line.len());
+        // Byte offset up to which input was consumed by a multi-character token
+        let mut skip = 0;
+        let mut prev: Option<char> = None;
+
+        for (i, c) in line.char_indices() {
+            if i < skip {
+                continue;
+            }
+            let rest = &line[i..];
+            let before = prev.replace(c);
+
+            if state.in_block_comment {
+                if rest.starts_with("*/") {
+                    state.in_block_comment = false;
+                    skip = i + 2;
+                }
+                continue;
+            }
+            if let Some(hashes) = state.raw_hashes {
+                if rest.starts_with('"')
+                    && rest[1..].bytes().take_while(|b| *b == b'#').count() >= hashes
+                {
+                    state.raw_hashes = None;
+                    skip = i + 1 + hashes;
+                    out.push(' ');
+                }
+                continue;
+            }
+            if let Some(q) = state.quote {
+                if c == '\\' {
+                    skip = i + 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+                } else if c == q {
+                    state.quote = None;
+                    out.push(' ');
+                }
+                continue;
+            }
+            // `#[...]` is an attribute in PHP, not a comment
+            if self
+                .line_comments
+                .iter()
+                .any(|p| rest.starts_with(p) && !rest.starts_with("#["))
+            {
+                break;
+            }
+            let word_start = !before.is_some_and(|b| b.is_alphanumeric() || b == '_');
+            if self.raw_strings && word_start {
+                if let Some((hashes, len)) = raw_string_open(rest) {
+                    state.raw_hashes = Some(hashes);
+                    skip = i + len;
+                    continue;
+                }
+            }
+            match c {
+                '/' if self.block_comments && rest.starts_with("/*") => {
+                    state.in_block_comment = true;
+                    skip = i + 2;
+                }
+                '"' => state.quote = Some(c),
+                '\'' if self.single_quote_strings => state.quote = Some(c),
+                '\'' if self.char_literals => match char_literal_len(rest) {
+                    Some(len) => {
+                        skip = i + len;
+                        out.push(' ');
+                    }
+                    // A lifetime or label
+                    None => out.push(c),
+                },
+                _ => out.push(c),
+            }
+        }
+
+        out
+    }
+}
+
+/// Number of closing `#`s and opener length if `rest` starts a raw string
+/// (`r"`, `r#"`, `br#"`, Swift `#"`)
+fn raw_string_open(rest: &str) -> Option<(usize, usize)> {
+    let body = rest
+        .strip_prefix("br")
+        .or_else(|| rest.strip_prefix('r'))
+        .unwrap_or(rest);
+    let hashes = body.bytes().take_while(|b| *b == b'#').count();
+    let prefixed = body.len() != rest.len();
+    (body[hashes..].starts_with('"') && (prefixed || hashes > 0))
+        .then_some((hashes, rest.len() - body.len() + hashes + 1))
+}
+
+/// Length of the Rust character literal starting `rest` (`'x'`, `'\''`,
+/// `'"'`, `'\u{1F600}'`), or `None` for a lifetime such as `'a`
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        chars.next()?;
+        chars
+            .take_while(|&(i, _)| i <= 12)
+            .find(|&(_, c)| c == '\'')
+            .map(|(i, _)| i + 1)
+    } else {
+        let (i, close) = chars.next()?;
+        (close == '\'').then_some(i + 1)
+    }
+}
+
+/// Languages whose files can name each other's symbols share a family
+fn language_family(language: Language) -> u8 {
+    match language {
+        Language::JavaScript | Language::TypeScript => 0,
+        Language::C | Language::Cpp => 1,
+        Language::Java | Language::Kotlin => 2,
+        other => other as u8 + 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::source;
+
+    fn function(name: &str, line: usize) -> Symbol {
+        Symbol::new(
+            SymbolKind::Function,
+            name.to_string(),
+            line,
+            Visibility::Public,
+        )
+    }
+
+    #[test]
+    fn test_references_across_files() {
+        let sources = vec![
+            source("src/graph.rs", "pub fn build_graph() {}\npub fn helper() {}\n"),
+            source("src/other.rs", "pub fn helper() {}\n"),
+            source(
+                "src/main.rs",
+                "use crate::graph::build_graph;\nfn main() {\n    build_graph();\n    helper();\n}\n",
+            ),
+            source("src/lib.rs", "use crate::graph;\nfn run() { graph::helper(); }\n"),
+            source("web/app.ts", "build_graph();\n"),
+        ];
+        let symbols = HashMap::from([
+            (
+                "src/graph.rs".to_string(),
+                vec![function("build_graph", 1), function("helper", 2)],
+            ),
+            ("src/other.rs".to_string(), vec![function("helper", 1)]),
+        ]);
+        let mut graph = FileGraph::new();
+        graph.add_file("src/main.rs", vec!["src/graph.rs".to_string()]);
+        graph.add_file("src/lib.rs", vec!["src/graph.rs".to_string()]);
+
+        let index = ReferenceIndex::build(&sources, &symbols, &graph);
+
+        let build = index.find("build_graph", None);
+        assert_eq!(build.len(), 1);
+        assert_eq!(
+            build[0].used_by,
+            vec![Reference {
+                file: "src/main.rs".to_string(),
+                lines: vec![1, 3],
+            }]
+        );
+
+        // `helper` is ambiguous, so only importers of the defining file count
+        let helper = index.find("helper", Some("src/graph.rs"));
+        let users: Vec<&str> = helper[0].used_by.iter().map(|r| r.file.as_str()).collect();
+        assert_eq!(users, vec!["src/lib.rs", "src/main.rs"]);
+        assert!(index.find("helper", Some("src/other.rs"))[0]
+            .used_by
+            .is_empty());
+        assert_eq!(index.for_file("src/graph.rs").count(), 2);
+    }
+
+    #[test]
+    fn test_comments_and_strings_are_not_references() {
+        let sources = vec![
+            source("src/graph.rs", "pub fn build_graph() {}\n"),
+            source(
+                "src/docs.rs",
+                "// build_graph is called elsewhere\n/* see\n   build_graph */\nfn log() { println!(\"build_graph done\"); }\n",
+            ),
+            source("src/main.rs", "fn main() { let s = \"x\"; build_graph(); }\n"),
+            source(
+                "src/quote.rs",
+                "fn f<'a>(s: &'a str) {\n    let q = '\"';\n    let e = '\\'';\n    let r = r#\"build_graph \" x\"#;\n    build_graph();\n}\n",
+            ),
+            source("app/graph.py", "def load():\n    pass\n"),
+            source("app/run.py", "# load\n\"\"\"\nload\n\"\"\"\nx = 'load'\n"),
+        ];
+        let symbols = HashMap::from([
+            ("src/graph.rs".to_string(), vec![function("build_graph", 1)]),
+            ("app/graph.py".to_string(), vec![function("load", 1)]),
+        ]);
+
+        let index = ReferenceIndex::build(&sources, &symbols, &FileGraph::new());
+
+        assert_eq!(
+            index.find("build_graph", None)[0].used_by,
+            vec![
+                Reference {
+                    file: "src/main.rs".to_string(),
+                    lines: vec![1],
+                },
+                Reference {
+                    file: "src/quote.rs".to_string(),
+                    lines: vec![5],
+                },
+            ]
+        );
+        assert!(index.find("load", None)[0].used_by.is_empty());
+    }
+}
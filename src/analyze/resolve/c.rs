@@ -0,0 +1,288 @@
+//! C/C++ include resolution.
+//!
+//! Quoted includes are looked up next to the including file, then in the
+//! include directories; angle-bracket includes only in the include
+//! directories, and are external (system or third-party headers) when not
+//! found. Include directories come from `-I`/`-iquote`/`-isystem` flags in
+//! `compile_commands.json` and the `include_dirs` config, followed by every
+//! project directory named `include` and the project root.
+//!
+//! Each header is also paired with its implementation file (`foo.h` with
+//! `foo.c`/`foo.cpp` beside it, or under the `src/` matching an `include/`
+//! directory), so including a header links the code behind it. C has no
+//! module marker of its own; a library's `include/` and `src/` belong to the
+//! component directory holding both, so pairing never leaves that directory.
+
+use super::{join, parent_dir, Target};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path};
+
+pub(super) const COMPILE_COMMANDS: &str = "compile_commands.json";
+
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx", "m", "mm"];
+
+/// Compiler flags naming an include directory
+const INCLUDE_FLAGS: &[&str] = &["-I", "-iquote", "-isystem", "-idirafter"];
+
+#[derive(Debug, Default)]
+pub(super) struct CResolver {
+    /// Include directories, explicit ones first
+    include_dirs: Vec<String>,
+    /// Implementation file of each header that has one
+    implementations: HashMap<String, String>,
+}
+
+impl CResolver {
+    pub(super) fn new(files: &HashSet<String>) -> Self {
+        let mut include_dirs: Vec<String> = Vec::new();
+        let mut implementations = HashMap::new();
+
+        let mut headers: Vec<&String> = files.iter().filter(|f| is_header(f)).collect();
+        headers.sort();
+        for header in headers {
+            let dir = parent_dir(header);
+            let segments: Vec<&str> = dir.split('/').collect();
+            if let Some(pos) = segments.iter().rposition(|s| *s == "include") {
+                let include_dir = segments[..=pos].join("/");
+                if !include_dirs.contains(&include_dir) {
+                    include_dirs.push(include_dir);
+                }
+            }
+            if let Some(source) = implementation_candidates(header)
+                .into_iter()
+                .find(|c| files.contains(c))
+            {
+                implementations.insert(header.clone(), source);
+            }
+        }
+        include_dirs.push(String::new());
+
+        Self {
+            include_dirs,
+            implementations,
+        }
+    }
+
+    /// Search `dirs` before the discovered include directories
+    pub(super) fn add_include_dirs(&mut self, dirs: &[String]) {
+        let mut explicit: Vec<String> = dirs
+            .iter()
+            .filter_map(|dir| join("", dir))
+            .filter(|dir| !dir.is_empty())
+            .collect();
+        explicit.dedup();
+        self.include_dirs.retain(|dir| !explicit.contains(dir));
+        explicit.append(&mut self.include_dirs);
+        self.include_dirs = explicit;
+    }
+
+    pub(super) fn resolve(
+        &self,
+        from: &str,
+        import: &str,
+        files: &HashSet<String>,
+    ) -> Option<Target> {
+        if let Some(system) = import.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            return Some(
+                self.search(system, files)
+                    .map(Target::File)
+                    .unwrap_or_else(|| Target::External(system.to_string())),
+            );
+        }
+
+        join(parent_dir(from), import)
+            .filter(|path| files.contains(path))
+            .or_else(|| self.search(import, files))
+            .map(Target::File)
+    }
+
+    /// The implementation file paired with `header`
+    pub(super) fn implementation(&self, header: &str) -> Option<&str> {
+        self.implementations.get(header).map(String::as_str)
+    }
+
+    fn search(&self, include: &str, files: &HashSet<String>) -> Option<String> {
+        self.include_dirs
+            .iter()
+            .filter_map(|dir| join(dir, include))
+            .find(|path| files.contains(path))
+    }
+}
+
+fn is_header(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, ext)| HEADER_EXTENSIONS.contains(&ext))
+}
+
+/// Where `header`'s implementation may live, most likely first
+fn implementation_candidates(header: &str) -> Vec<String> {
+    let name = header.rsplit('/').next().unwrap_or(header);
+    let Some((stem, _)) = name.rsplit_once('.') else {
+        return Vec::new();
+    };
+    let dir = parent_dir(header);
+
+    let mut dirs = vec![dir.to_string()];
+    let segments: Vec<&str> = dir.split('/').collect();
+    if let Some(pos) = segments.iter().rposition(|s| *s == "include") {
+        // include/lib/foo.h -> src/lib/foo.c, then src/foo.c, both under the
+        // component that owns this `include/`
+        let mut mirrored = segments.clone();
+        mirrored[pos] = "src";
+        dirs.push(mirrored.join("/"));
+        let mut flat = segments[..pos].to_vec();
+        flat.push("src");
+        dirs.push(flat.join("/"));
+    }
+
+    dirs.iter()
+        .flat_map(|dir| {
+            SOURCE_EXTENSIONS
+                .iter()
+                .filter_map(move |ext| join(dir, &format!("{}.{}", stem, ext)))
+        })
+        .collect()
+}
+
+/// Include directories passed to the compiler in a `compile_commands.json`,
+/// relative to the project `root`. Directories outside the project are left
+/// out.
+pub(super) fn compile_command_dirs(root: &Path, content: &str) -> Vec<String> {
+    let Ok(Value::Array(entries)) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let directory = entry.get("directory").and_then(Value::as_str).unwrap_or("");
+        let arguments: Vec<String> = match entry.get("arguments").and_then(Value::as_array) {
+            Some(arguments) => arguments
+                .iter()
+                .filter_map(|a| a.as_str().map(str::to_string))
+                .collect(),
+            None => entry
+                .get("command")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .split_whitespace()
+                .map(|a| a.trim_matches(|c| c == '"' || c == '\'').to_string())
+                .collect(),
+        };
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            let Some(flag) = INCLUDE_FLAGS.iter().find(|f| argument.starts_with(**f)) else {
+                continue;
+            };
+            let value = match &argument[flag.len()..] {
+                "" => arguments.next().map(String::as_str),
+                attached => Some(attached),
+            };
+            let Some(value) = value else {
+                continue;
+            };
+            if let Some(dir) = relative_to(root, &Path::new(directory).join(value)) {
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+    }
+    dirs
+}
+
+/// `path` relative to `root`, with `.` and `..` collapsed
+fn relative_to(root: &Path, path: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => {}
+        }
+    }
+    let root: Vec<String> = root
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    if !path.is_absolute() || !parts.starts_with(&root) {
+        return None;
+    }
+    Some(parts[root.len()..].join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_includes_and_implementations() {
+        let files: HashSet<String> = [
+            "include/net/socket.h",
+            "src/net/socket.c",
+            "src/main.c",
+            "src/util.h",
+            "src/util.c",
+            "third_party/json/json.hpp",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let commands = r#"[{"directory": "/work/proj/build", "file": "../src/main.c",
+            "command": "cc -I../third_party/json -isystem /usr/include -c ../src/main.c"}]"#;
+        let mut resolver = CResolver::new(&files);
+        resolver.add_include_dirs(&compile_command_dirs(Path::new("/work/proj"), commands));
+
+        let file = |path: &str| Some(Target::File(path.to_string()));
+        let from = "src/main.c";
+        assert_eq!(resolver.resolve(from, "util.h", &files), file("src/util.h"));
+        assert_eq!(
+            resolver.resolve(from, "<net/socket.h>", &files),
+            file("include/net/socket.h")
+        );
+        assert_eq!(
+            resolver.resolve(from, "json.hpp", &files),
+            file("third_party/json/json.hpp")
+        );
+        assert_eq!(
+            resolver.resolve(from, "<stdio.h>", &files),
+            Some(Target::External("stdio.h".to_string()))
+        );
+        assert_eq!(resolver.resolve(from, "missing.h", &files), None);
+
+        assert_eq!(
+            resolver.implementation("include/net/socket.h"),
+            Some("src/net/socket.c")
+        );
+        assert_eq!(resolver.implementation("src/util.h"), Some("src/util.c"));
+        assert_eq!(resolver.implementation("third_party/json/json.hpp"), None);
+    }
+
+    #[test]
+    fn test_implementations_stay_in_their_component() {
+        let files: HashSet<String> = [
+            "libs/a/include/a/codec.h",
+            "libs/a/src/codec.c",
+            "libs/b/include/b/buffer.h",
+            "libs/c/src/b/buffer.c",
+            "src/b/buffer.c",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let resolver = CResolver::new(&files);
+
+        assert_eq!(
+            resolver.implementation("libs/a/include/a/codec.h"),
+            Some("libs/a/src/codec.c")
+        );
+        assert_eq!(resolver.implementation("libs/b/include/b/buffer.h"), None);
+    }
+}
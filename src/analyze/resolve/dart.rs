@@ -0,0 +1,102 @@
+//! Dart import resolution.
+//!
+//! URIs without a scheme (`src/a.dart`, `../b.dart`) are relative to the
+//! importing file. `package:<name>/<path>` resolves to `lib/<path>` of the
+//! project package whose pubspec.yaml declares `name`; other packages are
+//! external. `dart:` libraries stay unresolved.
+
+use super::{join, parent_dir, Target};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Default)]
+pub(super) struct DartResolver {
+    /// Directory of each project package by pubspec name
+    packages: HashMap<String, String>,
+}
+
+impl DartResolver {
+    pub(super) fn new(manifests: &BTreeMap<String, String>) -> Self {
+        let packages = manifests
+            .iter()
+            .filter(|(path, _)| path.rsplit('/').next() == Some("pubspec.yaml"))
+            .filter_map(|(path, content)| {
+                let name = content
+                    .lines()
+                    .find_map(|line| line.strip_prefix("name:"))?
+                    .split('#')
+                    .next()?
+                    .trim()
+                    .trim_matches(|c| c == '"' || c == '\'');
+                Some((name.to_string(), parent_dir(path).to_string()))
+            })
+            .collect();
+
+        Self { packages }
+    }
+
+    pub(super) fn resolve(
+        &self,
+        from: &str,
+        import: &str,
+        files: &HashSet<String>,
+    ) -> Option<Target> {
+        if let Some(rest) = import.strip_prefix("package:") {
+            let (name, path) = rest.split_once('/')?;
+            let Some(dir) = self.packages.get(name) else {
+                return Some(Target::External(name.to_string()));
+            };
+            let path = join(dir, &format!("lib/{}", path))?;
+            return files.contains(&path).then_some(Target::File(path));
+        }
+        if import.contains(':') {
+            return None;
+        }
+        let path = join(parent_dir(from), import)?;
+        files.contains(&path).then_some(Target::File(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> Option<Target> {
+        Some(Target::File(path.to_string()))
+    }
+
+    #[test]
+    fn test_relative_and_package_imports() {
+        let files: HashSet<String> = [
+            "pubspec.yaml",
+            "lib/app.dart",
+            "lib/src/a.dart",
+            "lib/src/widgets/b.dart",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let manifests = BTreeMap::from([(
+            "pubspec.yaml".to_string(),
+            "name: app # the app\ndependencies:\n  http: ^1.2.0\n".to_string(),
+        )]);
+        let resolver = DartResolver::new(&manifests);
+
+        assert_eq!(
+            resolver.resolve("lib/app.dart", "src/a.dart", &files),
+            file("lib/src/a.dart")
+        );
+        assert_eq!(
+            resolver.resolve("lib/src/widgets/b.dart", "../a.dart", &files),
+            file("lib/src/a.dart")
+        );
+        assert_eq!(
+            resolver.resolve("lib/src/a.dart", "package:app/src/widgets/b.dart", &files),
+            file("lib/src/widgets/b.dart")
+        );
+        assert_eq!(
+            resolver.resolve("lib/app.dart", "package:http/http.dart", &files),
+            Some(Target::External("http".to_string()))
+        );
+        assert_eq!(resolver.resolve("lib/app.dart", "dart:async", &files), None);
+    }
+}
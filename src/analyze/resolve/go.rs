@@ -0,0 +1,143 @@
+//! Go package resolution.
+//!
+//! An import path under a `module` declared by one of the project's
+//! `go.mod` files names a directory of that module. Go imports whole
+//! packages, so the edge goes to one representative file of the directory:
+//! the file named after it, then `main.go`, then the first by name, never a
+//! `_test.go`. Other imports are external: the module from a matching
+//! `require` when there is one, otherwise the import path itself (standard
+//! library packages such as `net/http`).
+
+use super::{join, parent_dir, Target};
+use crate::analyze::manifest::parse_manifest;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Default)]
+pub(super) struct GoResolver {
+    /// Module paths with their directories, longest path first
+    modules: Vec<(String, String)>,
+    /// Modules required by any go.mod
+    requires: Vec<String>,
+    /// Representative file of each package directory
+    packages: HashMap<String, String>,
+}
+
+impl GoResolver {
+    pub(super) fn new(manifests: &BTreeMap<String, String>, files: &HashSet<String>) -> Self {
+        let mut modules = Vec::new();
+        let mut requires = Vec::new();
+
+        for (path, content) in manifests {
+            if path.rsplit('/').next() != Some("go.mod") {
+                continue;
+            }
+            let Some(manifest) = parse_manifest(path, content) else {
+                continue;
+            };
+            if let Some(module) = manifest.name {
+                modules.push((module, parent_dir(path).to_string()));
+            }
+            requires.extend(manifest.dependencies.into_iter().map(|d| d.name));
+        }
+        modules.sort_by_key(|(module, _): &(String, String)| std::cmp::Reverse(module.len()));
+
+        let mut by_dir: HashMap<String, Vec<&str>> = HashMap::new();
+        for file in files {
+            if file.ends_with(".go") && !file.ends_with("_test.go") {
+                by_dir
+                    .entry(parent_dir(file).to_string())
+                    .or_default()
+                    .push(file);
+            }
+        }
+        let packages = by_dir
+            .into_iter()
+            .map(|(dir, mut files)| {
+                files.sort_unstable();
+                let name = dir.rsplit('/').next().unwrap_or("");
+                let named = |stem: &str| {
+                    files
+                        .iter()
+                        .find(|f| f.rsplit('/').next() == Some(&format!("{}.go", stem)))
+                        .copied()
+                };
+                let file = named(name).or_else(|| named("main")).unwrap_or(files[0]);
+                (dir, file.to_string())
+            })
+            .collect();
+
+        Self {
+            modules,
+            requires,
+            packages,
+        }
+    }
+
+    pub(super) fn resolve(&self, import: &str) -> Option<Target> {
+        for (module, dir) in &self.modules {
+            let rest = if import == module {
+                ""
+            } else if let Some(rest) = import.strip_prefix(&format!("{}/", module)) {
+                rest
+            } else {
+                continue;
+            };
+            return join(dir, rest)
+                .and_then(|package| self.packages.get(&package))
+                .cloned()
+                .map(Target::File);
+        }
+
+        let required = self
+            .requires
+            .iter()
+            .filter(|m| import == *m || import.starts_with(&format!("{}/", m)))
+            .max_by_key(|m| m.len());
+        Some(Target::External(
+            required.map(String::as_str).unwrap_or(import).to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_packages_and_requires() {
+        let files: HashSet<String> = [
+            "go.mod",
+            "main.go",
+            "internal/db/conn.go",
+            "internal/db/db.go",
+            "internal/db/db_test.go",
+            "pkg/util/a.go",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let manifests = BTreeMap::from([(
+            "go.mod".to_string(),
+            "module example.com/app\n\ngo 1.22\n\nrequire (\n\tgithub.com/lib/pq v1.10.9 // indirect\n)\n".to_string(),
+        )]);
+        let resolver = GoResolver::new(&manifests, &files);
+        let file = |path: &str| Some(Target::File(path.to_string()));
+        let external = |name: &str| Some(Target::External(name.to_string()));
+
+        assert_eq!(
+            resolver.resolve("example.com/app/internal/db"),
+            file("internal/db/db.go")
+        );
+        assert_eq!(
+            resolver.resolve("example.com/app/pkg/util"),
+            file("pkg/util/a.go")
+        );
+        assert_eq!(resolver.resolve("example.com/app"), file("main.go"));
+        assert_eq!(resolver.resolve("example.com/app/missing"), None);
+        assert_eq!(
+            resolver.resolve("github.com/lib/pq/oid"),
+            external("github.com/lib/pq")
+        );
+        assert_eq!(resolver.resolve("net/http"), external("net/http"));
+    }
+}
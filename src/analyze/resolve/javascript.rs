@@ -0,0 +1,506 @@
+//! JavaScript/TypeScript module resolution.
+//!
+//! Follows what bundlers and `tsc` do for project code:
+//! - relative specifiers, probing extensions and `index.*` files, and mapping
+//!   `./a.js` to `a.ts` for ESM-style TypeScript imports
+//! - `compilerOptions.paths` and `baseUrl` from the nearest `tsconfig.json` or
+//!   `jsconfig.json`, following relative `extends`
+//! - workspace package names, via the package's `package.json` entry fields
+
+use super::{join, parent_dir};
+use crate::analyze::{PackageEcosystem, WorkspacePackage};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+/// Extensions tried, in order, for specifiers written without one
+const EXTENSIONS: &[&str] = &[
+    ".ts", ".tsx", ".d.ts", ".js", ".jsx", ".mjs", ".cjs", ".mts", ".cts",
+];
+
+/// TypeScript sources an emitted-JS specifier may refer to
+const TS_FOR_JS: &[(&str, &[&str])] = &[
+    (".js", &[".ts", ".tsx"]),
+    (".jsx", &[".tsx"]),
+    (".mjs", &[".mts"]),
+    (".cjs", &[".cts"]),
+];
+
+/// Never follow `extends` chains deeper than this
+const MAX_EXTENDS: usize = 8;
+
+#[derive(Debug, Default)]
+pub(super) struct JsResolver {
+    /// Compiler configs, deepest directory first
+    configs: Vec<CompilerConfig>,
+    packages: Vec<JsPackage>,
+}
+
+/// Module resolution options from one tsconfig/jsconfig
+#[derive(Debug)]
+struct CompilerConfig {
+    /// Directory containing the config
+    dir: String,
+    /// `baseUrl`, relative to the project root
+    base_url: Option<String>,
+    /// `paths` patterns with their targets, relative to the project root
+    paths: Vec<(String, Vec<String>)>,
+}
+
+/// A workspace package importable by name
+#[derive(Debug)]
+struct JsPackage {
+    name: String,
+    dir: String,
+    /// Entry files named by package.json, relative to the project root
+    entries: Vec<String>,
+}
+
+impl JsResolver {
+    pub(super) fn new(
+        manifests: &BTreeMap<String, String>,
+        packages: &[WorkspacePackage],
+        read: &dyn Fn(&str) -> Option<String>,
+    ) -> Self {
+        // tsconfig.json wins over jsconfig.json in the same directory
+        let named = |name: &'static str| {
+            manifests
+                .iter()
+                .filter(move |(path, _)| path.rsplit('/').next() == Some(name))
+        };
+        let mut configs: Vec<CompilerConfig> = named("tsconfig.json")
+            .chain(named("jsconfig.json"))
+            .filter_map(|(path, content)| CompilerConfig::parse(path, content, read))
+            .collect();
+        // Deepest first (stable, keeping tsconfig ahead)
+        configs.sort_by(|a, b| b.dir.len().cmp(&a.dir.len()).then(a.dir.cmp(&b.dir)));
+        configs.dedup_by(|a, b| a.dir == b.dir);
+
+        let packages = packages
+            .iter()
+            .filter(|p| p.ecosystem == PackageEcosystem::Npm)
+            .map(|p| JsPackage {
+                name: p.name.clone(),
+                dir: p.path.clone(),
+                entries: manifests
+                    .get(&p.manifest)
+                    .map(|content| package_entries(&p.path, content))
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Self { configs, packages }
+    }
+
+    pub(super) fn resolve(
+        &self,
+        from: &str,
+        specifier: &str,
+        files: &HashSet<String>,
+    ) -> Option<String> {
+        let specifier = specifier.split(['?', '#']).next().unwrap_or(specifier);
+
+        if specifier == "." || specifier.starts_with("./") || specifier.starts_with("../") {
+            let path = join(parent_dir(from), specifier)?;
+            return probe(&path, files);
+        }
+
+        if let Some(config) = self.config_for(from) {
+            if let Some(found) = config.resolve(specifier, files) {
+                return Some(found);
+            }
+        }
+
+        self.resolve_package(specifier, files)
+    }
+
+    /// The nearest config whose directory contains `file`
+    fn config_for(&self, file: &str) -> Option<&CompilerConfig> {
+        self.configs.iter().find(|c| contains(&c.dir, file))
+    }
+
+    fn resolve_package(&self, specifier: &str, files: &HashSet<String>) -> Option<String> {
+        for package in &self.packages {
+            if specifier == package.name {
+                return package
+                    .entries
+                    .iter()
+                    .find_map(|entry| probe(entry, files))
+                    .or_else(|| probe(&join(&package.dir, "src/index")?, files))
+                    .or_else(|| probe(&join(&package.dir, "index")?, files));
+            }
+
+            if let Some(subpath) = specifier
+                .strip_prefix(package.name.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                return probe(&join(&package.dir, subpath)?, files)
+                    .or_else(|| probe(&join(&package.dir, &format!("src/{}", subpath))?, files));
+            }
+        }
+        None
+    }
+}
+
+impl CompilerConfig {
+    fn parse(path: &str, content: &str, read: &dyn Fn(&str) -> Option<String>) -> Option<Self> {
+        let dir = parent_dir(path).to_string();
+        let mut config = Self {
+            dir: dir.clone(),
+            base_url: None,
+            paths: Vec::new(),
+        };
+
+        // Walk the extends chain from the config itself outwards; the first
+        // file to set an option wins
+        let mut current = Some((dir, parse_jsonc(content)?));
+        let mut has_paths = false;
+        for _ in 0..MAX_EXTENDS {
+            let Some((config_dir, json)) = current.take() else {
+                break;
+            };
+            let options = json.get("compilerOptions");
+
+            let base_url = options
+                .and_then(|o| o.get("baseUrl"))
+                .and_then(Value::as_str)
+                .and_then(|b| join(&config_dir, b));
+            if config.base_url.is_none() {
+                config.base_url = base_url.clone();
+            }
+
+            if let (false, Some(Value::Object(paths))) =
+                (has_paths, options.and_then(|o| o.get("paths")))
+            {
+                has_paths = true;
+                // Targets are relative to baseUrl, or to the declaring config
+                let target_base = config.base_url.clone().unwrap_or(config_dir.clone());
+                config.paths = paths
+                    .iter()
+                    .map(|(pattern, targets)| {
+                        let targets = targets
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_str)
+                            .filter_map(|t| join(&target_base, t))
+                            .collect();
+                        (pattern.clone(), targets)
+                    })
+                    .collect();
+            }
+
+            current = json
+                .get("extends")
+                .and_then(Value::as_str)
+                .filter(|e| e.starts_with('.'))
+                .and_then(|e| {
+                    let file = if e.ends_with(".json") {
+                        join(&config_dir, e)?
+                    } else {
+                        join(&config_dir, &format!("{}.json", e))?
+                    };
+                    let json = parse_jsonc(&read(&file)?)?;
+                    Some((parent_dir(&file).to_string(), json))
+                });
+        }
+
+        Some(config)
+    }
+
+    fn resolve(&self, specifier: &str, files: &HashSet<String>) -> Option<String> {
+        // The pattern with the longest prefix before `*` wins, as in tsc
+        let mut best: Option<(usize, &str, &[String])> = None;
+        for (pattern, targets) in &self.paths {
+            let (matched, rank) = match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let ok = specifier.len() >= prefix.len() + suffix.len()
+                        && specifier.starts_with(prefix)
+                        && specifier.ends_with(suffix);
+                    let capture = if ok {
+                        &specifier[prefix.len()..specifier.len() - suffix.len()]
+                    } else {
+                        ""
+                    };
+                    (ok.then_some(capture), prefix.len())
+                }
+                None => ((pattern == specifier).then_some(""), usize::MAX),
+            };
+            if let Some(capture) = matched {
+                if best.is_none_or(|(r, _, _)| rank > r) {
+                    best = Some((rank, capture, targets));
+                }
+            }
+        }
+
+        if let Some((_, capture, targets)) = best {
+            for target in targets {
+                if let Some(found) = probe(&target.replacen('*', capture, 1), files) {
+                    return Some(found);
+                }
+            }
+        }
+
+        let base_url = self.base_url.as_deref()?;
+        probe(&join(base_url, specifier)?, files)
+    }
+}
+
+/// Whether `path` lies inside directory `dir` ("" = project root)
+fn contains(dir: &str, path: &str) -> bool {
+    dir.is_empty()
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// The known file a module path refers to: the path itself, the path with a
+/// source extension, or an `index` file inside it
+fn probe(path: &str, files: &HashSet<String>) -> Option<String> {
+    if files.contains(path) {
+        return Some(path.to_string());
+    }
+
+    for (js, sources) in TS_FOR_JS {
+        if let Some(stem) = path.strip_suffix(js) {
+            for ext in *sources {
+                let candidate = format!("{}{}", stem, ext);
+                if files.contains(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    let index = if path.is_empty() {
+        "index".to_string()
+    } else {
+        format!("{}/index", path)
+    };
+    for base in [path, index.as_str()] {
+        if base.is_empty() {
+            continue;
+        }
+        for ext in EXTENSIONS {
+            let candidate = format!("{}{}", base, ext);
+            if files.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Entry files declared by a package.json, most source-like first
+fn package_entries(dir: &str, content: &str) -> Vec<String> {
+    let Ok(json) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+
+    let root_export = json.get("exports").map(|e| e.get(".").unwrap_or(e));
+    let mut fields: Vec<&Value> = ["source", "types", "typings", "module", "main"]
+        .iter()
+        .filter_map(|field| json.get(*field))
+        .collect();
+    if let Some(export) = root_export {
+        fields.push(export);
+        if let Value::Object(conditions) = export {
+            fields.extend(
+                ["types", "import", "default", "require"]
+                    .iter()
+                    .filter_map(|c| conditions.get(*c)),
+            );
+        }
+    }
+
+    fields
+        .into_iter()
+        .filter_map(Value::as_str)
+        .filter_map(|entry| join(dir, entry))
+        .collect()
+}
+
+/// Parse JSON with comments and trailing commas, as tsconfig files allow
+fn parse_jsonc(content: &str) -> Option<Value> {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    // Drop commas directly before a closing bracket
+    let mut cleaned = String::with_capacity(out.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in out.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = out[i + 1..].trim_start().chars().next();
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        cleaned.push(c);
+    }
+
+    serde_json::from_str(&cleaned).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_set(paths: &[&str]) -> HashSet<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn resolver(manifests: &[(&str, &str)], packages: &[WorkspacePackage]) -> JsResolver {
+        let manifests: BTreeMap<String, String> = manifests
+            .iter()
+            .map(|(p, c)| (p.to_string(), c.to_string()))
+            .collect();
+        let read = |path: &str| manifests.get(path).cloned();
+        JsResolver::new(&manifests, packages, &read)
+    }
+
+    #[test]
+    fn test_relative_specifiers() {
+        let files = file_set(&[
+            "src/app.ts",
+            "src/utils.ts",
+            "src/lib/index.tsx",
+            "src/esm.ts",
+        ]);
+        let js = resolver(&[], &[]);
+
+        assert_eq!(
+            js.resolve("src/app.ts", "./utils", &files).as_deref(),
+            Some("src/utils.ts")
+        );
+        assert_eq!(
+            js.resolve("src/app.ts", "./lib", &files).as_deref(),
+            Some("src/lib/index.tsx")
+        );
+        assert_eq!(
+            js.resolve("src/app.ts", "./esm.js", &files).as_deref(),
+            Some("src/esm.ts")
+        );
+        assert_eq!(js.resolve("src/app.ts", "../../x", &files), None);
+        assert_eq!(js.resolve("src/app.ts", "react", &files), None);
+    }
+
+    #[test]
+    fn test_tsconfig_paths_and_extends() {
+        let files = file_set(&[
+            "web/src/app.ts",
+            "web/src/lib/db.ts",
+            "web/src/components/Button.tsx",
+            "web/src/config.ts",
+        ]);
+        let js = resolver(
+            &[
+                (
+                    "web/tsconfig.json",
+                    r#"{
+                        // app config
+                        "extends": "./tsconfig.base",
+                        "compilerOptions": { "baseUrl": "src", },
+                    }"#,
+                ),
+                (
+                    "web/tsconfig.base.json",
+                    r#"{ "compilerOptions": { "paths": { "@/*": ["*"], "~ui/*": ["./components/*"] } } }"#,
+                ),
+            ],
+            &[],
+        );
+
+        assert_eq!(
+            js.resolve("web/src/app.ts", "@/lib/db", &files).as_deref(),
+            Some("web/src/lib/db.ts")
+        );
+        assert_eq!(
+            js.resolve("web/src/app.ts", "~ui/Button", &files)
+                .as_deref(),
+            Some("web/src/components/Button.tsx")
+        );
+        assert_eq!(
+            js.resolve("web/src/app.ts", "config", &files).as_deref(),
+            Some("web/src/config.ts")
+        );
+    }
+
+    #[test]
+    fn test_workspace_packages() {
+        let files = file_set(&[
+            "apps/web/main.ts",
+            "packages/ui/src/index.ts",
+            "packages/ui/src/button.ts",
+        ]);
+        let package = WorkspacePackage {
+            name: "@acme/ui".to_string(),
+            version: None,
+            path: "packages/ui".to_string(),
+            ecosystem: PackageEcosystem::Npm,
+            manifest: "packages/ui/package.json".to_string(),
+            dependencies: Vec::new(),
+        };
+        let js = resolver(
+            &[(
+                "packages/ui/package.json",
+                r#"{ "name": "@acme/ui", "main": "dist/index.js" }"#,
+            )],
+            &[package],
+        );
+
+        assert_eq!(
+            js.resolve("apps/web/main.ts", "@acme/ui", &files)
+                .as_deref(),
+            Some("packages/ui/src/index.ts")
+        );
+        assert_eq!(
+            js.resolve("apps/web/main.ts", "@acme/ui/button", &files)
+                .as_deref(),
+            Some("packages/ui/src/button.ts")
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Import resolution.
+//!
+//! Parsers extract imports as the raw specifiers written in the source
+//! (`./utils`, `@/lib/db`, `@acme/ui`). The resolver maps each one to the
+//! `relative_path` of the project file it refers to, so the file graph links
+//! real files. Imports that leave the project are reduced to the package
+//! they come from where the language makes that clear (Rust crates, Python
+//! top-level packages, Go modules, system headers); anything else is kept
+//! as written. Java, Kotlin and C# package imports resolve to the files of
+//! the package that the importer uses, which may be several or none. PHP,
+//! Ruby and Dart paths resolve relative to the importer, and PHP classes
+//! through composer's PSR-4 roots. Swift imports whole modules and stays as
+//! written.
+
+mod c;
+mod dart;
+mod go;
+mod javascript;
+mod namespace;
+mod php;
+mod python;
+mod ruby;
+mod rust;
+
+use crate::analyze::ProjectLayout;
+use crate::scan::SourceFile;
+use crate::types::{FileEntry, Language};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use c::CResolver;
+use dart::DartResolver;
+use go::GoResolver;
+use javascript::JsResolver;
+use namespace::NamespaceResolver;
+use php::PhpResolver;
+use python::PythonResolver;
+use rust::RustResolver;
+
+/// Where an import leads
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// A project file
+    File(String),
+    /// Several project files, such as the files of an imported namespace
+    /// that the importer uses (possibly none)
+    Files(Vec<String>),
+    /// A package outside the project (`std`, `serde`, `numpy`, ...)
+    External(String),
+}
+
+/// Resolves raw import specifiers to project files
+#[derive(Debug, Default)]
+pub struct ImportResolver {
+    /// Relative paths of every known project file
+    files: HashSet<String>,
+    c: CResolver,
+    dart: DartResolver,
+    go: GoResolver,
+    javascript: JsResolver,
+    namespace: NamespaceResolver,
+    php: PhpResolver,
+    python: PythonResolver,
+    rust: RustResolver,
+}
+
+impl ImportResolver {
+    /// Build a resolver for `sources` from the project's build manifests,
+    /// reading any other config they point to (tsconfig `extends`,
+    /// compile_commands.json) under `root`
+    pub fn load(root: &Path, sources: &[SourceFile], manifests: &BTreeMap<String, String>) -> Self {
+        let read = |path: &str| fs::read_to_string(root.join(path)).ok();
+        let mut resolver = Self::from_manifests(sources, manifests, &read);
+
+        // compile_commands.json usually sits in a git-ignored build directory
+        let mut compile_commands: Vec<String> = manifests
+            .iter()
+            .filter(|(path, _)| path.rsplit('/').next() == Some(c::COMPILE_COMMANDS))
+            .map(|(_, content)| content.clone())
+            .collect();
+        if compile_commands.is_empty() {
+            compile_commands.extend(read(&format!("build/{}", c::COMPILE_COMMANDS)));
+        }
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        for content in compile_commands {
+            resolver
+                .c
+                .add_include_dirs(&c::compile_command_dirs(&root, &content));
+        }
+        resolver
+    }
+
+    /// Search `dirs` (relative to the project root) for C/C++ includes before
+    /// any discovered include directory
+    pub fn with_include_dirs(mut self, dirs: &[String]) -> Self {
+        self.c.add_include_dirs(dirs);
+        self
+    }
+
+    /// Build a resolver from manifest contents keyed by relative path.
+    /// `read` loads other project files by relative path (e.g. a tsconfig's
+    /// `extends` target).
+    pub fn from_manifests(
+        sources: &[SourceFile],
+        manifests: &BTreeMap<String, String>,
+        read: &dyn Fn(&str) -> Option<String>,
+    ) -> Self {
+        let layout = ProjectLayout::from_contents(manifests);
+
+        let files: HashSet<String> = sources
+            .iter()
+            .map(|s| s.entry.relative_path.clone())
+            .collect();
+
+        Self {
+            c: CResolver::new(&files),
+            dart: DartResolver::new(manifests),
+            go: GoResolver::new(manifests, &files),
+            javascript: JsResolver::new(manifests, &layout.packages, read),
+            namespace: NamespaceResolver::new(sources),
+            php: PhpResolver::new(manifests),
+            python: PythonResolver::new(manifests, &files),
+            rust: RustResolver::new(manifests, sources),
+            files,
+        }
+    }
+
+    /// The project file `import` refers to, if it is one (the first, for
+    /// imports that bring in several)
+    pub fn resolve(&self, from: &FileEntry, import: &str) -> Option<String> {
+        match self.target(from, import)? {
+            Target::File(file) => Some(file),
+            Target::Files(files) => files.into_iter().next(),
+            Target::External(_) => None,
+        }
+    }
+
+    /// The external package `import` comes from, for imports that leave the
+    /// project and whose package can be told from the path alone
+    pub fn external(&self, from: &FileEntry, import: &str) -> Option<String> {
+        match self.target(from, import)? {
+            Target::External(name) => Some(name),
+            Target::File(_) | Target::Files(_) => None,
+        }
+    }
+
+    fn target(&self, from: &FileEntry, import: &str) -> Option<Target> {
+        let from_path = from.relative_path.as_str();
+        match from.language {
+            Language::JavaScript | Language::TypeScript => self
+                .javascript
+                .resolve(from_path, import, &self.files)
+                .map(Target::File),
+            Language::Rust => self.rust.resolve(from_path, import),
+            Language::Python => self.python.resolve(from_path, import, &self.files),
+            Language::Go => self.go.resolve(import),
+            Language::C | Language::Cpp => self.c.resolve(from_path, import, &self.files),
+            Language::Java | Language::Kotlin | Language::CSharp => {
+                self.namespace.resolve(from.language, from_path, import)
+            }
+            Language::Php => self.php.resolve(from_path, import, &self.files),
+            Language::Ruby => ruby::resolve(from_path, import, &self.files).map(Target::File),
+            Language::Dart => self.dart.resolve(from_path, import, &self.files),
+            _ => None,
+        }
+    }
+
+    /// Resolve every import of `from` to a project file or external package,
+    /// keeping anything else as written
+    pub fn resolve_all(&self, from: &FileEntry, imports: &[String]) -> Vec<String> {
+        let mut resolved: Vec<String> = Vec::with_capacity(imports.len());
+        for import in imports {
+            let targets = match self.target(from, import) {
+                // Including a header also depends on the code that implements it
+                Some(Target::File(path)) => {
+                    let implementation = self.c.implementation(&path).map(str::to_string);
+                    std::iter::once(path).chain(implementation).collect()
+                }
+                Some(Target::Files(paths)) => paths,
+                Some(Target::External(name)) => vec![name],
+                None => vec![import.clone()],
+            };
+            for target in targets {
+                if target != from.relative_path && !resolved.contains(&target) {
+                    resolved.push(target);
+                }
+            }
+        }
+        resolved
+    }
+}
+
+/// Directory part of a relative path ("" for files at the root)
+pub(crate) fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+/// Join `relative` onto `base` and collapse `.` and `..` segments.
+/// Returns `None` if the path climbs above the project root.
+pub(crate) fn join(base: &str, relative: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in base.split('/').chain(relative.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            segment => parts.push(segment),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_collapses_segments() {
+        assert_eq!(join("src/a", "../b/./c").as_deref(), Some("src/b/c"));
+        assert_eq!(join("", "./x").as_deref(), Some("x"));
+        assert_eq!(join("src", "../../x"), None);
+    }
+}
@@ -0,0 +1,290 @@
+//! Java, Kotlin and C# import resolution.
+//!
+//! These languages import by package or namespace, not by path, so each
+//! file's declared `package`/`namespace` and the types it declares are
+//! indexed up front. A type import (`com.acme.billing.Invoice`, `using static
+//! Acme.Util;`) resolves to the file declaring that type in that package,
+//! preferring the file named after it. A package or namespace import
+//! (`com.acme.billing.*`, `using Acme.Billing;`) resolves to the files of
+//! that package whose types the importing file mentions, so an unused or
+//! broad `using` doesn't link every file in the namespace. Kotlin and Java
+//! share one index, since either can import the other's declarations;
+//! Kotlin's top-level functions and objects count as its declarations.
+
+use super::Target;
+use crate::scan::SourceFile;
+use crate::types::Language;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+static JAVA_PACKAGE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*package\s+([\w.]+)\s*;").unwrap());
+
+static KOTLIN_PACKAGE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*package[ \t]+([\w.]+)").unwrap());
+
+static CSHARP_NAMESPACE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*namespace\s+([\w.]+)").unwrap());
+
+static TYPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:class|interface|enum|record|struct|@interface)\s+([A-Za-z_]\w*)").unwrap()
+});
+
+static KOTLIN_DECLARATION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)\b(?:class|interface|object|typealias)[ \t]+([A-Za-z_]\w*)|^(?:\w+[ \t]+)*fun[ \t]+(?:<[^>]*>[ \t]*)?(?:[\w.<>?]+\.)?([A-Za-z_]\w*)[ \t]*\(")
+        .unwrap()
+});
+
+static TYPE_NAME_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Z]\w*").unwrap());
+
+#[derive(Debug, Default)]
+pub(super) struct NamespaceResolver {
+    java: NamespaceIndex,
+    csharp: NamespaceIndex,
+}
+
+/// Declarations of one language's files
+#[derive(Debug, Default)]
+struct NamespaceIndex {
+    /// Files declaring each package/namespace
+    files: HashMap<String, Vec<String>>,
+    /// Types declared by each file
+    types: HashMap<String, HashSet<String>>,
+    /// Capitalised identifiers each file mentions (candidate type names)
+    mentions: HashMap<String, HashSet<String>>,
+}
+
+impl NamespaceResolver {
+    pub(super) fn new(sources: &[SourceFile]) -> Self {
+        let mut resolver = Self::default();
+
+        for source in sources {
+            let (index, pattern, declarations) = match source.entry.language {
+                Language::Java => (&mut resolver.java, &*JAVA_PACKAGE_PATTERN, &*TYPE_PATTERN),
+                Language::Kotlin => (
+                    &mut resolver.java,
+                    &*KOTLIN_PACKAGE_PATTERN,
+                    &*KOTLIN_DECLARATION_PATTERN,
+                ),
+                Language::CSharp => (
+                    &mut resolver.csharp,
+                    &*CSHARP_NAMESPACE_PATTERN,
+                    &*TYPE_PATTERN,
+                ),
+                _ => continue,
+            };
+            let path = &source.entry.relative_path;
+            let content = &source.content;
+
+            let mut namespaces: Vec<&str> = pattern
+                .captures_iter(content)
+                .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+                .collect();
+            if namespaces.is_empty() {
+                // Default package / global namespace
+                namespaces.push("");
+            }
+            for namespace in namespaces {
+                let files = index.files.entry(namespace.to_string()).or_default();
+                if !files.contains(path) {
+                    files.push(path.clone());
+                }
+            }
+
+            index.types.insert(
+                path.clone(),
+                declarations
+                    .captures_iter(content)
+                    .filter_map(|cap| Some(cap.get(1).or(cap.get(2))?.as_str().to_string()))
+                    .collect(),
+            );
+            index.mentions.insert(
+                path.clone(),
+                TYPE_NAME_PATTERN
+                    .find_iter(content)
+                    .map(|m| m.as_str().to_string())
+                    .collect(),
+            );
+        }
+
+        for index in [&mut resolver.java, &mut resolver.csharp] {
+            for files in index.files.values_mut() {
+                files.sort();
+            }
+        }
+        resolver
+    }
+
+    pub(super) fn resolve(&self, language: Language, from: &str, import: &str) -> Option<Target> {
+        let index = match language {
+            Language::Java | Language::Kotlin => &self.java,
+            Language::CSharp => &self.csharp,
+            _ => return None,
+        };
+
+        if let Some(package) = import.strip_suffix(".*") {
+            return index
+                .used_files(from, package)
+                .or_else(|| index.type_file(package));
+        }
+        index
+            .type_file(import)
+            .or_else(|| index.used_files(from, import))
+    }
+}
+
+impl NamespaceIndex {
+    /// File declaring the type `path` names (`pkg.Type` or `pkg.Outer.Inner`)
+    fn type_file(&self, path: &str) -> Option<Target> {
+        let segments: Vec<&str> = path.split('.').collect();
+        for split in (0..segments.len()).rev() {
+            let package = segments[..split].join(".");
+            let name = segments[split];
+            let Some(files) = self.files.get(&package) else {
+                continue;
+            };
+            let declaring: Vec<&String> = files
+                .iter()
+                .filter(|f| self.types.get(*f).is_some_and(|t| t.contains(name)))
+                .collect();
+            let named = declaring.iter().find(|f| {
+                let file_name = f.rsplit('/').next().unwrap_or(f);
+                file_name.rsplit_once('.').map(|(stem, _)| stem) == Some(name)
+            });
+            if let Some(file) = named.or(declaring.first()) {
+                return Some(Target::File((*file).clone()));
+            }
+        }
+        None
+    }
+
+    /// Files of `package` declaring a type that `from` mentions
+    fn used_files(&self, from: &str, package: &str) -> Option<Target> {
+        let files = self.files.get(package)?;
+        let mentions = self.mentions.get(from);
+        let used = files
+            .iter()
+            .filter(|f| {
+                let types = self.types.get(*f);
+                types.is_some_and(|types| {
+                    mentions.is_some_and(|m| types.iter().any(|t| m.contains(t)))
+                })
+            })
+            .cloned()
+            .collect();
+        Some(Target::Files(used))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::source;
+
+    #[test]
+    fn test_java_and_csharp_imports() {
+        let sources = vec![
+            source(
+                "src/main/java/com/acme/billing/Invoice.java",
+                "package com.acme.billing;\n\npublic class Invoice {\n    public static class Line {}\n}\n",
+            ),
+            source(
+                "src/main/java/com/acme/billing/Payment.java",
+                "package com.acme.billing;\n\nrecord Payment(int cents) {}\n",
+            ),
+            source(
+                "Billing/Models.cs",
+                "namespace Acme.Billing;\n\npublic class Invoice {}\npublic class Refund {}\n",
+            ),
+            source("Billing/Ledger.cs", "namespace Acme.Billing\n{\n    class Ledger {}\n}\n"),
+            source("Api/Controller.cs", "using Acme.Billing;\n\nclass Controller { Invoice Get() => null; }\n"),
+            source("App.java", "import com.acme.billing.*;\n\nclass App { Payment p; }\n"),
+        ];
+        let resolver = NamespaceResolver::new(&sources);
+        let file = |path: &str| Some(Target::File(path.to_string()));
+        let files =
+            |paths: &[&str]| Some(Target::Files(paths.iter().map(|p| p.to_string()).collect()));
+
+        let invoice = "src/main/java/com/acme/billing/Invoice.java";
+        assert_eq!(
+            resolver.resolve(Language::Java, "App.java", "com.acme.billing.Invoice"),
+            file(invoice)
+        );
+        assert_eq!(
+            resolver.resolve(Language::Java, "App.java", "com.acme.billing.Invoice.Line"),
+            file(invoice)
+        );
+        assert_eq!(
+            resolver.resolve(Language::Java, "App.java", "com.acme.billing.*"),
+            files(&["src/main/java/com/acme/billing/Payment.java"])
+        );
+        assert_eq!(
+            resolver.resolve(Language::Java, "App.java", "java.util.List"),
+            None
+        );
+
+        assert_eq!(
+            resolver.resolve(Language::CSharp, "Api/Controller.cs", "Acme.Billing"),
+            files(&["Billing/Models.cs"])
+        );
+        assert_eq!(
+            resolver.resolve(Language::CSharp, "Api/Controller.cs", "Acme.Billing.Ledger"),
+            file("Billing/Ledger.cs")
+        );
+        assert_eq!(
+            resolver.resolve(Language::CSharp, "Api/Controller.cs", "System.Linq"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_kotlin_and_java_share_packages() {
+        let sources = vec![
+            source(
+                "src/main/java/com/acme/billing/Invoice.java",
+                "package com.acme.billing;\n\npublic class Invoice {}\n",
+            ),
+            source(
+                "src/main/kotlin/com/acme/billing/Money.kt",
+                "package com.acme.billing\n\ndata class Money(val cents: Long)\n\nfun Money.format(): String = \"$cents\"\n",
+            ),
+            source(
+                "src/main/kotlin/com/acme/App.kt",
+                "package com.acme\n\nimport com.acme.billing.*\n\nfun main() { println(Money(1)) }\n",
+            ),
+        ];
+        let resolver = NamespaceResolver::new(&sources);
+        let file = |path: &str| Some(Target::File(path.to_string()));
+        let money = "src/main/kotlin/com/acme/billing/Money.kt";
+
+        assert_eq!(
+            resolver.resolve(
+                Language::Kotlin,
+                "src/main/kotlin/com/acme/App.kt",
+                "com.acme.billing.Invoice"
+            ),
+            file("src/main/java/com/acme/billing/Invoice.java")
+        );
+        assert_eq!(
+            resolver.resolve(Language::Java, "App.java", "com.acme.billing.Money"),
+            file(money)
+        );
+        assert_eq!(
+            resolver.resolve(
+                Language::Kotlin,
+                "src/main/kotlin/com/acme/App.kt",
+                "com.acme.billing.format"
+            ),
+            file(money)
+        );
+        assert_eq!(
+            resolver.resolve(
+                Language::Kotlin,
+                "src/main/kotlin/com/acme/App.kt",
+                "com.acme.billing.*"
+            ),
+            Some(Target::Files(vec![money.to_string()]))
+        );
+    }
+}
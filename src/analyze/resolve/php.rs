@@ -0,0 +1,123 @@
+//! PHP include and class resolution.
+//!
+//! `require`/`include` paths built from `__DIR__` (extracted as `./...`)
+//! are relative to the including file; other literal paths are tried from
+//! the project root. A `use` import names a class, which composer's PSR-4
+//! autoloading maps to a file: the longest namespace prefix declared in a
+//! composer.json names a directory and the rest of the name is the path
+//! under it, so `App\Models\User` with `"App\\": "app/"` is
+//! `app/Models/User.php`. Functions, constants and classes outside every
+//! PSR-4 root stay unresolved.
+
+use super::{join, parent_dir, Target};
+use crate::analyze::layout::parse_psr4_roots;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Default)]
+pub(super) struct PhpResolver {
+    /// Namespace prefixes (with their trailing `\`) and the directories they
+    /// map to, longest prefix first
+    roots: Vec<(String, String)>,
+}
+
+impl PhpResolver {
+    pub(super) fn new(manifests: &BTreeMap<String, String>) -> Self {
+        let mut roots = Vec::new();
+
+        for (path, content) in manifests {
+            if path.rsplit('/').next() != Some("composer.json") {
+                continue;
+            }
+            let dir = parent_dir(path);
+            for (prefix, root) in parse_psr4_roots(content) {
+                if let Some(root) = join(dir, &root) {
+                    roots.push((prefix, root));
+                }
+            }
+        }
+        roots.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.cmp(b)));
+
+        Self { roots }
+    }
+
+    pub(super) fn resolve(
+        &self,
+        from: &str,
+        import: &str,
+        files: &HashSet<String>,
+    ) -> Option<Target> {
+        let path = if import.starts_with("./") || import.starts_with("../") {
+            join(parent_dir(from), import)
+        } else if import.contains('/') || import.ends_with(".php") {
+            join("", import)
+        } else {
+            self.class_file(import, files)
+        }?;
+        files.contains(&path).then_some(Target::File(path))
+    }
+
+    /// PSR-4 file of a fully qualified class name
+    fn class_file(&self, class: &str, files: &HashSet<String>) -> Option<String> {
+        self.roots.iter().find_map(|(prefix, root)| {
+            let rest = class.strip_prefix(prefix.as_str())?;
+            let path = join(root, &format!("{}.php", rest.replace('\\', "/")))?;
+            files.contains(&path).then_some(path)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> Option<Target> {
+        Some(Target::File(path.to_string()))
+    }
+
+    #[test]
+    fn test_psr4_classes_and_includes() {
+        let files: HashSet<String> = [
+            "composer.json",
+            "app/Models/User.php",
+            "app/Http/Controllers/UserController.php",
+            "bootstrap.php",
+            "config/app.php",
+            "tests/Feature/UserTest.php",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let manifests = BTreeMap::from([(
+            "composer.json".to_string(),
+            r#"{"autoload": {"psr-4": {"App\\": "app/"}}, "autoload-dev": {"psr-4": {"Tests\\": "tests/"}}}"#
+                .to_string(),
+        )]);
+        let resolver = PhpResolver::new(&manifests);
+        let from = "app/Http/Controllers/UserController.php";
+
+        assert_eq!(
+            resolver.resolve(from, "App\\Models\\User", &files),
+            file("app/Models/User.php")
+        );
+        assert_eq!(
+            resolver.resolve("tests/Feature/UserTest.php", "App\\Models\\User", &files),
+            file("app/Models/User.php")
+        );
+        assert_eq!(
+            resolver.resolve(from, "../../../bootstrap.php", &files),
+            file("bootstrap.php")
+        );
+        assert_eq!(
+            resolver.resolve(from, "config/app.php", &files),
+            file("config/app.php")
+        );
+        assert_eq!(
+            resolver.resolve(from, "App\\helpers\\format_money", &files),
+            None
+        );
+        assert_eq!(
+            resolver.resolve(from, "Illuminate\\Support\\Str", &files),
+            None
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Python module resolution.
+//!
+//! Absolute imports are looked up under each source root: the project root,
+//! `src/`, and the directory of every `pyproject.toml` along with its `src/`
+//! and any setuptools/poetry package directories it names. Relative imports
+//! (`.utils`, `..core.models`) start from the importing file's package. A
+//! dotted name resolves to the longest prefix that is a module (`a/b.py`,
+//! `a/b/__init__.py` or their `.pyi` stubs), so `pkg.mod.func` lands on
+//! `pkg/mod.py`. Absolute imports found under no root are external, named by
+//! their top-level package.
+
+use super::{join, parent_dir, Target};
+use std::collections::{BTreeMap, HashSet};
+
+/// Files tried, in order, for a module path `p`
+const MODULE_FILES: &[&str] = &[".py", ".pyi", "/__init__.py", "/__init__.pyi"];
+
+#[derive(Debug, Default)]
+pub(super) struct PythonResolver {
+    /// Directories absolute imports are looked up in, deepest first
+    roots: Vec<String>,
+}
+
+impl PythonResolver {
+    pub(super) fn new(manifests: &BTreeMap<String, String>, files: &HashSet<String>) -> Self {
+        let mut roots = vec![String::new(), "src".to_string()];
+
+        for (path, content) in manifests {
+            if path.rsplit('/').next() != Some("pyproject.toml") {
+                continue;
+            }
+            let dir = parent_dir(path);
+            roots.push(dir.to_string());
+            roots.extend(join(dir, "src"));
+            roots.extend(
+                package_dirs(content)
+                    .iter()
+                    .filter_map(|package_dir| join(dir, package_dir)),
+            );
+        }
+
+        // Only directories that hold Python files can be roots
+        roots.retain(|root| {
+            let prefix = if root.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", root)
+            };
+            files
+                .iter()
+                .any(|f| f.starts_with(&prefix) && (f.ends_with(".py") || f.ends_with(".pyi")))
+        });
+        roots.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        roots.dedup();
+
+        Self { roots }
+    }
+
+    pub(super) fn resolve(
+        &self,
+        from: &str,
+        import: &str,
+        files: &HashSet<String>,
+    ) -> Option<Target> {
+        let dots = import.chars().take_while(|c| *c == '.').count();
+        let segments: Vec<&str> = import[dots..]
+            .split('.')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if dots > 0 {
+            // One dot is the current package, each further dot its parent
+            let mut base = parent_dir(from);
+            for _ in 1..dots {
+                if base.is_empty() {
+                    return None;
+                }
+                base = parent_dir(base);
+            }
+            return module_file(base, &segments, 0, files).map(Target::File);
+        }
+
+        let first = *segments.first()?;
+        self.roots
+            .iter()
+            .find_map(|root| module_file(root, &segments, 1, files))
+            .map(Target::File)
+            .or_else(|| Some(Target::External(first.to_string())))
+    }
+}
+
+/// File of the longest prefix of `segments`, at least `min_len` long, that
+/// is a module under `base`. The empty prefix is the package `base` itself.
+fn module_file(
+    base: &str,
+    segments: &[&str],
+    min_len: usize,
+    files: &HashSet<String>,
+) -> Option<String> {
+    for len in (min_len..=segments.len()).rev() {
+        let module = join(base, &segments[..len].join("/"))?;
+        for suffix in MODULE_FILES {
+            // The package itself is only ever its `__init__`, never a sibling `base.py`
+            if len == 0 && !suffix.starts_with('/') {
+                continue;
+            }
+            let candidate = format!("{}{}", module, suffix);
+            let candidate = candidate.trim_start_matches('/');
+            if files.contains(candidate) {
+                return Some(candidate.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Package directories a pyproject.toml points setuptools or poetry at
+fn package_dirs(content: &str) -> Vec<String> {
+    let Ok(toml) = toml::from_str::<toml::Value>(content) else {
+        return Vec::new();
+    };
+    let tool = toml.get("tool");
+    let setuptools = tool.and_then(|t| t.get("setuptools"));
+    let strings = |value: Option<&toml::Value>| -> Vec<String> {
+        value
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    };
+
+    let mut dirs = strings(
+        setuptools
+            .and_then(|s| s.get("packages"))
+            .and_then(|p| p.get("find"))
+            .and_then(|f| f.get("where")),
+    );
+    dirs.extend(
+        setuptools
+            .and_then(|s| s.get("package-dir"))
+            .and_then(|d| d.get(""))
+            .and_then(|d| d.as_str())
+            .map(str::to_string),
+    );
+    dirs.extend(
+        tool.and_then(|t| t.get("poetry"))
+            .and_then(|p| p.get("packages"))
+            .and_then(|p| p.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.get("from")?.as_str().map(str::to_string)),
+    );
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> Option<Target> {
+        Some(Target::File(path.to_string()))
+    }
+
+    #[test]
+    fn test_absolute_and_relative_imports() {
+        let files: HashSet<String> = [
+            "pyproject.toml",
+            "src/app/__init__.py",
+            "src/app/core/__init__.py",
+            "src/app/core/models.py",
+            "src/app/api/views.py",
+            "scripts/run.py",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let manifests = BTreeMap::from([(
+            "pyproject.toml".to_string(),
+            "[project]\nname = \"app\"\n".to_string(),
+        )]);
+        let resolver = PythonResolver::new(&manifests, &files);
+        let from = "src/app/api/views.py";
+
+        assert_eq!(
+            resolver.resolve(from, "app.core.models.User", &files),
+            file("src/app/core/models.py")
+        );
+        assert_eq!(
+            resolver.resolve(from, "app.helpers", &files),
+            file("src/app/__init__.py")
+        );
+        assert_eq!(
+            resolver.resolve(from, "..core.models.User", &files),
+            file("src/app/core/models.py")
+        );
+        assert_eq!(
+            resolver.resolve(from, "..core.Thing", &files),
+            file("src/app/core/__init__.py")
+        );
+        assert_eq!(
+            resolver.resolve(from, "..version", &files),
+            file("src/app/__init__.py")
+        );
+        assert_eq!(
+            resolver.resolve(from, "numpy.linalg", &files),
+            Some(Target::External("numpy".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_relative_import_of_own_package() {
+        let files: HashSet<String> = ["pkg/sub.py", "pkg/sub/__init__.py", "pkg/sub/views.py"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        let resolver = PythonResolver::new(&BTreeMap::new(), &files);
+
+        assert_eq!(
+            resolver.resolve("pkg/sub/views.py", ".helper", &files),
+            file("pkg/sub/__init__.py")
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Ruby require resolution.
+//!
+//! `require_relative` paths (extracted as `./...`) are relative to the
+//! requiring file. Plain `require` paths are looked up under `lib/`, where
+//! Ruby projects put their load path, then the project root; gems and the
+//! standard library stay unresolved. The `.rb` extension is optional.
+
+use super::{join, parent_dir};
+use std::collections::HashSet;
+
+pub(super) fn resolve(from: &str, import: &str, files: &HashSet<String>) -> Option<String> {
+    let bases: &[&str] = if import.starts_with("./") || import.starts_with("../") {
+        &[parent_dir(from)]
+    } else {
+        &["lib", ""]
+    };
+    bases.iter().find_map(|base| {
+        let path = join(base, import)?;
+        [format!("{}.rb", path), path]
+            .into_iter()
+            .find(|candidate| files.contains(candidate))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_and_load_path_requires() {
+        let files: HashSet<String> = [
+            "lib/app.rb",
+            "lib/app/models/user.rb",
+            "spec/support/helper.rb",
+            "spec/user_spec.rb",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        let from = "spec/user_spec.rb";
+
+        assert_eq!(
+            resolve(from, "./support/helper", &files).as_deref(),
+            Some("spec/support/helper.rb")
+        );
+        assert_eq!(
+            resolve(from, "../lib/app.rb", &files).as_deref(),
+            Some("lib/app.rb")
+        );
+        assert_eq!(
+            resolve(from, "app/models/user", &files).as_deref(),
+            Some("lib/app/models/user.rb")
+        );
+        assert_eq!(resolve(from, "json", &files), None);
+    }
+}
@@ -0,0 +1,377 @@
+//! Rust module resolution.
+//!
+//! Builds each crate's module tree the way rustc does: starting from the
+//! crate roots Cargo knows about (`src/lib.rs`, `src/main.rs`, `src/bin/*`,
+//! `tests/*`, `examples/*`, `benches/*`, `build.rs`) and following
+//! `mod name;` declarations to `name.rs` or `name/mod.rs`, honouring
+//! `#[path = "..."]`. `use` paths are then walked down that tree from
+//! `crate::`, `self::`, `super::`, a workspace crate's name, or a child
+//! module, and resolve to the file of the deepest module they name.
+//! Anything else is an external crate.
+
+use super::{parent_dir, Target};
+use crate::scan::SourceFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+
+static MOD_DECL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*((?:#\[[^\]]*\]\s*)*)(?:pub(?:\([^)]+\))?\s+)?mod\s+(\w+)\s*([;{])")
+        .unwrap()
+});
+
+static PATH_ATTR_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"#\[\s*path\s*=\s*"([^"]+)"\s*\]"#).unwrap());
+
+/// Crate roots of a package, relative to its directory. `*` matches one path
+/// segment.
+const BIN_ROOTS: &[&str] = &[
+    "src/main.rs",
+    "src/bin/*.rs",
+    "src/bin/*/main.rs",
+    "tests/*.rs",
+    "examples/*.rs",
+    "examples/*/main.rs",
+    "benches/*.rs",
+    "build.rs",
+];
+
+#[derive(Debug, Default)]
+pub(super) struct RustResolver {
+    crates: Vec<CrateTree>,
+    /// Crate index and module path of every file reached from a crate root
+    modules: HashMap<String, (usize, String)>,
+    /// Library crates by the name other crates import them under
+    libraries: HashMap<String, usize>,
+}
+
+/// Module paths of one crate ("" = crate root) mapped to their files
+#[derive(Debug, Default)]
+struct CrateTree {
+    files: HashMap<String, String>,
+}
+
+/// Crate roots declared by one Cargo.toml
+struct CargoPackage {
+    dir: String,
+    lib: Option<(String, String)>,
+    bins: Vec<String>,
+}
+
+impl RustResolver {
+    pub(super) fn new(manifests: &BTreeMap<String, String>, sources: &[SourceFile]) -> Self {
+        let contents: HashMap<&str, &str> = sources
+            .iter()
+            .filter(|s| s.entry.relative_path.ends_with(".rs"))
+            .map(|s| (s.entry.relative_path.as_str(), s.content.as_str()))
+            .collect();
+        let mut paths: Vec<&str> = contents.keys().copied().collect();
+        paths.sort_unstable();
+
+        let mut packages: Vec<CargoPackage> = manifests
+            .iter()
+            .filter(|(path, _)| path.rsplit('/').next() == Some("Cargo.toml"))
+            .filter_map(|(path, content)| CargoPackage::parse(parent_dir(path), content))
+            .collect();
+        if packages.is_empty() {
+            // No Cargo.toml: treat every lib.rs/main.rs as a crate root
+            let named = |name: &str| {
+                paths
+                    .iter()
+                    .filter(|p| p.rsplit('/').next() == Some(name))
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+            };
+            packages.push(CargoPackage {
+                dir: String::new(),
+                lib: None,
+                bins: named("lib.rs")
+                    .into_iter()
+                    .chain(named("main.rs"))
+                    .collect(),
+            });
+        }
+
+        let mut resolver = Self::default();
+
+        // Libraries first, so files shared with a binary belong to the library
+        for package in &packages {
+            if let Some((name, root)) = &package.lib {
+                if contents.contains_key(root.as_str()) {
+                    let index = resolver.add_crate(root, &contents);
+                    resolver.libraries.insert(name.clone(), index);
+                }
+            }
+        }
+        for package in &packages {
+            let mut roots: Vec<String> = package.bins.clone();
+            for pattern in BIN_ROOTS {
+                let pattern = super::join(&package.dir, pattern).unwrap_or_default();
+                roots.extend(
+                    paths
+                        .iter()
+                        .filter(|p| matches_root(&pattern, p))
+                        .map(|p| p.to_string()),
+                );
+            }
+            for root in roots {
+                if contents.contains_key(root.as_str()) && !resolver.modules.contains_key(&root) {
+                    resolver.add_crate(&root, &contents);
+                }
+            }
+        }
+
+        resolver
+    }
+
+    /// Walk the module tree rooted at `root`, returning the new crate's index
+    fn add_crate(&mut self, root: &str, contents: &HashMap<&str, &str>) -> usize {
+        let index = self.crates.len();
+        let mut tree = CrateTree::default();
+        let mut queue = vec![(
+            root.to_string(),
+            String::new(),
+            parent_dir(root).to_string(),
+        )];
+
+        while let Some((file, module, child_dir)) = queue.pop() {
+            if self.modules.contains_key(&file) {
+                continue;
+            }
+            self.modules.insert(file.clone(), (index, module.clone()));
+            tree.files.insert(module.clone(), file.clone());
+
+            let Some(content) = contents.get(file.as_str()) else {
+                continue;
+            };
+            for cap in MOD_DECL_PATTERN.captures_iter(content) {
+                let name = &cap[2];
+                let child = join_module(&module, name);
+
+                if &cap[3] == "{" {
+                    // Inline module: its items live in this file
+                    tree.files.entry(child).or_insert_with(|| file.clone());
+                    continue;
+                }
+
+                let explicit = PATH_ATTR_PATTERN
+                    .captures(&cap[1])
+                    .and_then(|attr| super::join(parent_dir(&file), &attr[1]));
+                let candidates = match explicit {
+                    Some(path) => vec![path],
+                    None => vec![
+                        format!("{}{}.rs", dir_prefix(&child_dir), name),
+                        format!("{}{}/mod.rs", dir_prefix(&child_dir), name),
+                    ],
+                };
+                if let Some(path) = candidates
+                    .into_iter()
+                    .find(|p| contents.contains_key(p.as_str()))
+                {
+                    let grandchild_dir = children_dir(&path);
+                    queue.push((path, child, grandchild_dir));
+                }
+            }
+        }
+
+        self.crates.push(tree);
+        index
+    }
+
+    /// Resolve a `use` path written in `from`
+    pub(super) fn resolve(&self, from: &str, path: &str) -> Option<Target> {
+        let segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty()).collect();
+        let first = *segments.first()?;
+
+        let Some((index, module)) = self.modules.get(from) else {
+            // Not part of any known crate: only absolute crate names are certain
+            return match first {
+                "crate" | "self" | "super" => None,
+                name => match self.libraries.get(name) {
+                    Some(&lib) => self.descend(lib, String::new(), &segments[1..]),
+                    None => Some(Target::External(name.to_string())),
+                },
+            };
+        };
+        let tree = &self.crates[*index];
+
+        match first {
+            "crate" => self.descend(*index, String::new(), &segments[1..]),
+            "self" => self.descend(*index, module.clone(), &segments[1..]),
+            "super" => {
+                let supers = segments.iter().take_while(|s| **s == "super").count();
+                let mut base: Vec<&str> = module.split("::").filter(|s| !s.is_empty()).collect();
+                if supers > base.len() {
+                    return None;
+                }
+                base.truncate(base.len() - supers);
+                self.descend(*index, base.join("::"), &segments[supers..])
+            }
+            name if tree.files.contains_key(&join_module(module, name)) => {
+                self.descend(*index, module.clone(), &segments)
+            }
+            name => match self.libraries.get(name) {
+                Some(&lib) => self.descend(lib, String::new(), &segments[1..]),
+                None => Some(Target::External(name.to_string())),
+            },
+        }
+    }
+
+    /// Follow `segments` down from `module` for as long as they name modules
+    fn descend(&self, index: usize, mut module: String, segments: &[&str]) -> Option<Target> {
+        let tree = &self.crates[index];
+        for segment in segments {
+            let child = join_module(&module, segment);
+            if !tree.files.contains_key(&child) {
+                break;
+            }
+            module = child;
+        }
+        tree.files.get(&module).cloned().map(Target::File)
+    }
+}
+
+impl CargoPackage {
+    fn parse(dir: &str, content: &str) -> Option<Self> {
+        let toml: toml::Value = toml::from_str(content).ok()?;
+        let package = toml.get("package")?;
+        let in_dir = |path: &str| super::join(dir, path);
+
+        let lib_name = toml
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .or_else(|| package.get("name"))
+            .and_then(|name| name.as_str())
+            .map(|name| name.replace('-', "_"));
+        let lib_path = toml
+            .get("lib")
+            .and_then(|lib| lib.get("path"))
+            .and_then(|path| path.as_str())
+            .unwrap_or("src/lib.rs");
+        let lib = lib_name.zip(in_dir(lib_path));
+
+        let bins = toml
+            .get("bin")
+            .and_then(|bins| bins.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|bin| bin.get("path")?.as_str())
+            .filter_map(in_dir)
+            .collect();
+
+        Some(Self {
+            dir: dir.to_string(),
+            lib,
+            bins,
+        })
+    }
+}
+
+/// Directory holding the files of `file`'s child modules: its own directory
+/// for crate roots and `mod.rs`, `a/b/` for `a/b.rs`
+fn children_dir(file: &str) -> String {
+    match file.rsplit('/').next() {
+        Some("mod.rs" | "lib.rs" | "main.rs") => parent_dir(file).to_string(),
+        _ => file.trim_end_matches(".rs").to_string(),
+    }
+}
+
+fn dir_prefix(dir: &str) -> String {
+    if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir)
+    }
+}
+
+fn join_module(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module, name)
+    }
+}
+
+/// Whether `path` matches a crate root pattern where `*` is one segment
+fn matches_root(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(&path)
+            .all(|(p, s)| match p.split_once('*') {
+                Some((prefix, suffix)) => {
+                    s.len() >= prefix.len() + suffix.len()
+                        && s.starts_with(prefix)
+                        && s.ends_with(suffix)
+                }
+                None => p == s,
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::source;
+
+    fn file(path: &str) -> Option<Target> {
+        Some(Target::File(path.to_string()))
+    }
+
+    #[test]
+    fn test_module_tree_layouts() {
+        let manifests = BTreeMap::from([(
+            "Cargo.toml".to_string(),
+            "[package]\nname = \"my-app\"\n".to_string(),
+        )]);
+        let sources = vec![
+            source("src/lib.rs", "pub mod analyze;\nmod util;\n#[path = \"gen/out.rs\"]\nmod generated;\nmod inline { }\n"),
+            source("src/analyze/mod.rs", "pub mod graph;\n"),
+            source("src/analyze/graph.rs", "use super::super::util::helper;\n"),
+            source("src/util.rs", "mod nested;\n"),
+            source("src/util/nested.rs", ""),
+            source("src/gen/out.rs", ""),
+            source("src/main.rs", "use my_app::analyze::graph::FileGraph;\n"),
+        ];
+        let resolver = RustResolver::new(&manifests, &sources);
+
+        let from = "src/analyze/graph.rs";
+        assert_eq!(
+            resolver.resolve(from, "crate::analyze::graph::FileGraph"),
+            file("src/analyze/graph.rs")
+        );
+        assert_eq!(
+            resolver.resolve(from, "crate::util::nested::X"),
+            file("src/util/nested.rs")
+        );
+        assert_eq!(
+            resolver.resolve(from, "super::super::util::helper"),
+            file("src/util.rs")
+        );
+        assert_eq!(
+            resolver.resolve(from, "crate::generated"),
+            file("src/gen/out.rs")
+        );
+        assert_eq!(
+            resolver.resolve(from, "crate::inline::Item"),
+            file("src/lib.rs")
+        );
+        assert_eq!(
+            resolver.resolve("src/analyze/mod.rs", "self::graph"),
+            file("src/analyze/graph.rs")
+        );
+        assert_eq!(
+            resolver.resolve("src/lib.rs", "analyze::graph"),
+            file("src/analyze/graph.rs")
+        );
+        assert_eq!(
+            resolver.resolve("src/main.rs", "my_app::analyze::graph::FileGraph"),
+            file("src/analyze/graph.rs")
+        );
+        assert_eq!(
+            resolver.resolve(from, "std::collections::HashMap"),
+            Some(Target::External("std".to_string()))
+        );
+    }
+}
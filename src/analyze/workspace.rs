@@ -0,0 +1,433 @@
+//! Workspace/monorepo package detection.
+//!
+//! Reads workspace manifests and resolves their member packages:
+//! - Cargo: `[workspace] members` / `exclude` in Cargo.toml
+//! - npm/yarn/pnpm: `workspaces` in package.json, `packages` in pnpm-workspace.yaml
+//! - Go: `use` directives in go.work
+//! - Python: `[tool.uv.workspace] members` / `exclude` in pyproject.toml
+//!
+//! Dependencies are limited to other packages of the same workspace.
+
+use crate::analyze::manifest::parse_manifest;
+use crate::analyze::resolve::parent_dir;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Ecosystem a package belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageEcosystem {
+    Cargo,
+    Npm,
+    Go,
+    Python,
+    Composer,
+    Gem,
+    Pub,
+    Swift,
+}
+
+impl PackageEcosystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageEcosystem::Cargo => "cargo",
+            PackageEcosystem::Npm => "npm",
+            PackageEcosystem::Go => "go",
+            PackageEcosystem::Python => "python",
+            PackageEcosystem::Composer => "composer",
+            PackageEcosystem::Gem => "gem",
+            PackageEcosystem::Pub => "pub",
+            PackageEcosystem::Swift => "swift",
+        }
+    }
+}
+
+/// A member package of a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspacePackage {
+    /// Package name (Go: module path)
+    pub name: String,
+    /// Declared version, if any
+    pub version: Option<String>,
+    /// Package directory relative to project root ("" = project root)
+    pub path: String,
+    /// Ecosystem the package belongs to
+    pub ecosystem: PackageEcosystem,
+    /// Manifest declaring the package
+    pub manifest: String,
+    /// Names of other workspace packages this package depends on
+    pub dependencies: Vec<String>,
+}
+
+/// Member globs declared by one workspace root
+struct WorkspaceRoot {
+    dir: String,
+    ecosystem: PackageEcosystem,
+    members: GlobSet,
+    excludes: GlobSet,
+    /// Whether the root manifest itself also declares a package
+    includes_root: bool,
+    /// `[workspace.package] version` for Cargo members using `version.workspace = true`
+    inherited_version: Option<String>,
+}
+
+/// Detect workspace packages from manifest contents keyed by relative path
+pub fn detect_packages(manifests: &BTreeMap<String, String>) -> Vec<WorkspacePackage> {
+    let roots: Vec<WorkspaceRoot> = manifests
+        .iter()
+        .filter_map(|(path, content)| parse_workspace_root(path, content))
+        .collect();
+
+    let mut packages: Vec<WorkspacePackage> = Vec::new();
+    let mut seen_paths: HashSet<String> = HashSet::new();
+
+    for (manifest, content) in manifests {
+        let Some(package) = parse_manifest(manifest, content) else {
+            continue;
+        };
+        let Some(name) = package.name else {
+            continue;
+        };
+        let dir = parent_dir(manifest).to_string();
+
+        // Roots only come from ecosystems with workspace support
+        let Some(root) = roots
+            .iter()
+            .find(|r| r.ecosystem == package.ecosystem && r.contains(&dir))
+        else {
+            continue;
+        };
+
+        if !seen_paths.insert(dir.clone()) {
+            continue;
+        }
+        let version = if package.inherits_version {
+            root.inherited_version.clone()
+        } else {
+            package.version
+        };
+        packages.push(WorkspacePackage {
+            name,
+            version,
+            path: dir,
+            ecosystem: package.ecosystem,
+            manifest: manifest.clone(),
+            dependencies: package
+                .dependencies
+                .iter()
+                .map(|d| d.package().to_string())
+                .collect(),
+        });
+    }
+
+    // Keep only dependencies on sibling packages of the same ecosystem,
+    // reported under the sibling's declared name
+    let names: HashMap<(PackageEcosystem, String), String> = packages
+        .iter()
+        .map(|p| {
+            (
+                (p.ecosystem, normalize_name(p.ecosystem, &p.name)),
+                p.name.clone(),
+            )
+        })
+        .collect();
+    for package in &mut packages {
+        let deps: BTreeSet<String> = package
+            .dependencies
+            .iter()
+            .filter_map(|d| names.get(&(package.ecosystem, normalize_name(package.ecosystem, d))))
+            .filter(|d| **d != package.name)
+            .cloned()
+            .collect();
+        package.dependencies = deps.into_iter().collect();
+    }
+
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    packages
+}
+
+impl WorkspaceRoot {
+    fn contains(&self, dir: &str) -> bool {
+        if dir == self.dir {
+            return self.includes_root;
+        }
+        self.members.is_match(dir) && !self.excludes.is_match(dir)
+    }
+}
+
+/// Parse a manifest that declares a workspace, if it does
+fn parse_workspace_root(manifest: &str, content: &str) -> Option<WorkspaceRoot> {
+    let dir = parent_dir(manifest).to_string();
+    let file_name = Path::new(manifest).file_name()?.to_str()?;
+
+    let (ecosystem, members, excludes, includes_root, inherited_version) = match file_name {
+        "Cargo.toml" => {
+            let toml: toml::Value = toml::from_str(content).ok()?;
+            let workspace = toml.get("workspace")?;
+            (
+                PackageEcosystem::Cargo,
+                toml_strings(workspace.get("members")),
+                toml_strings(workspace.get("exclude")),
+                toml.get("package").is_some(),
+                workspace
+                    .get("package")
+                    .and_then(|p| p.get("version"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+            )
+        }
+        "package.json" => {
+            let json: serde_json::Value = serde_json::from_str(content).ok()?;
+            let workspaces = json.get("workspaces")?;
+            // Either ["packages/*"] or { "packages": ["packages/*"] } (yarn)
+            let patterns = workspaces.get("packages").unwrap_or(workspaces);
+            let (members, excludes) = split_negations(json_strings(patterns));
+            (PackageEcosystem::Npm, members, excludes, false, None)
+        }
+        "pnpm-workspace.yaml" => {
+            let (members, excludes) = split_negations(parse_pnpm_packages(content));
+            (PackageEcosystem::Npm, members, excludes, false, None)
+        }
+        "go.work" => (
+            PackageEcosystem::Go,
+            parse_go_work_uses(content),
+            Vec::new(),
+            false,
+            None,
+        ),
+        "pyproject.toml" => {
+            let toml: toml::Value = toml::from_str(content).ok()?;
+            let workspace = toml.get("tool")?.get("uv")?.get("workspace")?;
+            (
+                PackageEcosystem::Python,
+                toml_strings(workspace.get("members")),
+                toml_strings(workspace.get("exclude")),
+                toml.get("project").is_some(),
+                None,
+            )
+        }
+        _ => return None,
+    };
+
+    // Members listed as "." refer to the workspace root itself
+    let includes_root = includes_root || members.iter().any(|m| is_current_dir(m));
+
+    Some(WorkspaceRoot {
+        members: build_globs(&dir, &members),
+        excludes: build_globs(&dir, &excludes),
+        dir,
+        ecosystem,
+        includes_root,
+        inherited_version,
+    })
+}
+
+/// Extract `use` directories from go.work (single-line or block form)
+fn parse_go_work_uses(content: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_use_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_use_block {
+            if line == ")" {
+                in_use_block = false;
+            } else if !line.is_empty() {
+                uses.push(line.trim_matches('"').to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_use_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.trim_matches('"').to_string());
+            }
+        }
+    }
+
+    uses
+}
+
+/// Extract the `packages:` list from pnpm-workspace.yaml
+fn parse_pnpm_packages(content: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                packages.push(item.trim().trim_matches(['\'', '"']).to_string());
+            }
+        }
+    }
+
+    packages
+}
+
+/// Python distribution names compare case-insensitively with `-`, `_`, `.` equivalent
+fn normalize_name(ecosystem: PackageEcosystem, name: &str) -> String {
+    match ecosystem {
+        PackageEcosystem::Python => name.to_lowercase().replace(['_', '.'], "-"),
+        _ => name.to_string(),
+    }
+}
+
+fn split_negations(patterns: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let (excludes, members): (Vec<String>, Vec<String>) =
+        patterns.into_iter().partition(|p| p.starts_with('!'));
+    let excludes = excludes
+        .into_iter()
+        .map(|p| p.trim_start_matches('!').to_string())
+        .collect();
+    (members, excludes)
+}
+
+fn toml_strings(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn json_strings(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_current_dir(pattern: &str) -> bool {
+    matches!(pattern.trim_end_matches('/'), "." | "./" | "")
+}
+
+/// Compile member patterns, relative to the workspace root dir, into a glob set
+fn build_globs(dir: &str, patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().filter(|p| !is_current_dir(p)) {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        let full = if dir.is_empty() {
+            pattern.to_string()
+        } else {
+            format!("{}/{}", dir, pattern)
+        };
+        if let Ok(glob) = GlobBuilder::new(&full).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+    builder
+        .build()
+        .unwrap_or_else(|_| GlobSetBuilder::new().build().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifests(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_workspace() {
+        let packages = detect_packages(&manifests(&[
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n\n[workspace.package]\nversion = \"1.2.0\"\n",
+            ),
+            (
+                "crates/core/Cargo.toml",
+                "[package]\nname = \"core\"\nversion.workspace = true\n",
+            ),
+            (
+                "crates/cli/Cargo.toml",
+                "[package]\nname = \"cli\"\nversion = \"0.3.0\"\n\n[dependencies]\ncore = { path = \"../core\" }\nserde = \"1\"\n",
+            ),
+            (
+                "crates/scratch/Cargo.toml",
+                "[package]\nname = \"scratch\"\n",
+            ),
+        ]));
+
+        assert_eq!(packages.len(), 2);
+        let cli = packages.iter().find(|p| p.name == "cli").unwrap();
+        assert_eq!(cli.path, "crates/cli");
+        assert_eq!(cli.version.as_deref(), Some("0.3.0"));
+        assert_eq!(cli.dependencies, vec!["core"]);
+
+        let core = packages.iter().find(|p| p.name == "core").unwrap();
+        assert_eq!(core.version.as_deref(), Some("1.2.0"));
+        assert_eq!(core.ecosystem, PackageEcosystem::Cargo);
+    }
+
+    #[test]
+    fn test_pnpm_workspace() {
+        let packages = detect_packages(&manifests(&[
+            ("package.json", r#"{"name": "root", "private": true}"#),
+            (
+                "pnpm-workspace.yaml",
+                "packages:\n  - 'apps/*'\n  - \"packages/*\"\n  - '!packages/legacy'\n",
+            ),
+            (
+                "apps/web/package.json",
+                r#"{"name": "web", "version": "1.0.0", "dependencies": {"@acme/ui": "workspace:*", "react": "^18"}}"#,
+            ),
+            ("packages/ui/package.json", r#"{"name": "@acme/ui"}"#),
+            ("packages/legacy/package.json", r#"{"name": "legacy"}"#),
+        ]));
+
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "@acme/ui"]);
+        assert_eq!(packages[0].dependencies, vec!["@acme/ui"]);
+    }
+
+    #[test]
+    fn test_go_work_and_uv_workspace() {
+        let packages = detect_packages(&manifests(&[
+            ("go.work", "go 1.22\n\nuse (\n\t./api\n\t./shared\n)\n"),
+            (
+                "api/go.mod",
+                "module example.com/api\n\nrequire (\n\texample.com/shared v0.0.0\n\tgithub.com/pkg/errors v0.9.1\n)\n",
+            ),
+            ("shared/go.mod", "module example.com/shared\n"),
+            (
+                "pyproject.toml",
+                "[project]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"Acme_Lib>=1.0\", \"requests\"]\n\n[tool.uv.workspace]\nmembers = [\"libs/*\"]\n",
+            ),
+            ("libs/acme/pyproject.toml", "[project]\nname = \"acme-lib\"\n"),
+        ]));
+
+        let api = packages
+            .iter()
+            .find(|p| p.name == "example.com/api")
+            .unwrap();
+        assert_eq!(api.ecosystem, PackageEcosystem::Go);
+        assert_eq!(api.dependencies, vec!["example.com/shared"]);
+
+        let app = packages.iter().find(|p| p.name == "app").unwrap();
+        assert_eq!(app.path, "");
+        assert_eq!(app.dependencies, vec!["acme-lib"]);
+    }
+}
@@ -1,6 +1,11 @@
+use anyhow::Context;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::analyze::{LayerRules, ModuleOptions};
+use crate::config::LayerConfig;
+use crate::scan::ScanOptions;
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum Command {
     /// Update agentlens to the latest version
@@ -187,6 +192,14 @@ pub struct Args {
     #[arg(short = 'd', long, default_value = "0")]
     pub depth: usize,
 
+    /// Worker threads for scanning and parsing (0 = one per CPU)
+    #[arg(short = 'j', long, default_value = "0")]
+    pub jobs: usize,
+
+    /// Skip instead of waiting when another run is writing the output directory
+    #[arg(long, default_value = "false")]
+    pub no_wait: bool,
+
     /// Force regenerate all modules (ignore cache)
     #[arg(long, default_value = "false")]
     pub force: bool,
@@ -198,6 +211,26 @@ pub struct Args {
     /// Check if docs are stale (exit 1 if regeneration needed)
     #[arg(long, default_value = "false")]
     pub check: bool,
+
+    /// Check for new dependency cycles (exit 1 if any appeared)
+    #[arg(long, default_value = "false")]
+    pub check_cycles: bool,
+
+    /// Check imports against the [[layer]] rules (exit 1 on violations)
+    #[arg(long, default_value = "false")]
+    pub check_layers: bool,
+
+    /// Explicit module roots from agentlens.toml `[[module]]` entries
+    #[arg(skip)]
+    pub module_roots: Vec<String>,
+
+    /// Extra C/C++ include directories from agentlens.toml `include_dirs`
+    #[arg(skip)]
+    pub include_dirs: Vec<String>,
+
+    /// Architecture layers from agentlens.toml `[[layer]]` entries
+    #[arg(skip)]
+    pub layers: Vec<LayerConfig>,
 }
 
 impl Args {
@@ -250,6 +283,9 @@ impl Args {
                     self.no_gitignore = no_gitignore;
                 }
             }
+            self.module_roots = cfg.modules.into_iter().map(|m| m.path).collect();
+            self.include_dirs = cfg.include_dirs;
+            self.layers = cfg.layers;
         }
 
         self
@@ -263,6 +299,32 @@ impl Args {
         }
     }
 
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            threshold: self.threshold,
+            respect_gitignore: !self.no_gitignore,
+            max_depth: if self.depth > 0 {
+                Some(self.depth)
+            } else {
+                None
+            },
+            ignore_patterns: self.ignore.clone(),
+            languages: self.lang.clone(),
+            jobs: self.jobs,
+        }
+    }
+
+    pub fn layer_rules(&self) -> anyhow::Result<LayerRules> {
+        LayerRules::new(&self.layers).context("invalid [[layer]] rules in agentlens.toml")
+    }
+
+    pub fn module_options(&self) -> ModuleOptions {
+        ModuleOptions {
+            max_depth: self.module_depth,
+            roots: self.module_roots.clone(),
+        }
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         let path_str = self.path.to_string_lossy();
         if path_str.starts_with("https://")
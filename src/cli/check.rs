@@ -1,11 +1,14 @@
 use anyhow::Result;
-use std::collections::HashMap;
 use std::path::Path;
 
-use crate::analyze::detect_modules;
+use crate::analyze::{
+    detect_modules, AnalysisCache, CycleReport, FileGraph, ImportResolver, LayerReport, ModuleInfo,
+    ProjectLayout, ReferenceIndex,
+};
 use crate::cli::Args;
-use crate::emit::{calculate_module_state, Manifest};
-use crate::scan::scan_directory;
+use crate::emit::{calculate_module_states, FileChange, Manifest};
+use crate::runner::analyze_files;
+use crate::scan::{scan_project, ProjectScan};
 use crate::types::FileEntry;
 
 pub struct CheckResult {
@@ -13,9 +16,22 @@ pub struct CheckResult {
     pub stale_modules: Vec<String>,
     pub new_modules: Vec<String>,
     pub removed_modules: Vec<String>,
+    /// Files added, modified or removed in stale and new modules
+    pub changed_files: Vec<FileChange>,
 }
 
-pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
+/// The current project, analysed as far as module state needs
+struct Project {
+    manifest: Manifest,
+    files: Vec<FileEntry>,
+    modules: Vec<ModuleInfo>,
+    file_graph: FileGraph,
+    references: ReferenceIndex,
+    cycles: CycleReport,
+    violations: LayerReport,
+}
+
+fn load_project(args: &Args, work_path: &Path) -> Result<Project> {
     let output_path = if args.output.is_absolute() {
         args.output.clone()
     } else {
@@ -24,27 +40,53 @@ pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
 
     let manifest = Manifest::load(&output_path);
 
-    let max_depth = if args.depth > 0 {
-        Some(args.depth)
-    } else {
-        None
-    };
-
-    let files: Vec<FileEntry> =
-        scan_directory(work_path, args.threshold, !args.no_gitignore, max_depth)?;
-
-    let modules = detect_modules(&files);
+    let ProjectScan { sources, manifests } = scan_project(work_path, &args.scan_options())?;
+    let files: Vec<FileEntry> = sources.iter().map(|s| s.entry.clone()).collect();
+
+    // Import edges and symbol references are part of module state; the cache
+    // keeps this from re-parsing
+    let mut cache = AnalysisCache::load(&output_path);
+    let resolver =
+        ImportResolver::load(work_path, &sources, &manifests).with_include_dirs(&args.include_dirs);
+    let (_, all_symbols, _, file_graph) =
+        analyze_files(&sources, &mut cache, &resolver, args.jobs)?;
+    let references = ReferenceIndex::build(&sources, &all_symbols, &file_graph);
+    let violations = args.layer_rules()?.check(&file_graph);
+
+    let layout = ProjectLayout::from_contents(&manifests);
+    let modules = detect_modules(&files, &layout, &args.module_options());
+    let cycles = CycleReport::detect(&modules, &file_graph);
+
+    Ok(Project {
+        manifest,
+        files,
+        modules,
+        file_graph,
+        references,
+        cycles,
+        violations,
+    })
+}
 
-    let module_states: HashMap<String, _> = modules
-        .iter()
-        .map(|m| {
-            let module_files: Vec<_> = files
-                .iter()
-                .filter(|f| m.files.contains(&f.relative_path))
-                .collect();
-            (m.slug.clone(), calculate_module_state(&module_files))
-        })
-        .collect();
+pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
+    let Project {
+        manifest,
+        files,
+        modules,
+        file_graph,
+        references,
+        cycles,
+        violations,
+    } = load_project(args, work_path)?;
+
+    let module_states = calculate_module_states(
+        &modules,
+        &files,
+        &file_graph,
+        &references,
+        &cycles,
+        &violations,
+    );
 
     let current_slugs: std::collections::HashSet<_> =
         modules.iter().map(|m| m.slug.clone()).collect();
@@ -52,10 +94,12 @@ pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
 
     let mut stale_modules = Vec::new();
     let mut new_modules = Vec::new();
+    let mut changed_files = Vec::new();
 
     for module in &modules {
         let state = &module_states[&module.slug];
         if manifest.needs_regeneration(&module.slug, state) {
+            changed_files.extend(manifest.changed_files(&module.slug, state));
             if manifest_slugs.contains(&module.slug) {
                 stale_modules.push(module.slug.clone());
             } else {
@@ -66,6 +110,10 @@ pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
 
     let removed_modules: Vec<_> = manifest_slugs.difference(&current_slugs).cloned().collect();
 
+    // A file that moved between modules shows up as removed from one and added to the other
+    changed_files.sort_by(|a, b| a.path.cmp(&b.path));
+    changed_files.dedup_by(|a, b| a.path == b.path);
+
     let is_stale =
         !stale_modules.is_empty() || !new_modules.is_empty() || !removed_modules.is_empty();
 
@@ -74,6 +122,7 @@ pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
         stale_modules,
         new_modules,
         removed_modules,
+        changed_files,
     })
 }
 
@@ -92,6 +141,12 @@ pub fn run_check(args: &Args, work_path: &Path) -> Result<i32> {
         if !result.removed_modules.is_empty() {
             eprintln!("  Removed modules: {}", result.removed_modules.join(", "));
         }
+        if !result.changed_files.is_empty() {
+            eprintln!("  Changed files:");
+            for change in &result.changed_files {
+                eprintln!("    {} {}", change.kind.marker(), change.path);
+            }
+        }
 
         eprintln!("\nRun 'agentlens' to regenerate documentation.");
         Ok(1)
@@ -102,3 +157,61 @@ pub fn run_check(args: &Args, work_path: &Path) -> Result<i32> {
         Ok(0)
     }
 }
+
+/// Fail when the import graph has dependency cycles that the last generated
+/// docs didn't record. Without a manifest every cycle is new.
+pub fn run_cycle_check(args: &Args, work_path: &Path) -> Result<i32> {
+    let project = load_project(args, work_path)?;
+    let cycles = &project.cycles;
+    let new_cycles = project.manifest.new_cycles(cycles);
+
+    if new_cycles.is_empty() {
+        if args.verbosity() > 0 {
+            eprintln!(
+                "No new dependency cycles ({} module, {} file cycles already recorded).",
+                cycles.modules.len(),
+                cycles.files.len()
+            );
+        }
+        return Ok(0);
+    }
+
+    eprintln!("New dependency cycles:");
+    for cycle in &new_cycles.modules {
+        eprintln!("  module: {}", cycle.display_path());
+    }
+    for cycle in &new_cycles.files {
+        eprintln!("  file:   {}", cycle.display_path());
+    }
+    eprintln!("\nBreak the cycles, or run 'agentlens' to accept them as the new baseline.");
+    Ok(1)
+}
+
+/// Fail when any import breaks the `[[layer]]` rules in agentlens.toml
+pub fn run_layer_check(args: &Args, work_path: &Path) -> Result<i32> {
+    let rules = args.layer_rules()?;
+    if rules.is_empty() {
+        if args.verbosity() > 0 {
+            eprintln!("No architecture layers configured in agentlens.toml.");
+        }
+        return Ok(0);
+    }
+
+    let project = load_project(args, work_path)?;
+    if project.violations.is_empty() {
+        if args.verbosity() > 0 {
+            eprintln!("No layer rule violations ({} layers checked).", rules.len());
+        }
+        return Ok(0);
+    }
+
+    eprintln!("Layer rule violations:");
+    for violation in &project.violations.violations {
+        eprintln!(
+            "  {} → {} ({})",
+            violation.file, violation.import, violation.rule
+        );
+    }
+    eprintln!("\nFix the imports, or update the [[layer]] rules in agentlens.toml.");
+    Ok(1)
+}
@@ -47,7 +47,7 @@ fi
 # Only run on branch checkout (not file checkout)
 if [ "$3" = "1" ]; then
     if command -v agentlens >/dev/null 2>&1; then
-        agentlens --quiet &
+        agentlens --quiet --no-wait &
     fi
 fi
 "#;
@@ -60,7 +60,7 @@ if [ -n "$AGENTLENS_SKIP" ]; then
 fi
 
 if command -v agentlens >/dev/null 2>&1; then
-    agentlens --quiet &
+    agentlens --quiet --no-wait &
 fi
 "#;
 
@@ -177,9 +177,9 @@ fi
 # Only run on branch checkout (not file checkout)
 if [ "$3" = "1" ]; then
     if command -v agentlens >/dev/null 2>&1; then
-        agentlens --quiet &
+        agentlens --quiet --no-wait &
     elif command -v npx >/dev/null 2>&1; then
-        npx agentlens-cli --quiet &
+        npx agentlens-cli --quiet --no-wait &
     fi
 fi
 "#;
@@ -191,9 +191,9 @@ if [ -n "$AGENTLENS_SKIP" ]; then
 fi
 
 if command -v agentlens >/dev/null 2>&1; then
-    agentlens --quiet &
+    agentlens --quiet --no-wait &
 elif command -v npx >/dev/null 2>&1; then
-    npx agentlens-cli --quiet &
+    npx agentlens-cli --quiet --no-wait &
 fi
 "#;
 
@@ -277,9 +277,9 @@ post-checkout:
       run: |
         if [ -z "$AGENTLENS_SKIP" ] && [ "$LEFTHOOK_GIT_CHECKOUT_TYPE" = "branch" ]; then
           if command -v agentlens >/dev/null 2>&1; then
-            agentlens --quiet &
+            agentlens --quiet --no-wait &
           elif command -v npx >/dev/null 2>&1; then
-            npx agentlens-cli --quiet &
+            npx agentlens-cli --quiet --no-wait &
           fi
         fi
 
@@ -289,9 +289,9 @@ post-merge:
       run: |
         if [ -z "$AGENTLENS_SKIP" ]; then
           if command -v agentlens >/dev/null 2>&1; then
-            agentlens --quiet &
+            agentlens --quiet --no-wait &
           elif command -v npx >/dev/null 2>&1; then
-            npx agentlens-cli --quiet &
+            npx agentlens-cli --quiet --no-wait &
           fi
         fi
 "#;
@@ -628,4 +628,22 @@ mod tests {
         fs::write(temp.path().join(".pre-commit-config.yaml"), "").unwrap();
         assert_eq!(detect_hook_manager(temp.path()), HookManager::PreCommit);
     }
+
+    #[test]
+    fn test_pre_commit_hooks_wait_for_running_generation() {
+        // A skipped pre-commit run would stage stale docs
+        let temp = TempDir::new().unwrap();
+        install_lefthook_hooks(temp.path()).unwrap();
+        install_pre_commit_hooks(temp.path()).unwrap();
+
+        let lefthook = fs::read_to_string(temp.path().join("lefthook.yml")).unwrap();
+        let pre_commit = lefthook.split("post-checkout:").next().unwrap();
+        assert!(pre_commit.contains("agentlens --quiet &&"));
+        assert!(!pre_commit.contains("--no-wait"));
+        assert!(lefthook.contains("agentlens --quiet --no-wait &"));
+
+        let framework = fs::read_to_string(temp.path().join(".pre-commit-config.yaml")).unwrap();
+        assert!(!framework.contains("--no-wait"));
+        assert!(!PRE_COMMIT_HOOK.contains("--no-wait"));
+    }
 }
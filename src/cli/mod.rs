@@ -10,7 +10,7 @@ mod update;
 mod watch;
 
 pub use args::{Args, Command, HooksAction, SkillsAction, TelemetryAction};
-pub use check::run_check;
+pub use check::{run_check, run_cycle_check, run_layer_check};
 pub use hooks::{install_hooks, install_hooks_with_manager, remove_hooks};
 pub use serve::{run_mcp_http_server, run_mcp_server};
 pub use skills::{install_skills, list_skills, remove_skills};
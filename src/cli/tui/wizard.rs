@@ -7,7 +7,7 @@ use std::time::Duration;
 
 use super::theme::{agentlens_theme, print_banner, print_error, print_success, print_summary};
 use crate::cli::{install_hooks_with_manager, run_templates};
-use crate::scan::scan_directory;
+use crate::scan::{scan_directory, ScanOptions};
 use crate::Config;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -200,7 +200,13 @@ struct ProjectInfo {
 }
 
 fn detect_project(path: &Path) -> ProjectInfo {
-    let files = scan_directory(path, 500, true, Some(5)).unwrap_or_default();
+    let options = ScanOptions {
+        threshold: 500,
+        respect_gitignore: true,
+        max_depth: Some(5),
+        ..Default::default()
+    };
+    let files = scan_directory(path, &options).unwrap_or_default();
 
     let mut lang_counts: std::collections::HashMap<String, usize> =
         std::collections::HashMap::new();
@@ -212,7 +218,7 @@ fn detect_project(path: &Path) -> ProjectInfo {
     }
 
     let mut languages: Vec<_> = lang_counts.into_iter().collect();
-    languages.sort_by(|a, b| b.1.cmp(&a.1));
+    languages.sort_by_key(|b| std::cmp::Reverse(b.1));
     let languages: Vec<String> = languages.into_iter().take(3).map(|(l, _)| l).collect();
 
     ProjectInfo {
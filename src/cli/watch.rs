@@ -6,6 +6,7 @@ use std::sync::mpsc::channel;
 use std::time::Duration;
 
 use crate::cli::Args;
+use crate::scan::IGNORE_FILE_NAME;
 
 pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
     let work_path = args.path.canonicalize().context("Failed to resolve path")?;
@@ -32,17 +33,41 @@ pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
         work_path.join(&args.output)
     };
 
+    let mut path_filter = args
+        .scan_options()
+        .path_filter(&work_path)
+        .context("Failed to build ignore rules")?;
+
     loop {
         match rx.recv() {
             Ok(Ok(events)) => {
+                // Edited ignore rules change which files exist, so rebuild the
+                // filter and regenerate even though the files themselves are hidden
+                let rules_changed = events.iter().any(|e| is_ignore_file(&e.path));
+                if rules_changed {
+                    match args.scan_options().path_filter(&work_path) {
+                        Ok(filter) => path_filter = filter,
+                        Err(e) => {
+                            eprintln!("[{}] Error: {:#}\n", timestamp(), e);
+                            continue;
+                        }
+                    }
+                }
+
                 let relevant_events: Vec<_> = events
                     .iter()
                     .filter(|e| e.kind == DebouncedEventKind::Any)
                     .filter(|e| !is_output_path(&e.path, &output_path))
                     .filter(|e| !is_hidden_or_git(&e.path))
+                    .filter(|e| !path_filter.is_ignored(&e.path, e.path.is_dir()))
+                    .filter(|e| {
+                        args.lang.is_empty()
+                            || e.path.is_dir()
+                            || path_filter.includes_file(&e.path)
+                    })
                     .collect();
 
-                if relevant_events.is_empty() {
+                if relevant_events.is_empty() && !rules_changed {
                     continue;
                 }
 
@@ -76,6 +101,11 @@ fn is_output_path(path: &Path, output_path: &Path) -> bool {
     path.starts_with(output_path)
 }
 
+fn is_ignore_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == IGNORE_FILE_NAME || name == ".gitignore")
+}
+
 fn is_hidden_or_git(path: &Path) -> bool {
     path.components().any(|c| {
         c.as_os_str()
@@ -88,3 +118,16 @@ fn is_hidden_or_git(path: &Path) -> bool {
 fn timestamp() -> String {
     chrono::Local::now().format("%H:%M:%S").to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_file_changes_are_detected() {
+        assert!(is_ignore_file(Path::new("/repo/.agentlensignore")));
+        assert!(is_ignore_file(Path::new("/repo/src/.gitignore")));
+        assert!(!is_ignore_file(Path::new("/repo/src/main.rs")));
+        assert!(!is_ignore_file(Path::new("/repo/.git/index")));
+    }
+}
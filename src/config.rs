@@ -18,6 +18,34 @@ pub struct Config {
     pub lang: Vec<String>,
     pub no_gitignore: Option<bool>,
     pub watch: Option<WatchConfig>,
+    #[serde(default, rename = "module")]
+    pub modules: Vec<ModuleConfig>,
+    /// Extra C/C++ include directories, relative to the project root
+    #[serde(default)]
+    pub include_dirs: Vec<String>,
+    #[serde(default, rename = "layer")]
+    pub layers: Vec<LayerConfig>,
+}
+
+/// Explicit module root declared with `[[module]]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModuleConfig {
+    /// Directory path or glob (e.g. "services/*")
+    pub path: String,
+}
+
+/// Architecture layer declared with `[[layer]]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayerConfig {
+    pub name: String,
+    /// Directory paths or globs (e.g. "src/domain", "packages/*/ui")
+    pub paths: Vec<String>,
+    /// Only layers this layer may import (unset = any not forbidden)
+    pub allow: Option<Vec<String>>,
+    /// Layers this layer must not import
+    pub forbid: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -57,7 +85,8 @@ impl Config {
 # Maximum directory depth (0 = unlimited)  
 # depth = 0
 
-# Additional patterns to ignore (in addition to .gitignore)
+# Additional patterns to ignore (gitignore syntax, in addition to
+# .gitignore and a .agentlensignore file at the project root)
 # ignore = ["*.test.ts", "fixtures/", "__mocks__/"]
 
 # Filter by language (empty = all languages)
@@ -69,6 +98,30 @@ impl Config {
 # Watch mode configuration
 # [watch]
 # debounce_ms = 300
+
+# Extra C/C++ include directories (searched before those found in
+# compile_commands.json and directories named `include`)
+# include_dirs = ["third_party/include"]
+
+# Explicit module roots (override automatic module detection)
+# [[module]]
+# path = "services/*"
+
+# Architecture layers; imports breaking their rules are reported in INDEX.md
+# and memory.md, and fail `agentlens --check-layers`
+# [[layer]]
+# name = "domain"
+# paths = ["src/domain"]
+# forbid = ["infra"]
+#
+# [[layer]]
+# name = "infra"
+# paths = ["src/infra"]
+#
+# [[layer]]
+# name = "ui"
+# paths = ["src/ui"]
+# allow = ["api"]    # may only import these layers
 "#
         .to_string()
     }
@@ -113,6 +166,7 @@ output = ".docs"
 threshold = 300
 complex_threshold = 500
 ignore = ["test/", "*.spec.ts"]
+include_dirs = ["vendor/include"]
 
 [watch]
 debounce_ms = 500
@@ -122,9 +176,47 @@ debounce_ms = 500
         assert_eq!(config.threshold, Some(300));
         assert_eq!(config.complex_threshold, Some(500));
         assert_eq!(config.ignore.len(), 2);
+        assert_eq!(config.include_dirs, vec!["vendor/include"]);
         assert_eq!(config.watch.unwrap().debounce_ms, Some(500));
     }
 
+    #[test]
+    fn test_parse_module_roots() {
+        let content = r#"
+module_depth = 2
+
+[[module]]
+path = "services/*"
+
+[[module]]
+path = "libs/core"
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.module_depth, Some(2));
+        assert_eq!(config.modules.len(), 2);
+        assert_eq!(config.modules[0].path, "services/*");
+    }
+
+    #[test]
+    fn test_parse_layers() {
+        let content = r#"
+[[layer]]
+name = "domain"
+paths = ["src/domain"]
+forbid = ["infra"]
+
+[[layer]]
+name = "ui"
+paths = ["src/ui"]
+allow = ["api"]
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.layers.len(), 2);
+        assert_eq!(config.layers[0].forbid, vec!["infra"]);
+        assert!(config.layers[0].allow.is_none());
+        assert_eq!(config.layers[1].allow, Some(vec!["api".to_string()]));
+    }
+
     #[test]
     fn test_find_config_file() {
         let temp = TempDir::new().unwrap();
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
-use crate::analyze::ModuleInfo;
+use crate::analyze::{Dependency, ModuleInfo, SymbolReferences, WorkspacePackage};
 use crate::scan::DiffStat;
 use crate::types::{FileEntry, MemoryEntry, Symbol};
 
@@ -10,6 +10,8 @@ pub struct JsonOutput {
     pub version: String,
     pub generated_at: DateTime<Utc>,
     pub project: ProjectInfo,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageOutput>,
     pub modules: Vec<ModuleOutput>,
     pub files: Vec<FileEntry>,
     pub large_files: Vec<LargeFileEntry>,
@@ -17,6 +19,9 @@ pub struct JsonOutput {
     pub entry_points: Vec<String>,
     pub critical_files: Vec<CriticalFile>,
     pub hub_files: Vec<HubFile>,
+    pub dependencies: Vec<DependencyOutput>,
+    /// Exported symbols referenced from other files
+    pub refs: Vec<SymbolReferences>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff: Option<DiffInfo>,
 }
@@ -41,6 +46,8 @@ pub struct ModuleOutput {
     pub warning_count: usize,
     pub symbol_count: usize,
     pub is_hub: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
 }
 
 impl ModuleOutput {
@@ -76,6 +83,59 @@ impl ModuleOutput {
             warning_count,
             symbol_count,
             is_hub,
+            package: module.package.clone(),
+        }
+    }
+}
+
+/// A workspace package with the slugs of the modules it contains
+#[derive(Serialize, Clone)]
+pub struct PackageOutput {
+    pub name: String,
+    pub version: Option<String>,
+    pub path: String,
+    pub ecosystem: String,
+    pub dependencies: Vec<String>,
+    pub modules: Vec<String>,
+}
+
+impl PackageOutput {
+    pub fn from_package(package: &WorkspacePackage, modules: &[ModuleInfo]) -> Self {
+        Self {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            path: package.path.clone(),
+            ecosystem: package.ecosystem.as_str().to_string(),
+            dependencies: package.dependencies.clone(),
+            modules: modules
+                .iter()
+                .filter(|m| m.package.as_deref() == Some(package.name.as_str()))
+                .map(|m| m.slug.clone())
+                .collect(),
+        }
+    }
+}
+
+/// A third-party package declared by a manifest, with the files importing it
+#[derive(Serialize, Clone)]
+pub struct DependencyOutput {
+    pub name: String,
+    pub version: Option<String>,
+    pub ecosystem: String,
+    pub manifest: String,
+    pub dev: bool,
+    pub used_by: Vec<String>,
+}
+
+impl DependencyOutput {
+    pub fn from_dependency(dependency: &Dependency) -> Self {
+        Self {
+            name: dependency.name.clone(),
+            version: dependency.version.clone(),
+            ecosystem: dependency.ecosystem.as_str().to_string(),
+            manifest: dependency.manifest.clone(),
+            dev: dependency.dev,
+            used_by: dependency.used_by.clone(),
         }
     }
 }
@@ -0,0 +1,69 @@
+//! Advisory lock on the output directory.
+//!
+//! Watch mode, git hooks (which run agentlens in the background) and the MCP
+//! `regenerate` tool can all target the same output directory. Runs that write
+//! outputs hold an exclusive lock on `.lock` so they never interleave.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::Path;
+
+const LOCK_FILE: &str = ".lock";
+
+/// Exclusive lock on an output directory, released when dropped
+#[derive(Debug)]
+pub struct OutputLock {
+    file: File,
+}
+
+impl OutputLock {
+    /// Block until the lock is available
+    pub fn acquire(output_dir: &Path) -> Result<Self> {
+        let file = open_lock_file(output_dir)?;
+        file.lock().context("Failed to lock output directory")?;
+        Ok(Self { file })
+    }
+
+    /// Take the lock if no other run holds it
+    pub fn try_acquire(output_dir: &Path) -> Result<Option<Self>> {
+        let file = open_lock_file(output_dir)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e).context("Failed to lock output directory"),
+        }
+    }
+}
+
+impl Drop for OutputLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn open_lock_file(output_dir: &Path) -> Result<File> {
+    fs::create_dir_all(output_dir)?;
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(output_dir.join(LOCK_FILE))
+        .context("Failed to open lock file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = TempDir::new().unwrap();
+
+        let held = OutputLock::acquire(dir.path()).unwrap();
+        assert!(OutputLock::try_acquire(dir.path()).unwrap().is_none());
+
+        drop(held);
+        assert!(OutputLock::try_acquire(dir.path()).unwrap().is_some());
+    }
+}
@@ -1,10 +1,24 @@
 //! Manifest tracking for incremental regeneration.
 //!
-//! Stores module timestamps to detect changes between runs.
+//! Stores per-file content hashes for each module to detect changes between runs.
+//! Content hashes (rather than mtimes) keep checkouts, `touch` and fresh CI clones
+//! from marking unchanged modules stale.
+//!
+//! Each module also records the import edges crossing its boundary. Its imports.md
+//! lists consumers from other modules, so a module is regenerated when another
+//! module starts or stops importing it even if its own files are unchanged.
+//! Likewise the files referencing each exported symbol are recorded, since L2
+//! file docs list them under "Used By", and the dependency cycles through the
+//! module and the layer rule violations by its imports, which its memory.md
+//! lists. The recorded cycles are also the baseline `--check-cycles` compares
+//! against.
 
+use crate::analyze::{Cycle, CycleReport, FileGraph, LayerReport, ModuleInfo, ReferenceIndex};
+use crate::types::FileEntry;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
@@ -19,18 +33,74 @@ pub struct Manifest {
     /// Timestamp of last full generation
     pub generated_at: u64,
     /// Per-module state
-    pub modules: HashMap<String, ModuleState>,
+    pub modules: BTreeMap<String, ModuleState>,
 }
 
 /// State for a single module
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
 pub struct ModuleState {
-    /// Latest modification time of any file in the module (unix timestamp)
-    pub latest_mtime: u64,
     /// Number of files in the module
     pub file_count: usize,
-    /// Hash of file paths (to detect file additions/removals)
-    pub files_hash: u64,
+    /// SHA-256 content hash (hex) of each file, keyed by relative path
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    /// Import edges crossing the module boundary
+    #[serde(default)]
+    pub edges: ModuleEdges,
+    /// Files referencing each exported symbol, as `file#symbol <- referrer`
+    #[serde(default)]
+    pub references: BTreeSet<String>,
+    /// Dependency cycles through the module
+    #[serde(default)]
+    pub cycles: ModuleCycles,
+    /// Layer rule violations by the module's imports, as `file -> import: rule`
+    #[serde(default)]
+    pub violations: BTreeSet<String>,
+}
+
+/// Members of the dependency cycles a module is part of
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ModuleCycles {
+    /// Slugs of each module-level cycle including this module
+    pub modules: BTreeSet<Vec<String>>,
+    /// Files of each file-level cycle through this module's files
+    pub files: BTreeSet<Vec<String>>,
+}
+
+/// Import edges between a module and the rest of the codebase
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ModuleEdges {
+    /// Slugs of other modules this module imports from
+    pub depends_on: BTreeSet<String>,
+    /// Files in other modules that import this module's files
+    pub consumers: BTreeSet<String>,
+}
+
+/// How a file changed since the manifest was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    /// Single-letter marker, as in `git status --short`
+    pub fn marker(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "A",
+            ChangeKind::Modified => "M",
+            ChangeKind::Removed => "D",
+        }
+    }
+}
+
+/// A file whose content differs from the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: ChangeKind,
 }
 
 impl Manifest {
@@ -51,7 +121,7 @@ impl Manifest {
     pub fn save(&self, output_dir: &Path) -> Result<()> {
         let manifest_path = output_dir.join(MANIFEST_FILE);
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(manifest_path, content)?;
+        super::write_atomic(&manifest_path, content)?;
         Ok(())
     }
 
@@ -60,13 +130,91 @@ impl Manifest {
         match self.modules.get(slug) {
             None => true,
             Some(old_state) => {
-                old_state.latest_mtime != current_state.latest_mtime
-                    || old_state.file_count != current_state.file_count
-                    || old_state.files_hash != current_state.files_hash
+                old_state.file_count != current_state.file_count
+                    || old_state.files != current_state.files
+                    || old_state.edges != current_state.edges
+                    || old_state.references != current_state.references
+                    || old_state.cycles != current_state.cycles
+                    || old_state.violations != current_state.violations
             }
         }
     }
 
+    /// Check if a module's cross-module import edges changed since the last run
+    pub fn edges_changed(&self, slug: &str, current_state: &ModuleState) -> bool {
+        self.modules
+            .get(slug)
+            .is_some_and(|old_state| old_state.edges != current_state.edges)
+    }
+
+    /// Check if the files referencing a module's exported symbols changed since the last run
+    pub fn references_changed(&self, slug: &str, current_state: &ModuleState) -> bool {
+        self.modules
+            .get(slug)
+            .is_some_and(|old_state| old_state.references != current_state.references)
+    }
+
+    /// Cycles in `report` that didn't exist when the manifest was written.
+    /// A cycle counts as existing if all its members were already in one
+    /// recorded cycle, so a cycle that grows to include new members is new.
+    pub fn new_cycles(&self, report: &CycleReport) -> CycleReport {
+        let known = |cycle: &Cycle, recorded: fn(&ModuleCycles) -> &BTreeSet<Vec<String>>| {
+            self.modules
+                .values()
+                .flat_map(|m| recorded(&m.cycles))
+                .any(|members| cycle.is_within(members))
+        };
+        CycleReport {
+            modules: report
+                .modules
+                .iter()
+                .filter(|c| !known(c, |m| &m.modules))
+                .cloned()
+                .collect(),
+            files: report
+                .files
+                .iter()
+                .filter(|c| !known(c, |m| &m.files))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// List files of a module that were added, modified or removed since the last run
+    pub fn changed_files(&self, slug: &str, current_state: &ModuleState) -> Vec<FileChange> {
+        let empty = BTreeMap::new();
+        let old_files = self.modules.get(slug).map_or(&empty, |m| &m.files);
+
+        let mut changes: Vec<FileChange> = current_state
+            .files
+            .iter()
+            .filter_map(|(path, hash)| {
+                let kind = match old_files.get(path) {
+                    None => ChangeKind::Added,
+                    Some(old_hash) if old_hash != hash => ChangeKind::Modified,
+                    Some(_) => return None,
+                };
+                Some(FileChange {
+                    path: path.clone(),
+                    kind,
+                })
+            })
+            .collect();
+
+        changes.extend(
+            old_files
+                .keys()
+                .filter(|path| !current_state.files.contains_key(*path))
+                .map(|path| FileChange {
+                    path: path.clone(),
+                    kind: ChangeKind::Removed,
+                }),
+        );
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+
     /// Update module state in manifest
     pub fn update_module(&mut self, slug: String, state: ModuleState) {
         self.modules.insert(slug, state);
@@ -79,36 +227,121 @@ impl Manifest {
     }
 }
 
-/// Calculate module state from file entries
-pub fn calculate_module_state(files: &[&crate::types::FileEntry]) -> ModuleState {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-
-    let mut latest_mtime: u64 = 0;
-    let mut hasher = DefaultHasher::new();
-
-    for file in files {
-        if let Ok(metadata) = fs::metadata(&file.path) {
-            if let Ok(mtime) = metadata.modified() {
-                if let Ok(duration) = mtime.duration_since(SystemTime::UNIX_EPOCH) {
-                    let timestamp = duration.as_secs();
-                    if timestamp > latest_mtime {
-                        latest_mtime = timestamp;
+/// Calculate the state of every module: content hashes plus import edges,
+/// symbol references and dependency cycles
+pub fn calculate_module_states(
+    modules: &[ModuleInfo],
+    files: &[FileEntry],
+    graph: &FileGraph,
+    references: &ReferenceIndex,
+    cycles: &CycleReport,
+    layers: &LayerReport,
+) -> HashMap<String, ModuleState> {
+    let mut edges = calculate_module_edges(modules, graph);
+
+    modules
+        .iter()
+        .map(|m| {
+            let module_files: Vec<_> = files
+                .iter()
+                .filter(|f| m.files.contains(&f.relative_path))
+                .collect();
+            let mut state = calculate_module_state(&module_files);
+            state.edges = edges.remove(&m.slug).unwrap_or_default();
+            state.references = m
+                .files
+                .iter()
+                .flat_map(|file| references.for_file(file))
+                .flat_map(|s| {
+                    s.used_by
+                        .iter()
+                        .map(move |r| format!("{}#{} <- {}", s.file, s.symbol, r.file))
+                })
+                .collect();
+            let (module_cycles, file_cycles) = cycles.for_module(m);
+            state.cycles = ModuleCycles {
+                modules: module_cycles.iter().map(|c| c.members.clone()).collect(),
+                files: file_cycles.iter().map(|c| c.members.clone()).collect(),
+            };
+            state.violations = layers
+                .for_module(m)
+                .iter()
+                .map(|v| format!("{} -> {}: {}", v.file, v.import, v.rule))
+                .collect();
+            (m.slug.clone(), state)
+        })
+        .collect()
+}
+
+/// Collect the import edges crossing each module's boundary, keyed by slug
+pub fn calculate_module_edges(
+    modules: &[ModuleInfo],
+    graph: &FileGraph,
+) -> HashMap<String, ModuleEdges> {
+    let file_to_module: HashMap<&str, &str> = modules
+        .iter()
+        .flat_map(|m| m.files.iter().map(move |f| (f.as_str(), m.slug.as_str())))
+        .collect();
+
+    modules
+        .iter()
+        .map(|module| {
+            let own_files: HashSet<&str> = module.files.iter().map(|f| f.as_str()).collect();
+            let mut edges = ModuleEdges::default();
+
+            for file in &module.files {
+                for import in graph.imports.get(file).into_iter().flatten() {
+                    if let Some(&target) = file_to_module.get(import.as_str()) {
+                        if target != module.slug {
+                            edges.depends_on.insert(target.to_string());
+                        }
+                    }
+                }
+                for importer in graph.importers.get(file).into_iter().flatten() {
+                    if !own_files.contains(importer.as_str()) {
+                        edges.consumers.insert(importer.clone());
                     }
                 }
             }
-        }
 
-        file.relative_path.hash(&mut hasher);
-    }
+            (module.slug.clone(), edges)
+        })
+        .collect()
+}
+
+/// Calculate module state from file entries by hashing their contents
+pub fn calculate_module_state(files: &[&FileEntry]) -> ModuleState {
+    let files: BTreeMap<String, String> = files
+        .iter()
+        .map(|file| {
+            // Scanned entries carry their hash; anything else is read here.
+            // Unreadable files still count as members, with an empty hash.
+            let hash = if file.content_hash.is_empty() {
+                fs::read(&file.path)
+                    .map(|bytes| hash_content(&bytes))
+                    .unwrap_or_default()
+            } else {
+                file.content_hash.clone()
+            };
+            (file.relative_path.clone(), hash)
+        })
+        .collect();
 
     ModuleState {
-        latest_mtime,
         file_count: files.len(),
-        files_hash: hasher.finish(),
+        files,
+        edges: ModuleEdges::default(),
+        references: BTreeSet::new(),
+        cycles: ModuleCycles::default(),
+        violations: BTreeSet::new(),
     }
 }
 
+/// SHA-256 of file content, hex encoded
+pub fn hash_content(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
 /// Get current unix timestamp
 pub fn current_timestamp() -> u64 {
     SystemTime::now()
@@ -121,92 +354,104 @@ pub fn current_timestamp() -> u64 {
 mod tests {
     use super::*;
 
+    fn state(files: &[(&str, &str)]) -> ModuleState {
+        let files: BTreeMap<String, String> = files
+            .iter()
+            .map(|(path, hash)| (path.to_string(), hash.to_string()))
+            .collect();
+        ModuleState {
+            file_count: files.len(),
+            files,
+            edges: ModuleEdges::default(),
+            references: BTreeSet::new(),
+            cycles: ModuleCycles::default(),
+            violations: BTreeSet::new(),
+        }
+    }
+
     #[test]
     fn test_needs_regeneration_new_module() {
         let manifest = Manifest::default();
-        let state = ModuleState {
-            latest_mtime: 1000,
-            file_count: 5,
-            files_hash: 12345,
-        };
+        let state = state(&[("a.rs", "1")]);
         assert!(manifest.needs_regeneration("new-module", &state));
     }
 
     #[test]
     fn test_needs_regeneration_unchanged() {
         let mut manifest = Manifest::default();
-        let state = ModuleState {
-            latest_mtime: 1000,
-            file_count: 5,
-            files_hash: 12345,
-        };
+        let state = state(&[("a.rs", "1"), ("b.rs", "2")]);
         manifest.update_module("module".to_string(), state.clone());
         assert!(!manifest.needs_regeneration("module", &state));
+        assert!(manifest.changed_files("module", &state).is_empty());
     }
 
     #[test]
-    fn test_needs_regeneration_mtime_changed() {
+    fn test_needs_regeneration_content_changed() {
         let mut manifest = Manifest::default();
-        let old_state = ModuleState {
-            latest_mtime: 1000,
-            file_count: 5,
-            files_hash: 12345,
-        };
-        manifest.update_module("module".to_string(), old_state);
+        manifest.update_module("module".to_string(), state(&[("a.rs", "1"), ("b.rs", "2")]));
 
-        let new_state = ModuleState {
-            latest_mtime: 2000,
-            file_count: 5,
-            files_hash: 12345,
-        };
+        let new_state = state(&[("a.rs", "1"), ("b.rs", "3")]);
         assert!(manifest.needs_regeneration("module", &new_state));
+        assert_eq!(
+            manifest.changed_files("module", &new_state),
+            vec![FileChange {
+                path: "b.rs".to_string(),
+                kind: ChangeKind::Modified,
+            }]
+        );
     }
 
     #[test]
-    fn test_needs_regeneration_file_count_changed() {
+    fn test_needs_regeneration_file_added_and_removed() {
         let mut manifest = Manifest::default();
-        let old_state = ModuleState {
-            latest_mtime: 1000,
-            file_count: 5,
-            files_hash: 12345,
-        };
-        manifest.update_module("module".to_string(), old_state);
+        manifest.update_module("module".to_string(), state(&[("a.rs", "1"), ("b.rs", "2")]));
 
-        let new_state = ModuleState {
-            latest_mtime: 1000,
-            file_count: 6,
-            files_hash: 12345,
-        };
+        let new_state = state(&[("a.rs", "1"), ("c.rs", "2")]);
         assert!(manifest.needs_regeneration("module", &new_state));
+
+        let changes = manifest.changed_files("module", &new_state);
+        let kinds: Vec<_> = changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![("b.rs", ChangeKind::Removed), ("c.rs", ChangeKind::Added)]
+        );
+    }
+
+    #[test]
+    fn test_calculate_module_state_ignores_mtime() {
+        use crate::types::Language;
+        use std::path::PathBuf;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("a.rs");
+        fs::write(&path, "fn a() {}").unwrap();
+
+        let entry = FileEntry {
+            path: PathBuf::from(&path),
+            relative_path: "a.rs".to_string(),
+            extension: Some("rs".to_string()),
+            language: Language::Rust,
+            size_bytes: 9,
+            line_count: 1,
+            is_large: false,
+            content_hash: String::new(),
+        };
+
+        let before = calculate_module_state(&[&entry]);
+        // Rewriting identical content bumps the mtime but not the hash
+        fs::write(&path, "fn a() {}").unwrap();
+        assert_eq!(before, calculate_module_state(&[&entry]));
+
+        fs::write(&path, "fn b() {}").unwrap();
+        assert_ne!(before, calculate_module_state(&[&entry]));
     }
 
     #[test]
     fn test_prune_modules() {
         let mut manifest = Manifest::default();
-        manifest.update_module(
-            "a".to_string(),
-            ModuleState {
-                latest_mtime: 0,
-                file_count: 0,
-                files_hash: 0,
-            },
-        );
-        manifest.update_module(
-            "b".to_string(),
-            ModuleState {
-                latest_mtime: 0,
-                file_count: 0,
-                files_hash: 0,
-            },
-        );
-        manifest.update_module(
-            "c".to_string(),
-            ModuleState {
-                latest_mtime: 0,
-                file_count: 0,
-                files_hash: 0,
-            },
-        );
+        manifest.update_module("a".to_string(), ModuleState::default());
+        manifest.update_module("b".to_string(), ModuleState::default());
+        manifest.update_module("c".to_string(), ModuleState::default());
 
         manifest.prune_modules(&["a".to_string(), "c".to_string()]);
 
@@ -214,4 +459,47 @@ mod tests {
         assert!(!manifest.modules.contains_key("b"));
         assert!(manifest.modules.contains_key("c"));
     }
+
+    #[test]
+    fn test_new_importer_invalidates_dependency() {
+        use crate::analyze::BoundaryType;
+
+        let mut a = ModuleInfo::new("a", BoundaryType::Implicit, None);
+        a.add_file("a/main.rs".to_string());
+        let mut b = ModuleInfo::new("b", BoundaryType::Implicit, None);
+        b.add_file("b/lib.rs".to_string());
+        let modules = vec![a, b];
+
+        let before = calculate_module_edges(&modules, &FileGraph::new());
+        let mut manifest = Manifest::default();
+        for (slug, edges) in before {
+            manifest.update_module(
+                slug,
+                ModuleState {
+                    edges,
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Module a starts importing module b
+        let mut graph = FileGraph::new();
+        graph.add_file("a/main.rs", vec!["b/lib.rs".to_string()]);
+        let after = calculate_module_edges(&modules, &graph);
+
+        let b_state = ModuleState {
+            edges: after["b"].clone(),
+            ..Default::default()
+        };
+        assert!(b_state.edges.consumers.contains("a/main.rs"));
+        assert!(manifest.needs_regeneration("b", &b_state));
+        assert!(manifest.edges_changed("b", &b_state));
+
+        let a_state = ModuleState {
+            edges: after["a"].clone(),
+            ..Default::default()
+        };
+        assert!(a_state.edges.depends_on.contains("b"));
+        assert!(manifest.needs_regeneration("a", &a_state));
+    }
 }
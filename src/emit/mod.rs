@@ -1,9 +1,18 @@
 mod json;
+mod lock;
 mod manifest;
 mod writer;
 
 pub use json::{
-    CriticalFile, DiffInfo, HubFile, JsonOutput, LargeFileEntry, ModuleOutput, ProjectInfo,
+    CriticalFile, DependencyOutput, DiffInfo, HubFile, JsonOutput, LargeFileEntry, ModuleOutput,
+    PackageOutput, ProjectInfo,
+};
+pub use lock::OutputLock;
+pub use manifest::{
+    calculate_module_edges, calculate_module_state, calculate_module_states, current_timestamp,
+    hash_content, ChangeKind, FileChange, Manifest, ModuleCycles, ModuleEdges, ModuleState,
+};
+pub use writer::{
+    slug_to_dir_name, write_atomic, write_hierarchical, HierarchicalOutput, LiveOutputs,
+    ModuleContent,
 };
-pub use manifest::{calculate_module_state, current_timestamp, Manifest, ModuleState};
-pub use writer::{slug_to_dir_name, write_hierarchical, HierarchicalOutput, ModuleContent};
@@ -1,9 +1,22 @@
 //! Output writer for hierarchical content structure.
 
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Directory under the output directory where a run's files are written before
+/// being renamed into place
+const STAGING_DIR: &str = ".staging";
+
+/// Keeps machine-local state out of commits, since the git hooks run
+/// `git add .agentlens/` after regenerating
+const GITIGNORE: &str = "# Local agentlens state, not documentation
+.cache.json
+.lock
+.staging/
+*.tmp
+";
 
 /// Content for a single module
 #[derive(Debug, Clone, Default)]
@@ -25,10 +38,22 @@ pub struct HierarchicalOutput {
     pub index_md: String,
     /// AGENT.md content (AI agent instructions)
     pub agent_md: Option<String>,
+    /// dependencies.md content (third-party package inventory)
+    pub dependencies_md: Option<String>,
     /// Per-module content, keyed by module slug
     pub modules: HashMap<String, ModuleContent>,
     /// Optional L2 file-level docs, keyed by file slug
     pub files: HashMap<String, String>,
+    /// Slugs of every current module and L2 file doc, including those not
+    /// regenerated this run. When set, outputs for anything else are removed.
+    pub live: Option<LiveOutputs>,
+}
+
+/// Module and file slugs whose docs should remain in the output directory
+#[derive(Debug, Clone, Default)]
+pub struct LiveOutputs {
+    pub modules: HashSet<String>,
+    pub files: HashSet<String>,
 }
 
 impl HierarchicalOutput {
@@ -36,8 +61,10 @@ impl HierarchicalOutput {
         Self {
             index_md,
             agent_md: None,
+            dependencies_md: None,
             modules: HashMap::new(),
             files: HashMap::new(),
+            live: None,
         }
     }
 
@@ -45,6 +72,10 @@ impl HierarchicalOutput {
         self.agent_md = Some(content);
     }
 
+    pub fn set_dependencies_md(&mut self, content: String) {
+        self.dependencies_md = Some(content);
+    }
+
     /// Add content for a module
     pub fn add_module(&mut self, slug: String, content: ModuleContent) {
         self.modules.insert(slug, content);
@@ -55,10 +86,17 @@ impl HierarchicalOutput {
         self.files.insert(slug, content);
     }
 
+    /// Enable removal of outputs for modules and files not in `live`
+    pub fn set_live(&mut self, live: LiveOutputs) {
+        self.live = Some(live);
+    }
+
     /// Count total files that would be written
     pub fn file_count(&self) -> usize {
         let agent_count = if self.agent_md.is_some() { 1 } else { 0 };
+        let dependencies_count = if self.dependencies_md.is_some() { 1 } else { 0 };
         1 + agent_count
+            + dependencies_count
             + self
                 .modules
                 .values()
@@ -83,62 +121,199 @@ impl HierarchicalOutput {
     }
 }
 
-/// Write hierarchical output structure
+/// Write hierarchical output structure.
+///
+/// Files are written to a staging directory first and then renamed into place,
+/// so readers never see a half-written doc. The renames are atomic per file,
+/// not for the run as a whole: a reader without the lock can see some docs
+/// from this run next to older ones until the last rename. Callers should hold
+/// an [`OutputLock`](super::OutputLock) so concurrent runs don't share the
+/// staging directory.
+///
+/// Returns the orphaned outputs that were removed (or would be, in dry-run
+/// mode), relative to the output directory.
 pub fn write_hierarchical(
     output_dir: &Path,
     output: &HierarchicalOutput,
     dry_run: bool,
-) -> Result<()> {
+) -> Result<Vec<PathBuf>> {
+    let orphans = find_orphans(output_dir, output);
+
     if dry_run {
-        print_hierarchical_dry_run(output_dir, output);
-        return Ok(());
+        print_hierarchical_dry_run(output_dir, output, &orphans);
+        return Ok(orphans);
     }
 
     fs::create_dir_all(output_dir)?;
+    write_gitignore(output_dir)?;
 
-    fs::write(output_dir.join("INDEX.md"), &output.index_md)?;
+    let staging_dir = output_dir.join(STAGING_DIR);
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    let entries = output_entries(output);
+    let result = stage_entries(&staging_dir, &entries)
+        .and_then(|()| commit_entries(output_dir, &staging_dir, &entries));
+    let _ = fs::remove_dir_all(&staging_dir);
+    result?;
+
+    for orphan in &orphans {
+        let path = output_dir.join(orphan);
+        if path.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
 
-    if let Some(ref agent_md) = output.agent_md {
-        fs::write(output_dir.join("AGENT.md"), agent_md)?;
+    Ok(orphans)
+}
+
+/// Every file to write, relative to the output directory. INDEX.md comes last
+/// so it is never swapped in before the module docs it links to.
+fn output_entries(output: &HierarchicalOutput) -> Vec<(PathBuf, &str)> {
+    let mut entries = Vec::new();
+
+    for (slug, content) in &output.modules {
+        let module_dir = Path::new("modules").join(slug);
+        let sections = [
+            ("MODULE.md", &content.module_md),
+            ("outline.md", &content.outline),
+            ("memory.md", &content.memory),
+            ("imports.md", &content.imports),
+        ];
+        for (name, body) in sections {
+            if !body.is_empty() {
+                entries.push((module_dir.join(name), body.as_str()));
+            }
+        }
     }
 
-    let modules_dir = output_dir.join("modules");
-    if !output.modules.is_empty() {
-        fs::create_dir_all(&modules_dir)?;
+    for (slug, content) in &output.files {
+        entries.push((
+            Path::new("files").join(format!("{}.md", slug)),
+            content.as_str(),
+        ));
     }
 
-    for (slug, content) in &output.modules {
-        let module_dir = modules_dir.join(slug);
-        fs::create_dir_all(&module_dir)?;
+    if let Some(ref agent_md) = output.agent_md {
+        entries.push((PathBuf::from("AGENT.md"), agent_md.as_str()));
+    }
+    if let Some(ref dependencies_md) = output.dependencies_md {
+        entries.push((PathBuf::from("dependencies.md"), dependencies_md.as_str()));
+    }
+    entries.push((PathBuf::from("INDEX.md"), output.index_md.as_str()));
 
-        if !content.module_md.is_empty() {
-            fs::write(module_dir.join("MODULE.md"), &content.module_md)?;
-        }
-        if !content.outline.is_empty() {
-            fs::write(module_dir.join("outline.md"), &content.outline)?;
+    entries
+}
+
+/// Write every entry into the staging directory
+fn stage_entries(staging_dir: &Path, entries: &[(PathBuf, &str)]) -> Result<()> {
+    for (path, content) in entries {
+        let staged = staging_dir.join(path);
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
         }
-        if !content.memory.is_empty() {
-            fs::write(module_dir.join("memory.md"), &content.memory)?;
+        fs::write(staged, content)?;
+    }
+    Ok(())
+}
+
+/// Rename staged files over their targets; each replacement is atomic
+fn commit_entries(
+    output_dir: &Path,
+    staging_dir: &Path,
+    entries: &[(PathBuf, &str)],
+) -> Result<()> {
+    for (path, _) in entries {
+        let target = output_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
         }
-        if !content.imports.is_empty() {
-            fs::write(module_dir.join("imports.md"), &content.imports)?;
+        fs::rename(staging_dir.join(path), target)?;
+    }
+    Ok(())
+}
+
+/// Write the output directory's .gitignore, unless it is already current
+fn write_gitignore(output_dir: &Path) -> Result<()> {
+    let path = output_dir.join(".gitignore");
+    if fs::read_to_string(&path).is_ok_and(|content| content == GITIGNORE) {
+        return Ok(());
+    }
+    write_atomic(&path, GITIGNORE)
+}
+
+/// Write a file by renaming a fully written temp file over it, so readers
+/// never see partial content
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", file_name));
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Find outputs left behind by removed modules and files, plus module docs
+/// that a regenerated module no longer produces
+fn find_orphans(output_dir: &Path, output: &HierarchicalOutput) -> Vec<PathBuf> {
+    let mut orphans = Vec::new();
+    let modules_dir = output_dir.join("modules");
+
+    for (slug, content) in &output.modules {
+        let sections = [
+            ("outline.md", &content.outline),
+            ("memory.md", &content.memory),
+            ("imports.md", &content.imports),
+        ];
+        for (name, body) in sections {
+            if body.is_empty() && modules_dir.join(slug).join(name).is_file() {
+                orphans.push(Path::new("modules").join(slug).join(name));
+            }
         }
     }
 
-    if !output.files.is_empty() {
-        let files_dir = output_dir.join("files");
-        fs::create_dir_all(&files_dir)?;
+    if let Some(ref live) = output.live {
+        if output.dependencies_md.is_none() && output_dir.join("dependencies.md").is_file() {
+            orphans.push(PathBuf::from("dependencies.md"));
+        }
 
-        for (slug, content) in &output.files {
-            fs::write(files_dir.join(format!("{}.md", slug)), content)?;
+        for name in list_dir(&modules_dir) {
+            if modules_dir.join(&name).is_dir() && !live.modules.contains(&name) {
+                orphans.push(Path::new("modules").join(name));
+            }
+        }
+
+        let files_dir = output_dir.join("files");
+        for name in list_dir(&files_dir) {
+            if let Some(slug) = name.strip_suffix(".md") {
+                if !live.files.contains(slug) {
+                    orphans.push(Path::new("files").join(&name));
+                }
+            }
         }
     }
 
-    Ok(())
+    orphans.sort();
+    orphans
+}
+
+fn list_dir(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// Print what would be written in dry-run mode
-fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput) {
+fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput, orphans: &[PathBuf]) {
     println!("Dry run mode - hierarchical structure:");
     println!("  {}/", output_dir.display());
     println!("  ├── INDEX.md ({} bytes)", output.index_md.len());
@@ -147,6 +322,10 @@ fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput) {
         println!("  ├── AGENT.md ({} bytes)", agent_md.len());
     }
 
+    if let Some(ref dependencies_md) = output.dependencies_md {
+        println!("  ├── dependencies.md ({} bytes)", dependencies_md.len());
+    }
+
     if !output.modules.is_empty() {
         println!("  ├── modules/");
 
@@ -195,6 +374,13 @@ fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput) {
         }
     }
 
+    if !orphans.is_empty() {
+        println!("\nWould remove {} orphaned outputs:", orphans.len());
+        for orphan in orphans {
+            println!("  - {}", orphan.display());
+        }
+    }
+
     println!("\nTotal: {} files", output.file_count());
 }
 
@@ -206,6 +392,7 @@ pub fn slug_to_dir_name(slug: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_hierarchical_output_file_count() {
@@ -237,4 +424,58 @@ mod tests {
         assert_eq!(slug_to_dir_name("src-analyze"), "src-analyze");
         assert_eq!(slug_to_dir_name("root"), "root");
     }
+
+    #[test]
+    fn test_write_hierarchical_removes_orphans() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path();
+        fs::create_dir_all(out.join("modules/kept")).unwrap();
+        fs::create_dir_all(out.join("modules/removed")).unwrap();
+        fs::create_dir_all(out.join("files")).unwrap();
+        fs::write(out.join("modules/kept/MODULE.md"), "old").unwrap();
+        fs::write(out.join("modules/kept/memory.md"), "old").unwrap();
+        fs::write(out.join("modules/removed/MODULE.md"), "old").unwrap();
+        fs::write(out.join("files/kept-file.md"), "old").unwrap();
+        fs::write(out.join("files/gone-file.md"), "old").unwrap();
+
+        let mut output = HierarchicalOutput::new("# INDEX".to_string());
+        output.add_module(
+            "kept".to_string(),
+            ModuleContent {
+                module_md: "# Module".to_string(),
+                ..Default::default()
+            },
+        );
+        output.set_live(LiveOutputs {
+            modules: ["kept".to_string()].into_iter().collect(),
+            files: ["kept-file".to_string()].into_iter().collect(),
+        });
+
+        let dry = write_hierarchical(out, &output, true).unwrap();
+        assert_eq!(dry.len(), 3);
+        assert!(out.join("modules/removed").exists());
+
+        let removed = write_hierarchical(out, &output, false).unwrap();
+        assert_eq!(removed, dry);
+        assert!(removed.contains(&PathBuf::from("modules/kept/memory.md")));
+        assert!(!out.join("modules/removed").exists());
+        assert!(!out.join("modules/kept/memory.md").exists());
+        assert!(!out.join("files/gone-file.md").exists());
+        assert!(out.join("modules/kept/MODULE.md").exists());
+        assert!(out.join("files/kept-file.md").exists());
+    }
+
+    #[test]
+    fn test_write_hierarchical_ignores_local_state() {
+        let dir = TempDir::new().unwrap();
+        let output = HierarchicalOutput::new("# INDEX".to_string());
+
+        write_hierarchical(dir.path(), &output, false).unwrap();
+
+        let gitignore = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        for ignored in [".cache.json", ".lock", ".staging/"] {
+            assert!(gitignore.lines().any(|line| line == ignored));
+        }
+        assert!(!gitignore.contains(".manifest.json"));
+    }
 }
@@ -0,0 +1,138 @@
+//! dependencies.md generator.
+//!
+//! Lists every third-party package declared by the project's manifests,
+//! grouped by ecosystem, with the modules that import it. Packages no
+//! scanned file imports are listed separately as removal candidates.
+
+use crate::analyze::{DependencyInventory, ModuleInfo, PackageEcosystem};
+
+/// Generate dependencies.md content
+pub fn generate_dependencies_md(inventory: &DependencyInventory, modules: &[ModuleInfo]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Third-party Dependencies\n\n");
+    output.push_str("[← Back to INDEX](INDEX.md)\n\n");
+
+    if inventory.is_empty() {
+        output.push_str("_No third-party packages declared._\n");
+        return output;
+    }
+
+    let mut ecosystems: Vec<PackageEcosystem> =
+        inventory.dependencies.iter().map(|d| d.ecosystem).collect();
+    ecosystems.dedup();
+
+    for ecosystem in ecosystems {
+        output.push_str(&format!("## {}\n\n", ecosystem.as_str()));
+        output.push_str("| Package | Version | Manifest | Used in |\n");
+        output.push_str("| ------- | ------- | -------- | ------- |\n");
+
+        for dependency in inventory
+            .dependencies
+            .iter()
+            .filter(|d| d.ecosystem == ecosystem)
+        {
+            let name = if dependency.dev {
+                format!("{} (dev)", dependency.name)
+            } else {
+                dependency.name.clone()
+            };
+            let used_in = if dependency.used_by.is_empty() {
+                "-".to_string()
+            } else {
+                let mut slugs: Vec<&str> = modules
+                    .iter()
+                    .filter(|m| dependency.used_by.iter().any(|f| m.files.contains(f)))
+                    .map(|m| m.slug.as_str())
+                    .collect();
+                slugs.sort();
+                let plural = if dependency.used_by.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                };
+                format!(
+                    "{} file{}: {}",
+                    dependency.used_by.len(),
+                    plural,
+                    slugs
+                        .iter()
+                        .map(|s| format!("[{}](modules/{}/MODULE.md)", s, s))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            output.push_str(&format!(
+                "| {} | {} | `{}` | {} |\n",
+                name,
+                dependency.version.as_deref().unwrap_or("-"),
+                dependency.manifest,
+                used_in
+            ));
+        }
+        output.push('\n');
+    }
+
+    let unused: Vec<_> = inventory
+        .dependencies
+        .iter()
+        .filter(|d| d.used_by.is_empty())
+        .collect();
+    if !unused.is_empty() {
+        output.push_str("## Not Imported\n\n");
+        output.push_str(
+            "Declared but not imported by any scanned file. Build tools, plugins and \
+             packages loaded at runtime also appear here.\n\n",
+        );
+        for dependency in unused {
+            output.push_str(&format!(
+                "- `{}` ({}, `{}`)\n",
+                dependency.name,
+                dependency.ecosystem.as_str(),
+                dependency.manifest
+            ));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::{BoundaryType, FileGraph};
+    use crate::types::FileEntry;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_generate_dependencies_md() {
+        let manifests = BTreeMap::from([(
+            "package.json".to_string(),
+            r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"vitest": "^1.0.0"}}"#
+                .to_string(),
+        )]);
+        let files = vec![FileEntry::new(
+            "src/app.tsx".into(),
+            "src/app.tsx".to_string(),
+            0,
+            0,
+            500,
+        )];
+        let mut graph = FileGraph::new();
+        graph.add_file("src/app.tsx", vec!["react".to_string()]);
+        let inventory = DependencyInventory::from_manifests(&manifests, &files, &graph);
+        let mut module = ModuleInfo::new("src", BoundaryType::JsModule, None);
+        module.add_file("src/app.tsx".to_string());
+
+        let result = generate_dependencies_md(&inventory, &[module]);
+
+        assert!(result.contains("## npm"));
+        assert!(result.contains(
+            "| react | ^18.2.0 | `package.json` | 1 file: [src](modules/src/MODULE.md) |"
+        ));
+        assert!(result.contains("| vitest (dev) | ^1.0.0 | `package.json` | - |"));
+        assert!(result.contains("## Not Imported"));
+        assert!(result.contains("- `vitest` (npm, `package.json`)"));
+    }
+}
@@ -3,9 +3,13 @@
 //! Generates deep documentation for exceptionally complex files
 //! that exceed the complexity threshold (>1000 lines OR >50 public symbols).
 
-use crate::analyze::path_to_slug;
+use crate::analyze::{path_to_slug, ReferenceIndex};
+use crate::generate::nest_symbols;
 use crate::types::{FileEntry, MemoryEntry, Symbol, Visibility};
 
+/// Referencing files listed per symbol in the "Used By" table
+const MAX_USED_BY_FILES: usize = 10;
+
 /// Default threshold for L2 file generation
 pub const DEFAULT_COMPLEX_LINES_THRESHOLD: usize = 1000;
 pub const DEFAULT_COMPLEX_SYMBOLS_THRESHOLD: usize = 50;
@@ -35,6 +39,7 @@ pub fn generate_file_doc(
     symbols: &[Symbol],
     memory: &[MemoryEntry],
     module_slug: &str,
+    references: &ReferenceIndex,
 ) -> String {
     let mut output = String::new();
 
@@ -65,7 +70,7 @@ pub fn generate_file_doc(
         output.push_str("| Line | Kind | Name | Visibility | Signature |\n");
         output.push_str("| ---- | ---- | ---- | ---------- | --------- |\n");
 
-        for sym in symbols {
+        for (name, sym) in nest_symbols(symbols) {
             let sig = sym
                 .signature
                 .as_ref()
@@ -74,7 +79,7 @@ pub fn generate_file_doc(
 
             output.push_str(&format!(
                 "| {} | {} | {} | {} | {} |\n",
-                sym.line_range.start, sym.kind, sym.name, sym.visibility, sig
+                sym.line_range.start, sym.kind, name, sym.visibility, sig
             ));
         }
         output.push('\n');
@@ -90,15 +95,56 @@ pub fn generate_file_doc(
         output.push_str("## Public API\n\n");
 
         for sym in public_symbols {
+            if sym.signature.is_none() && sym.doc_comment.is_none() {
+                continue;
+            }
+            output.push_str(&format!("### `{}`\n\n", sym.qualified_name()));
             if let Some(ref sig) = sym.signature {
-                output.push_str(&format!("### `{}`\n\n", sym.name));
                 output.push_str(&format!("```\n{}\n```\n\n", sig));
-                output.push_str(&format!(
-                    "**Line:** {} | **Kind:** {}\n\n",
-                    sym.line_range.start, sym.kind
+            }
+            output.push_str(&format!(
+                "**Line:** {} | **Kind:** {}\n\n",
+                sym.line_range.start, sym.kind
+            ));
+            if let Some(ref doc) = sym.doc_comment {
+                output.push_str(doc);
+                output.push_str("\n\n");
+            }
+        }
+    }
+
+    // Other files referencing this file's exported symbols
+    let referenced: Vec<_> = references
+        .for_file(&file.relative_path)
+        .filter(|s| !s.used_by.is_empty())
+        .collect();
+
+    if !referenced.is_empty() {
+        output.push_str("## Used By\n\n");
+        output.push_str("| Symbol | Files | Used by |\n");
+        output.push_str("| ------ | ----- | ------- |\n");
+
+        for symbol in referenced {
+            let mut users: Vec<String> = symbol
+                .used_by
+                .iter()
+                .take(MAX_USED_BY_FILES)
+                .map(|r| format!("`{}`", r.file))
+                .collect();
+            if symbol.used_by.len() > MAX_USED_BY_FILES {
+                users.push(format!(
+                    "+{} more",
+                    symbol.used_by.len() - MAX_USED_BY_FILES
                 ));
             }
+            output.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                symbol.symbol,
+                symbol.used_by.len(),
+                users.join(", ")
+            ));
         }
+        output.push('\n');
     }
 
     // Memory markers for this file
@@ -136,12 +182,13 @@ pub fn file_path_to_slug(path: &str) -> String {
 }
 
 /// Truncate signature for table display
-fn truncate_signature(sig: &str, max_len: usize) -> String {
-    let sig = sig.replace('\n', " ").replace("  ", " ");
-    if sig.len() <= max_len {
+pub(crate) fn truncate_signature(sig: &str, max_len: usize) -> String {
+    let sig = sig.split_whitespace().collect::<Vec<_>>().join(" ");
+    if sig.chars().count() <= max_len {
         sig
     } else {
-        format!("{}...", &sig[..max_len - 3])
+        let kept: String = sig.chars().take(max_len - 3).collect();
+        format!("{}...", kept)
     }
 }
 
@@ -160,6 +207,7 @@ mod tests {
             size_bytes: 1000,
             line_count: lines,
             is_large: lines > 500,
+            content_hash: String::new(),
         }
     }
 
@@ -171,6 +219,7 @@ mod tests {
             line_range: LineRange { start: 1, end: 10 },
             signature: Some(format!("fn {}()", name)),
             doc_comment: None,
+            parent: None,
         }
     }
 
@@ -217,7 +266,7 @@ mod tests {
         let symbols = vec![make_symbol("my_func", Visibility::Public)];
         let memory: Vec<MemoryEntry> = vec![];
 
-        let result = generate_file_doc(&file, &symbols, &memory, "src");
+        let result = generate_file_doc(&file, &symbols, &memory, "src", &ReferenceIndex::default());
 
         assert!(result.contains("# src/big.rs"));
         assert!(result.contains("Lines:** 1500"));
@@ -226,6 +275,44 @@ mod tests {
         assert!(result.contains("my_func"));
     }
 
+    #[test]
+    fn test_generate_file_doc_includes_full_doc_comment() {
+        let file = make_file("src/big.rs", 1500);
+        let symbol = make_symbol("parse", Visibility::Public)
+            .with_doc_comment("Parse the input.\n\nReturns an error on bad syntax.".to_string());
+
+        let result = generate_file_doc(&file, &[symbol], &[], "src", &ReferenceIndex::default());
+
+        assert!(result.contains("Parse the input.\n\nReturns an error on bad syntax."));
+    }
+
+    #[test]
+    fn test_generate_file_doc_used_by() {
+        use crate::analyze::FileGraph;
+        use crate::scan::SourceFile;
+        use std::collections::HashMap;
+
+        let file = make_file("src/big.rs", 1500);
+        let symbols = vec![make_symbol("parse", Visibility::Public)];
+        let sources = vec![
+            SourceFile {
+                entry: file.clone(),
+                content: "pub fn parse() {}\n".to_string(),
+            },
+            SourceFile {
+                entry: make_file("src/main.rs", 10),
+                content: "fn main() { big::parse(); }\n".to_string(),
+            },
+        ];
+        let all_symbols = HashMap::from([("src/big.rs".to_string(), symbols.clone())]);
+        let references = ReferenceIndex::build(&sources, &all_symbols, &FileGraph::new());
+
+        let result = generate_file_doc(&file, &symbols, &[], "src", &references);
+
+        assert!(result.contains("## Used By"));
+        assert!(result.contains("| `parse` | 1 | `src/main.rs` |"));
+    }
+
     #[test]
     fn test_truncate_signature() {
         let short = "fn foo()";
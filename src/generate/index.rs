@@ -3,9 +3,14 @@
 //! Generates a constant-size O(m) routing table where m = number of modules,
 //! regardless of total file count in the repository.
 
-use crate::analyze::{FileGraph, ModuleInfo};
+use crate::analyze::{
+    module_dependencies, CycleReport, DependencyInventory, FileGraph, LayerReport, ModuleInfo,
+    WorkspacePackage,
+};
 use crate::types::{FileEntry, MemoryEntry};
-use std::collections::{HashMap, HashSet};
+
+/// Layer rule violations listed before the rest are summarised
+const MAX_LISTED_VIOLATIONS: usize = 10;
 
 /// Configuration for INDEX.md generation
 pub struct IndexConfig<'a> {
@@ -21,6 +26,14 @@ pub struct IndexConfig<'a> {
     pub project_name: Option<&'a str>,
     /// File dependency graph (optional, for Mermaid diagram)
     pub file_graph: Option<&'a FileGraph>,
+    /// Workspace packages (empty for single-package projects)
+    pub packages: &'a [WorkspacePackage],
+    /// Third-party packages declared by manifests (optional, links dependencies.md)
+    pub dependencies: Option<&'a DependencyInventory>,
+    /// Dependency cycles (optional, listed under Critical Alerts)
+    pub cycles: Option<&'a CycleReport>,
+    /// Layer rule violations (optional, listed under Critical Alerts)
+    pub violations: Option<&'a LayerReport>,
 }
 
 /// Generate INDEX.md content
@@ -50,8 +63,12 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
 
     // Critical alerts summary
     let warning_count = count_high_priority_warnings(config.memory_entries);
-    if warning_count > 0 {
+    let cycles = config.cycles.filter(|c| !c.is_empty());
+    let violations = config.violations.filter(|v| !v.is_empty());
+    if warning_count > 0 || cycles.is_some() || violations.is_some() {
         output.push_str("## ⚠️ Critical Alerts\n\n");
+    }
+    if warning_count > 0 {
         output.push_str(&format!(
             "**{}** high-priority warnings across {} modules. ",
             warning_count,
@@ -59,54 +76,115 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
         ));
         output.push_str("Check each module's `memory.md` for details.\n\n");
     }
+    if let Some(cycles) = cycles {
+        if !cycles.modules.is_empty() {
+            output.push_str(&format!(
+                "**{}** module dependency cycles:\n\n",
+                cycles.modules.len()
+            ));
+            for cycle in &cycles.modules {
+                output.push_str(&format!("- {}\n", cycle.display_path()));
+            }
+            output.push('\n');
+        }
+        if !cycles.files.is_empty() {
+            let file_count: usize = cycles.files.iter().map(|c| c.members.len()).sum();
+            output.push_str(&format!(
+                "**{}** file import cycles involving {} files. ",
+                cycles.files.len(),
+                file_count
+            ));
+            output.push_str("Affected modules list them in `memory.md`.\n\n");
+        }
+    }
+    if let Some(violations) = violations {
+        output.push_str(&format!(
+            "**{}** layer rule violations:\n\n",
+            violations.violations.len()
+        ));
+        for violation in violations.violations.iter().take(MAX_LISTED_VIOLATIONS) {
+            output.push_str(&format!(
+                "- `{}` → `{}`: {}\n",
+                violation.file, violation.import, violation.rule
+            ));
+        }
+        if violations.violations.len() > MAX_LISTED_VIOLATIONS {
+            output.push_str(&format!(
+                "- ... and {} more (run `agentlens --check-layers` for all)\n",
+                violations.violations.len() - MAX_LISTED_VIOLATIONS
+            ));
+        }
+        output.push('\n');
+    }
+
+    // Package table
+    if !config.packages.is_empty() {
+        output.push_str("## Packages\n\n");
+        output.push_str("| Package | Version | Ecosystem | Path | Modules |\n");
+        output.push_str("| ------- | ------- | --------- | ---- | ------- |\n");
+        for package in config.packages {
+            let module_count = config
+                .modules
+                .iter()
+                .filter(|m| m.package.as_deref() == Some(package.name.as_str()))
+                .count();
+            let path = if package.path.is_empty() {
+                "."
+            } else {
+                package.path.as_str()
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} | `{}` | {} |\n",
+                package.name,
+                package.version.as_deref().unwrap_or("-"),
+                package.ecosystem.as_str(),
+                path,
+                module_count
+            ));
+        }
+        output.push('\n');
+    }
 
     // Module table
     output.push_str("## Modules\n\n");
 
+    // Sort modules by path for consistent output
+    let mut sorted_modules: Vec<_> = config.modules.iter().collect();
+    sorted_modules.sort_by(|a, b| a.path.cmp(&b.path));
+
     if config.modules.is_empty() {
         output.push_str("_No modules detected._\n\n");
+    } else if config.packages.is_empty() {
+        push_module_table(&mut output, &sorted_modules, config);
     } else {
-        output.push_str("| Module | Type | Files | Warnings | Hub |\n");
-        output.push_str("| ------ | ---- | ----- | -------- | --- |\n");
-
-        // Sort modules by path for consistent output
-        let mut sorted_modules: Vec<_> = config.modules.iter().collect();
-        sorted_modules.sort_by(|a, b| a.path.cmp(&b.path));
-
-        for module in sorted_modules {
-            let warning_count = count_module_warnings(module, config.memory_entries);
-            let is_hub = config
-                .hub_modules
+        // Group modules under their workspace package
+        for package in config.packages {
+            let package_modules: Vec<_> = sorted_modules
                 .iter()
-                .any(|(path, _)| *path == module.path);
-            let hub_indicator = if is_hub { "★" } else { "" };
-            let warning_str = if warning_count > 0 {
-                format!("{}", warning_count)
-            } else {
-                "-".to_string()
-            };
-
-            let module_link = if module.slug == "root" {
-                "[root](modules/root/MODULE.md)".to_string()
-            } else {
-                format!("[{}](modules/{}/MODULE.md)", module.path, module.slug)
-            };
+                .copied()
+                .filter(|m| m.package.as_deref() == Some(package.name.as_str()))
+                .collect();
+            if package_modules.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("### {}\n\n", package.name));
+            push_module_table(&mut output, &package_modules, config);
+        }
 
-            output.push_str(&format!(
-                "| {} | {} | {} | {} | {} |\n",
-                module_link,
-                module.boundary_type.as_str(),
-                module.file_count(),
-                warning_str,
-                hub_indicator
-            ));
+        let other_modules: Vec<_> = sorted_modules
+            .iter()
+            .copied()
+            .filter(|m| m.package.is_none())
+            .collect();
+        if !other_modules.is_empty() {
+            output.push_str("### Other\n\n");
+            push_module_table(&mut output, &other_modules, config);
         }
-        output.push('\n');
     }
 
     // Module dependency graph (Mermaid)
     if let Some(graph) = config.file_graph {
-        let module_deps = compute_module_dependencies(config.modules, graph);
+        let module_deps = module_dependencies(config.modules, graph);
         if !module_deps.is_empty() {
             output.push_str("## Module Dependencies\n\n");
             output.push_str("```mermaid\ngraph TD\n");
@@ -122,6 +200,47 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
         }
     }
 
+    // Package dependency graph (Mermaid)
+    let package_deps: Vec<(&str, &str)> = config
+        .packages
+        .iter()
+        .flat_map(|p| {
+            p.dependencies
+                .iter()
+                .map(move |d| (p.name.as_str(), d.as_str()))
+        })
+        .collect();
+    if !package_deps.is_empty() {
+        output.push_str("## Package Dependencies\n\n");
+        output.push_str("```mermaid\ngraph TD\n");
+        for (from_package, to_package) in package_deps {
+            output.push_str(&format!(
+                "    pkg_{}[{}] --> pkg_{}[{}]\n",
+                sanitize_mermaid_id(from_package),
+                from_package,
+                sanitize_mermaid_id(to_package),
+                to_package
+            ));
+        }
+        output.push_str("```\n\n");
+    }
+
+    // Third-party dependency summary
+    if let Some(dependencies) = config.dependencies.filter(|d| !d.is_empty()) {
+        let unused = dependencies
+            .dependencies
+            .iter()
+            .filter(|d| d.used_by.is_empty())
+            .count();
+        output.push_str("## Third-party Dependencies\n\n");
+        output.push_str(&format!(
+            "**{}** declared packages, {} not imported by any scanned file. ",
+            dependencies.dependencies.len(),
+            unused
+        ));
+        output.push_str("See [dependencies.md](dependencies.md) for versions and usage.\n\n");
+    }
+
     // Footer
     output.push_str("---\n\n");
     output.push_str("*Generated by [agentlens](https://github.com/nguyenphutrong/agentlens)*\n");
@@ -129,6 +248,42 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
     output
 }
 
+/// Append a module routing table for the given modules
+fn push_module_table(output: &mut String, modules: &[&ModuleInfo], config: &IndexConfig) {
+    output.push_str("| Module | Type | Files | Warnings | Hub |\n");
+    output.push_str("| ------ | ---- | ----- | -------- | --- |\n");
+
+    for module in modules {
+        let warning_count = count_module_warnings(module, config.memory_entries);
+        let is_hub = config
+            .hub_modules
+            .iter()
+            .any(|(path, _)| *path == module.path);
+        let hub_indicator = if is_hub { "★" } else { "" };
+        let warning_str = if warning_count > 0 {
+            format!("{}", warning_count)
+        } else {
+            "-".to_string()
+        };
+
+        let module_link = if module.slug == "root" {
+            "[root](modules/root/MODULE.md)".to_string()
+        } else {
+            format!("[{}](modules/{}/MODULE.md)", module.path, module.slug)
+        };
+
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            module_link,
+            module.boundary_type.as_str(),
+            module.file_count(),
+            warning_str,
+            hub_indicator
+        ));
+    }
+    output.push('\n');
+}
+
 /// Count high-priority (Warning, Safety, Rule) memory entries
 fn count_high_priority_warnings(entries: &[MemoryEntry]) -> usize {
     use crate::types::Priority;
@@ -161,33 +316,6 @@ fn count_module_warnings(module: &ModuleInfo, entries: &[MemoryEntry]) -> usize
         .count()
 }
 
-fn compute_module_dependencies(modules: &[ModuleInfo], graph: &FileGraph) -> Vec<(String, String)> {
-    let mut deps: HashSet<(String, String)> = HashSet::new();
-
-    let file_to_module: HashMap<&str, &str> = modules
-        .iter()
-        .flat_map(|m| m.files.iter().map(move |f| (f.as_str(), m.slug.as_str())))
-        .collect();
-
-    for module in modules {
-        for file in &module.files {
-            if let Some(imports) = graph.imports.get(file) {
-                for imp in imports {
-                    if let Some(&target_module) = file_to_module.get(imp.as_str()) {
-                        if target_module != module.slug {
-                            deps.insert((module.slug.clone(), target_module.to_string()));
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    let mut result: Vec<_> = deps.into_iter().collect();
-    result.sort();
-    result
-}
-
 fn sanitize_mermaid_id(s: &str) -> String {
     s.chars()
         .map(|c| if c.is_alphanumeric() { c } else { '_' })
@@ -238,7 +366,8 @@ pub fn detect_entry_points(files: &[FileEntry]) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analyze::BoundaryType;
+    use crate::analyze::{BoundaryType, LayerRules};
+    use crate::config::LayerConfig;
     use crate::types::{Language, MemoryKind, Priority};
 
     fn make_module(path: &str, files: Vec<String>) -> ModuleInfo {
@@ -259,6 +388,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_index_md_lists_cycles_and_violations() {
+        let modules = vec![
+            make_module("src/a", vec!["src/a/mod.rs".to_string()]),
+            make_module("src/b", vec!["src/b/mod.rs".to_string()]),
+        ];
+        let mut graph = FileGraph::new();
+        graph.add_file("src/a/mod.rs", vec!["src/b/mod.rs".to_string()]);
+        graph.add_file("src/b/mod.rs", vec!["src/a/mod.rs".to_string()]);
+        let cycles = CycleReport::detect(&modules, &graph);
+        let violations = LayerRules::new(&[
+            LayerConfig {
+                name: "a".to_string(),
+                paths: vec!["src/a".to_string()],
+                forbid: vec!["b".to_string()],
+                ..Default::default()
+            },
+            LayerConfig {
+                name: "b".to_string(),
+                paths: vec!["src/b".to_string()],
+                ..Default::default()
+            },
+        ])
+        .unwrap()
+        .check(&graph);
+
+        let config = IndexConfig {
+            modules: &modules,
+            memory_entries: &[],
+            entry_points: &[],
+            hub_modules: &[],
+            project_name: None,
+            file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: Some(&cycles),
+            violations: Some(&violations),
+        };
+        let result = generate_index_md(&config);
+
+        assert!(result.contains("## ⚠️ Critical Alerts"));
+        assert!(result.contains("**1** module dependency cycles:\n\n- src-a → src-b → src-a\n"));
+        assert!(result.contains("**1** file import cycles involving 2 files."));
+        assert!(result.contains(
+            "**1** layer rule violations:\n\n- `src/a/mod.rs` → `src/b/mod.rs`: `a` must not import `b`\n"
+        ));
+    }
+
     #[test]
     fn test_generate_index_md_basic() {
         let modules = vec![make_module("src", vec!["src/lib.rs".to_string()])];
@@ -270,6 +447,10 @@ mod tests {
             hub_modules: &[],
             project_name: Some("TestProject"),
             file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: None,
+            violations: None,
         };
 
         let result = generate_index_md(&config);
@@ -293,6 +474,10 @@ mod tests {
             hub_modules: &[],
             project_name: None,
             file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: None,
+            violations: None,
         };
 
         let result = generate_index_md(&config);
@@ -315,6 +500,10 @@ mod tests {
             hub_modules: &[("src/utils".to_string(), 10)],
             project_name: None,
             file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: None,
+            violations: None,
         };
 
         let result = generate_index_md(&config);
@@ -333,6 +522,10 @@ mod tests {
             hub_modules: &[],
             project_name: None,
             file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: None,
+            violations: None,
         };
 
         let result = generate_index_md(&config);
@@ -352,6 +545,10 @@ mod tests {
             hub_modules: &[],
             project_name: None,
             file_graph: None,
+            packages: &[],
+            dependencies: None,
+            cycles: None,
+            violations: None,
         };
 
         let result = generate_index_md(&config);
@@ -364,11 +561,12 @@ mod tests {
         FileEntry {
             path: PathBuf::from(relative_path),
             relative_path: relative_path.to_string(),
-            extension: relative_path.split('.').last().map(|s| s.to_string()),
+            extension: relative_path.split('.').next_back().map(|s| s.to_string()),
             language: Language::Rust,
             size_bytes: 1000,
             line_count,
             is_large: line_count > 500,
+            content_hash: String::new(),
         }
     }
 
@@ -384,4 +582,58 @@ mod tests {
         assert!(entries.contains(&"src/main.rs".to_string()));
         assert!(entries.contains(&"src/lib.rs".to_string()));
     }
+
+    #[test]
+    fn test_modules_grouped_by_package() {
+        use crate::analyze::{PackageEcosystem, WorkspacePackage};
+
+        let mut core = make_module(
+            "crates/core/src",
+            vec!["crates/core/src/lib.rs".to_string()],
+        );
+        core.package = Some("core".to_string());
+        let mut cli = make_module("crates/cli/src", vec!["crates/cli/src/main.rs".to_string()]);
+        cli.package = Some("cli".to_string());
+        let modules = vec![core, cli];
+
+        let packages = vec![
+            WorkspacePackage {
+                name: "cli".to_string(),
+                version: Some("0.2.0".to_string()),
+                path: "crates/cli".to_string(),
+                ecosystem: PackageEcosystem::Cargo,
+                manifest: "crates/cli/Cargo.toml".to_string(),
+                dependencies: vec!["core".to_string()],
+            },
+            WorkspacePackage {
+                name: "core".to_string(),
+                version: None,
+                path: "crates/core".to_string(),
+                ecosystem: PackageEcosystem::Cargo,
+                manifest: "crates/core/Cargo.toml".to_string(),
+                dependencies: vec![],
+            },
+        ];
+
+        let config = IndexConfig {
+            modules: &modules,
+            memory_entries: &[],
+            entry_points: &[],
+            hub_modules: &[],
+            project_name: None,
+            file_graph: None,
+            packages: &packages,
+            dependencies: None,
+            cycles: None,
+            violations: None,
+        };
+
+        let result = generate_index_md(&config);
+
+        assert!(result.contains("## Packages"));
+        assert!(result.contains("| cli | 0.2.0 | cargo | `crates/cli` | 1 |"));
+        assert!(result.contains("### core"));
+        assert!(result.contains("## Package Dependencies"));
+        assert!(result.contains("pkg_cli[cli] --> pkg_core[core]"));
+    }
 }
@@ -108,7 +108,7 @@ pub fn get_critical_files(entries: &[MemoryEntry]) -> Vec<(String, usize)> {
         .collect();
 
     // Sort by count descending
-    result.sort_by(|a, b| b.1.cmp(&a.1));
+    result.sort_by_key(|b| std::cmp::Reverse(b.1));
     result
 }
 
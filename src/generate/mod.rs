@@ -1,4 +1,5 @@
 mod agent;
+mod dependencies;
 mod file_doc;
 mod imports;
 mod index;
@@ -8,6 +9,7 @@ mod outline;
 mod templates;
 
 pub use agent::{generate_agent_md, AgentConfig, ProjectSize};
+pub use dependencies::generate_dependencies_md;
 pub use file_doc::{
     file_path_to_slug, generate_file_doc, is_complex_file, DEFAULT_COMPLEX_LINES_THRESHOLD,
     DEFAULT_COMPLEX_SYMBOLS_THRESHOLD,
@@ -16,5 +18,5 @@ pub use imports::generate_imports;
 pub use index::{detect_entry_points, generate_index_md, IndexConfig};
 pub use memory::{generate_memory, get_critical_files};
 pub use module_content::generate_module_content;
-pub use outline::generate_outline;
+pub use outline::{generate_outline, nest_symbols};
 pub use templates::{generate_template, parse_template_types, TemplateConfig, TemplateType};
@@ -3,11 +3,13 @@
 //! Generates per-module documentation files:
 //! - MODULE.md: Module summary and navigation
 //! - outline.md: Symbol maps for large files in this module
-//! - memory.md: Warnings/TODOs for this module
-//! - imports.md: Dependencies within/from this module
+//! - memory.md: Warnings/TODOs, dependency cycles and layer rule violations
+//!   for this module
+//! - imports.md: Dependencies within/from this module and third-party packages used
 
-use crate::analyze::{FileGraph, ModuleInfo};
+use crate::analyze::{CycleReport, DependencyInventory, FileGraph, LayerReport, ModuleInfo};
 use crate::emit::ModuleContent;
+use crate::generate::nest_symbols;
 use crate::types::{FileEntry, MemoryEntry, Priority, Symbol};
 
 const INLINE_THRESHOLD: usize = 500;
@@ -33,12 +35,16 @@ impl SectionContent {
 }
 
 /// Generate all content for a single module
+#[allow(clippy::too_many_arguments)]
 pub fn generate_module_content(
     module: &ModuleInfo,
     files: &[FileEntry],
     symbols: &[(FileEntry, Vec<Symbol>)],
     memory: &[MemoryEntry],
     graph: &FileGraph,
+    dependencies: &DependencyInventory,
+    cycles: &CycleReport,
+    violations: &LayerReport,
 ) -> ModuleContent {
     let module_files: Vec<&FileEntry> = files
         .iter()
@@ -46,8 +52,9 @@ pub fn generate_module_content(
         .collect();
 
     let outline = SectionContent::new(generate_module_outline(module, symbols));
-    let memory_content = SectionContent::new(generate_module_memory(module, memory));
-    let imports = SectionContent::new(generate_module_imports(module, graph));
+    let memory_content =
+        SectionContent::new(generate_module_memory(module, memory, cycles, violations));
+    let imports = SectionContent::new(generate_module_imports(module, graph, dependencies));
 
     let module_md = generate_module_md(module, &module_files, &outline, &memory_content, &imports);
 
@@ -94,10 +101,14 @@ fn generate_module_md(
 
     // Module info
     output.push_str(&format!(
-        "**Type:** {} | **Files:** {}\n\n",
+        "**Type:** {} | **Files:** {}",
         module.boundary_type.as_str(),
         module.file_count()
     ));
+    if let Some(ref package) = module.package {
+        output.push_str(&format!(" | **Package:** {}", package));
+    }
+    output.push_str("\n\n");
 
     // Entry point
     if let Some(ref entry) = module.entry_point {
@@ -204,31 +215,61 @@ fn generate_module_outline(module: &ModuleInfo, symbols: &[(FileEntry, Vec<Symbo
             continue;
         }
 
+        push_symbol_table(&mut output, syms);
+    }
+
+    output
+}
+
+/// Symbol table for one file; adds a description column with each doc
+/// comment's first sentence when any symbol is documented
+pub(crate) fn push_symbol_table(output: &mut String, symbols: &[Symbol]) {
+    let documented = symbols.iter().any(|s| s.doc_comment.is_some());
+
+    if documented {
+        output.push_str("| Line | Kind | Name | Visibility | Description |\n");
+        output.push_str("| ---- | ---- | ---- | ---------- | ----------- |\n");
+    } else {
         output.push_str("| Line | Kind | Name | Visibility |\n");
         output.push_str("| ---- | ---- | ---- | ---------- |\n");
+    }
 
-        for sym in syms {
-            output.push_str(&format!(
-                "| {} | {} | {} | {} |\n",
-                sym.line_range.start, sym.kind, sym.name, sym.visibility
-            ));
+    for (name, sym) in nest_symbols(symbols) {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |",
+            sym.line_range.start, sym.kind, name, sym.visibility
+        ));
+        if documented {
+            let summary = sym.doc_summary().unwrap_or_default();
+            output.push_str(&format!(" {} |", summary.replace('|', "\\|")));
         }
         output.push('\n');
     }
-
-    output
+    output.push('\n');
 }
 
 /// Generate module-scoped memory.md
-/// Returns empty string if no memory markers exist (skips file creation)
-fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String {
+/// Returns empty string if no memory markers or alerts exist (skips file creation)
+fn generate_module_memory(
+    module: &ModuleInfo,
+    memory: &[MemoryEntry],
+    cycles: &CycleReport,
+    violations: &LayerReport,
+) -> String {
     // Filter to only entries in this module
     let module_memory: Vec<_> = memory
         .iter()
         .filter(|e| module.files.contains(&e.source_file))
         .collect();
-
-    if module_memory.is_empty() {
+    let mut alerts = cycle_alerts(module, cycles);
+    alerts.extend(violations.for_module(module).iter().map(|v| {
+        format!(
+            "### `LAYER` ({})\n\n> imports `{}`: {}\n\n",
+            v.file, v.import, v.rule
+        )
+    }));
+
+    if module_memory.is_empty() && alerts.is_empty() {
         return String::new();
     }
 
@@ -238,10 +279,11 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
     output.push_str("[← Back to MODULE](MODULE.md) | [← Back to INDEX](../../INDEX.md)\n\n");
 
     // Count by priority
-    let high = module_memory
-        .iter()
-        .filter(|e| e.priority == Priority::High)
-        .count();
+    let high = alerts.len()
+        + module_memory
+            .iter()
+            .filter(|e| e.priority == Priority::High)
+            .count();
     let med = module_memory
         .iter()
         .filter(|e| e.priority == Priority::Medium)
@@ -260,6 +302,9 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
     // Group by priority
     if high > 0 {
         output.push_str("## 🔴 High Priority\n\n");
+        for alert in &alerts {
+            output.push_str(alert);
+        }
         for entry in module_memory
             .iter()
             .filter(|e| e.priority == Priority::High)
@@ -297,8 +342,47 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
     output
 }
 
+/// High-priority entries for the dependency cycles this module is part of
+fn cycle_alerts(module: &ModuleInfo, cycles: &CycleReport) -> Vec<String> {
+    let (module_cycles, file_cycles) = cycles.for_module(module);
+    let mut alerts = Vec::new();
+
+    for cycle in module_cycles {
+        alerts.push(format!(
+            "### `CYCLE` (module dependencies)\n\n> {}\n\n",
+            backtick_path(&cycle.path)
+        ));
+    }
+    for cycle in file_cycles {
+        // The path closes the loop, so it names its first file twice
+        let extra = cycle.members.len() + 1 - cycle.path.len();
+        let more = if extra > 0 {
+            format!(" (+{} more files in the cycle)", extra)
+        } else {
+            String::new()
+        };
+        alerts.push(format!(
+            "### `CYCLE` (file imports)\n\n> {}{}\n\n",
+            backtick_path(&cycle.path),
+            more
+        ));
+    }
+    alerts
+}
+
+fn backtick_path(path: &[String]) -> String {
+    path.iter()
+        .map(|p| format!("`{}`", p))
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
 /// Generate module-scoped imports.md
-fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
+fn generate_module_imports(
+    module: &ModuleInfo,
+    graph: &FileGraph,
+    dependencies: &DependencyInventory,
+) -> String {
     let mut output = String::new();
 
     output.push_str("# Imports\n\n");
@@ -306,13 +390,19 @@ fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
 
     let mut internal_deps: Vec<String> = Vec::new();
     let mut external_deps: Vec<String> = Vec::new();
+    let mut packages: Vec<String> = Vec::new();
     let mut consumers: Vec<String> = Vec::new();
 
     for file in &module.files {
         // Get what this file imports
         if let Some(imports) = graph.imports.get(file) {
             for imp in imports {
-                if module.files.iter().any(|f| f.contains(imp)) {
+                if let Some(package) = dependencies.package_for(file, imp) {
+                    let label = format!("`{}` ({})", package.name, package.ecosystem.as_str());
+                    if !packages.contains(&label) {
+                        packages.push(label);
+                    }
+                } else if module.files.iter().any(|f| f.contains(imp)) {
                     if !internal_deps.contains(imp) {
                         internal_deps.push(imp.clone());
                     }
@@ -334,9 +424,14 @@ fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
 
     internal_deps.sort();
     external_deps.sort();
+    packages.sort();
     consumers.sort();
 
-    if internal_deps.is_empty() && external_deps.is_empty() && consumers.is_empty() {
+    if internal_deps.is_empty()
+        && external_deps.is_empty()
+        && packages.is_empty()
+        && consumers.is_empty()
+    {
         output.push_str("_No import relationships detected._\n");
         return output;
     }
@@ -393,6 +488,15 @@ fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
         output.push('\n');
     }
 
+    if !packages.is_empty() {
+        output.push_str("## Third-party Packages\n\n");
+        output.push_str("Declared packages used by this module:\n\n");
+        for package in &packages {
+            output.push_str(&format!("- {}\n", package));
+        }
+        output.push('\n');
+    }
+
     if !consumers.is_empty() {
         output.push_str("## Consumers\n\n");
         output.push_str("Files from other modules that import from this module:\n\n");
@@ -426,7 +530,8 @@ fn path_to_module_name(path: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analyze::BoundaryType;
+    use crate::analyze::{BoundaryType, LayerRules};
+    use crate::config::LayerConfig;
     use crate::types::{Language, MemoryKind};
     use std::path::PathBuf;
 
@@ -447,13 +552,14 @@ mod tests {
             size_bytes: 1000,
             line_count: lines,
             is_large: lines > 500,
+            content_hash: String::new(),
         }
     }
 
     #[test]
     fn test_generate_module_md_with_separate_files() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let outline = SectionContent {
@@ -482,7 +588,7 @@ mod tests {
     #[test]
     fn test_generate_module_md_with_inline_content() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let imports_content = "# Imports\n\n[nav]\n\nExternal: crate_a".to_string();
@@ -509,7 +615,7 @@ mod tests {
     #[test]
     fn test_generate_module_md_no_docs() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let outline = SectionContent {
@@ -543,12 +649,38 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_generate_module_outline_shows_doc_summary() {
+        use crate::types::{SymbolKind, Visibility};
+
+        let module = make_module("src/big", vec!["src/big/lib.rs".to_string()]);
+        let symbol = Symbol::new(
+            SymbolKind::Function,
+            "run".to_string(),
+            3,
+            Visibility::Public,
+        )
+        .with_doc_comment("Run the job. Retries on failure.\n\nDetails.".to_string());
+        let symbols = vec![(make_file("src/big/lib.rs", 800), vec![symbol])];
+
+        let result = generate_module_outline(&module, &symbols);
+
+        assert!(result.contains("| Description |"));
+        assert!(result.contains("| 3 | fn | run | pub | Run the job. |"));
+        assert!(!result.contains("Details."));
+    }
+
     #[test]
     fn test_generate_module_memory_empty() {
         let module = make_module("src/clean", vec!["src/clean/mod.rs".to_string()]);
         let memory: Vec<MemoryEntry> = vec![];
 
-        let result = generate_module_memory(&module, &memory);
+        let result = generate_module_memory(
+            &module,
+            &memory,
+            &CycleReport::default(),
+            &LayerReport::default(),
+        );
 
         assert!(result.is_empty());
     }
@@ -564,19 +696,87 @@ mod tests {
             priority: Priority::High,
         }];
 
-        let result = generate_module_memory(&module, &memory);
+        let result = generate_module_memory(
+            &module,
+            &memory,
+            &CycleReport::default(),
+            &LayerReport::default(),
+        );
 
         assert!(result.contains("High Priority"));
         assert!(result.contains("This is dangerous"));
     }
 
+    #[test]
+    fn test_generate_module_memory_lists_cycles_and_violations() {
+        let module = make_module("src/a", vec!["src/a/mod.rs".to_string()]);
+        let other = make_module("src/b", vec!["src/b/mod.rs".to_string()]);
+        let mut graph = FileGraph::new();
+        graph.add_file("src/a/mod.rs", vec!["src/b/mod.rs".to_string()]);
+        graph.add_file("src/b/mod.rs", vec!["src/a/mod.rs".to_string()]);
+        let cycles = CycleReport::detect(&[module.clone(), other], &graph);
+
+        let violations = LayerRules::new(&[
+            LayerConfig {
+                name: "a".to_string(),
+                paths: vec!["src/a".to_string()],
+                allow: Some(Vec::new()),
+                ..Default::default()
+            },
+            LayerConfig {
+                name: "b".to_string(),
+                paths: vec!["src/b".to_string()],
+                ..Default::default()
+            },
+        ])
+        .unwrap()
+        .check(&graph);
+
+        let result = generate_module_memory(&module, &[], &cycles, &violations);
+
+        assert!(result.contains("| 3 | 0 | 0 |"));
+        assert!(result.contains(
+            "### `LAYER` (src/a/mod.rs)\n\n> imports `src/b/mod.rs`: `a` must not import other layers"
+        ));
+        assert!(result.contains("> `src-a` → `src-b` → `src-a`"));
+        assert!(result.contains("> `src/a/mod.rs` → `src/b/mod.rs` → `src/a/mod.rs`\n"));
+    }
+
     #[test]
     fn test_generate_module_imports_empty() {
         let module = make_module("src/isolated", vec!["src/isolated/mod.rs".to_string()]);
         let graph = FileGraph::new();
 
-        let result = generate_module_imports(&module, &graph);
+        let result = generate_module_imports(&module, &graph, &DependencyInventory::default());
 
         assert!(result.contains("No import relationships"));
     }
+
+    #[test]
+    fn test_generate_module_imports_third_party_packages() {
+        use std::collections::BTreeMap;
+
+        let module = make_module("src/api", vec!["src/api/mod.rs".to_string()]);
+        let mut graph = FileGraph::new();
+        graph.add_file(
+            "src/api/mod.rs",
+            vec!["serde_json".to_string(), "src/types/mod.rs".to_string()],
+        );
+        let manifests = BTreeMap::from([(
+            "Cargo.toml".to_string(),
+            "[dependencies]\nserde_json = \"1\"\n".to_string(),
+        )]);
+        let files = [
+            make_file("src/api/mod.rs", 100),
+            make_file("src/types/mod.rs", 100),
+        ];
+        let dependencies = DependencyInventory::from_manifests(&manifests, &files, &graph);
+
+        let result = generate_module_imports(&module, &graph, &dependencies);
+
+        assert!(result.contains("## Third-party Packages"));
+        assert!(result.contains("- `serde_json` (cargo)"));
+        assert!(!result.contains("- `serde_json`\n"));
+        assert!(result.contains("- `src/types/mod.rs`"));
+    }
 }
@@ -1,5 +1,69 @@
+use crate::generate::file_doc::truncate_signature;
+use crate::generate::module_content::push_symbol_table;
 use crate::types::{FileEntry, Symbol};
 
+/// Order symbols as a tree, each child directly after its parent, paired with
+/// the name to display. Children are indented under their parent; a symbol
+/// whose parent isn't in the list stays at the top level with a qualified name
+/// such as `Server::run`.
+pub fn nest_symbols(symbols: &[Symbol]) -> Vec<(String, &Symbol)> {
+    let parent_of: Vec<Option<usize>> = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, sym)| {
+            let name = sym.parent.as_deref()?;
+            let candidates: Vec<usize> = (0..symbols.len())
+                .filter(|&j| j != i && symbols[j].is_container() && symbols[j].name == name)
+                .collect();
+            candidates
+                .iter()
+                .copied()
+                .find(|&j| {
+                    let range = &symbols[j].line_range;
+                    range.start <= sym.line_range.start && sym.line_range.end <= range.end
+                })
+                .or_else(|| candidates.first().copied())
+        })
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); symbols.len()];
+    let mut roots = Vec::new();
+    for (i, parent) in parent_of.iter().enumerate() {
+        match parent {
+            Some(p) => children[*p].push(i),
+            None => roots.push(i),
+        }
+    }
+
+    let mut nested = Vec::with_capacity(symbols.len());
+    let mut visited = vec![false; symbols.len()];
+    let mut stack: Vec<(usize, usize)> = roots.iter().rev().map(|&i| (i, 0)).collect();
+
+    loop {
+        while let Some((i, depth)) = stack.pop() {
+            if std::mem::replace(&mut visited[i], true) {
+                continue;
+            }
+            let sym = &symbols[i];
+            let label = if depth == 0 {
+                sym.qualified_name()
+            } else {
+                format!("{}↳ {}", "\u{a0}\u{a0}".repeat(depth), sym.name)
+            };
+            nested.push((label, sym));
+            stack.extend(children[i].iter().rev().map(|&c| (c, depth + 1)));
+        }
+
+        // Symbols whose parents form a cycle are never reached from a root
+        match visited.iter().position(|v| !v) {
+            Some(i) => stack.push((i, 0)),
+            None => break,
+        }
+    }
+
+    nested
+}
+
 pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
     if files.is_empty() {
         return "# Outline\n\nNo large files found in this repository.".to_string();
@@ -32,16 +96,7 @@ pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
         if symbols.is_empty() {
             output.push_str("_No symbols extracted._\n\n");
         } else {
-            output.push_str("| Line | Kind | Name | Visibility |\n");
-            output.push_str("| ---- | ---- | ---- | ---------- |\n");
-
-            for sym in symbols {
-                output.push_str(&format!(
-                    "| {} | {} | {} | {} |\n",
-                    sym.line_range.start, sym.kind, sym.name, sym.visibility
-                ));
-            }
-            output.push('\n');
+            push_symbol_table(&mut output, symbols);
 
             let key_entries: Vec<_> = symbols
                 .iter()
@@ -58,7 +113,11 @@ pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
                 output.push_str("### Key Entry Points\n\n");
                 for sym in key_entries {
                     let sig = sym.signature.as_deref().unwrap_or(&sym.name);
-                    output.push_str(&format!("- `{}` (L{})\n", sig, sym.line_range.start));
+                    output.push_str(&format!(
+                        "- `{}` (L{})\n",
+                        truncate_signature(sig, 100),
+                        sym.line_range.start
+                    ));
                 }
                 output.push('\n');
             }
@@ -69,3 +128,36 @@ pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SymbolKind, Visibility};
+
+    #[test]
+    fn test_nest_symbols() {
+        let symbols = vec![
+            Symbol::new(SymbolKind::Struct, "Server".into(), 1, Visibility::Public)
+                .with_line_range(1, 3),
+            Symbol::new(
+                SymbolKind::Function,
+                "helper".into(),
+                5,
+                Visibility::Private,
+            ),
+            Symbol::new(SymbolKind::Method, "Run".into(), 10, Visibility::Public)
+                .with_parent("Server".into()),
+            Symbol::new(SymbolKind::Method, "Close".into(), 20, Visibility::Public)
+                .with_parent("Client".into()),
+        ];
+
+        let names: Vec<_> = nest_symbols(&symbols)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Server", "\u{a0}\u{a0}↳ Run", "helper", "Client::Close"]
+        );
+    }
+}
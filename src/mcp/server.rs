@@ -19,6 +19,7 @@ use tokio::sync::RwLock;
 use crate::analyze::extract_symbols;
 use crate::cli::check::check_staleness;
 use crate::cli::Args;
+use crate::generate::nest_symbols;
 use crate::scan::scan_directory;
 use crate::types::{Symbol, Visibility};
 
@@ -34,6 +35,14 @@ pub struct GetOutlineParams {
     pub file: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesParams {
+    #[schemars(description = "Exported symbol name (a function, type or constant)")]
+    pub symbol: String,
+    #[schemars(description = "Defining file, if several files export the name")]
+    pub file: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct AgentlensServer {
     work_path: Arc<PathBuf>,
@@ -55,13 +64,27 @@ impl AgentlensServer {
 
     #[tool(description = "Regenerate agentlens documentation for the codebase")]
     async fn regenerate(&self) -> Result<CallToolResult, McpError> {
-        let mut args = self.args.write().await;
+        let mut args = self.args.read().await.clone();
         args.force = true;
+        // Report a concurrent run (CLI, watch mode, a hook) instead of
+        // blocking this handler until it releases the output lock
+        args.no_wait = true;
+
+        let work_path = Arc::clone(&self.work_path);
+        let result =
+            tokio::task::spawn_blocking(move || crate::runner::try_run_analysis(&args, &work_path))
+                .await
+                .map_err(|e| {
+                    McpError::internal_error(format!("Regeneration panicked: {}", e), None)
+                })?;
 
-        match crate::runner::run_analysis(&args, &self.work_path) {
-            Ok(()) => Ok(CallToolResult::success(vec![Content::text(
+        match result {
+            Ok(true) => Ok(CallToolResult::success(vec![Content::text(
                 "Documentation regenerated successfully",
             )])),
+            Ok(false) => Ok(CallToolResult::success(vec![Content::text(
+                "Generation already running; try again when it finishes",
+            )])),
             Err(e) => Ok(CallToolResult::success(vec![Content::text(format!(
                 "Regeneration failed: {}",
                 e
@@ -128,6 +151,7 @@ impl AgentlensServer {
                     "stale_modules": result.stale_modules,
                     "new_modules": result.new_modules,
                     "removed_modules": result.removed_modules,
+                    "changed_files": result.changed_files,
                 });
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&response).unwrap_or_default(),
@@ -156,19 +180,9 @@ impl AgentlensServer {
         }
 
         let args = self.args.read().await;
-        let max_depth = if args.depth > 0 {
-            Some(args.depth)
-        } else {
-            None
-        };
 
-        let files = scan_directory(
-            &self.work_path,
-            args.threshold,
-            !args.no_gitignore,
-            max_depth,
-        )
-        .map_err(|e| McpError::internal_error(format!("Scan failed: {}", e), None))?;
+        let files = scan_directory(&self.work_path, &args.scan_options())
+            .map_err(|e| McpError::internal_error(format!("Scan failed: {}", e), None))?;
 
         let file_entry = files.iter().find(|f| &f.relative_path == file);
 
@@ -188,6 +202,31 @@ impl AgentlensServer {
             )),
         }
     }
+
+    #[tool(
+        description = "Find the files and lines referencing an exported symbol, to assess the impact of changing it"
+    )]
+    async fn find_references(
+        &self,
+        Parameters(params): Parameters<FindReferencesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let args = self.args.read().await;
+
+        let references = crate::runner::load_references(&args, &self.work_path)
+            .map_err(|e| McpError::internal_error(format!("Analysis failed: {}", e), None))?;
+        let definitions = references.find(&params.symbol, params.file.as_deref());
+
+        if definitions.is_empty() {
+            return Err(McpError::invalid_params(
+                format!("Exported symbol '{}' not found", params.symbol),
+                Some(json!({ "symbol": params.symbol, "file": params.file })),
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&definitions).unwrap_or_default(),
+        )]))
+    }
 }
 
 fn format_symbols_as_outline(file_path: &str, symbols: &[Symbol]) -> String {
@@ -199,7 +238,7 @@ fn format_symbols_as_outline(file_path: &str, symbols: &[Symbol]) -> String {
     output.push_str("| Line | Kind | Name | Visibility |\n");
     output.push_str("| ---- | ---- | ---- | ---------- |\n");
 
-    for sym in symbols {
+    for (name, sym) in nest_symbols(symbols) {
         let visibility = match sym.visibility {
             Visibility::Public => "pub",
             Visibility::Private => "(private)",
@@ -208,7 +247,7 @@ fn format_symbols_as_outline(file_path: &str, symbols: &[Symbol]) -> String {
         };
         output.push_str(&format!(
             "| {} | {} | {} | {} |\n",
-            sym.line_range.start, sym.kind, sym.name, visibility
+            sym.line_range.start, sym.kind, name, visibility
         ));
     }
 
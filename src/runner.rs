@@ -1,52 +1,93 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
-use std::fs;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use crate::analyze::{
-    detect_modules, extract_imports, extract_memory_markers, extract_symbols, FileGraph,
+    detect_modules, extract_imports, extract_memory_markers, extract_symbols, AnalysisCache,
+    CachedAnalysis, CycleReport, DependencyInventory, FileGraph, ImportResolver, ModuleInfo,
+    ProjectLayout, ReferenceIndex, WorkspacePackage,
 };
 use crate::cli::Args;
 use crate::emit::{
-    calculate_module_state, current_timestamp, write_hierarchical, HierarchicalOutput, Manifest,
+    calculate_module_states, current_timestamp, write_hierarchical, CriticalFile, DependencyOutput,
+    DiffInfo, HierarchicalOutput, HubFile, JsonOutput, LargeFileEntry, LiveOutputs, Manifest,
+    ModuleOutput, OutputLock, PackageOutput, ProjectInfo,
 };
 use crate::generate::{
-    detect_entry_points, file_path_to_slug, generate_agent_md, generate_file_doc,
-    generate_index_md, generate_module_content, is_complex_file, AgentConfig, IndexConfig,
+    detect_entry_points, file_path_to_slug, generate_agent_md, generate_dependencies_md,
+    generate_file_doc, generate_index_md, generate_module_content, get_critical_files,
+    is_complex_file, AgentConfig, IndexConfig,
+};
+use crate::scan::{
+    get_default_branch, get_diff_files, get_git_head, is_git_repo, scan_project, ProjectScan,
+    SourceFile,
 };
-use crate::scan::{get_default_branch, get_diff_files, get_git_head, is_git_repo, scan_directory};
 use crate::types::{FileEntry, MemoryEntry, Symbol};
 
+/// Scan and analyse the project, then write the hierarchical docs or, with
+/// `--json`, print the analysis to stdout
 pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
+    try_run_analysis(args, work_path).map(|_| ())
+}
+
+/// [`run_analysis`], returning `false` when `--no-wait` skipped the run
+/// because another run holds the output lock
+pub fn try_run_analysis(args: &Args, work_path: &Path) -> Result<bool> {
     if args.verbosity() > 0 && !args.json {
         eprintln!("Scanning: {}", work_path.display());
     }
 
-    let diff_file_set = get_diff_file_set(args, work_path);
-
-    let max_depth = if args.depth > 0 {
-        Some(args.depth)
-    } else {
-        None
-    };
+    let diff = get_diff(args, work_path);
+    let diff_file_set: Option<HashSet<String>> = diff
+        .as_ref()
+        .map(|diff| diff.files.iter().map(|s| s.path.clone()).collect());
 
-    let files = scan_directory(work_path, args.threshold, !args.no_gitignore, max_depth)
-        .context("Failed to scan directory")?;
+    let ProjectScan { sources, manifests } =
+        scan_project(work_path, &args.scan_options()).context("Failed to scan directory")?;
 
-    let files: Vec<_> = if let Some(ref diff_set) = diff_file_set {
-        files
+    let sources: Vec<_> = if let Some(ref diff_set) = diff_file_set {
+        sources
             .into_iter()
-            .filter(|f| diff_set.contains(&f.relative_path))
+            .filter(|s| diff_set.contains(&s.entry.relative_path))
             .collect()
     } else {
-        files
+        sources
     };
+    let files: Vec<FileEntry> = sources.iter().map(|s| s.entry.clone()).collect();
 
     if args.verbosity() > 0 && !args.json {
         eprintln!("  Files scanned: {}", files.len());
     }
 
-    let (all_memory, all_symbols, large_file_symbols, file_graph) = analyze_files(&files)?;
+    let output_path = if args.output.is_absolute() {
+        args.output.clone()
+    } else {
+        work_path.join(&args.output)
+    };
+
+    // Held until the run returns, after the manifest and cache are saved
+    let _lock = if args.dry_run || args.json {
+        None
+    } else {
+        match lock_output(args, &output_path)? {
+            Some(lock) => Some(lock),
+            None => return Ok(false),
+        }
+    };
+
+    let mut cache = load_analysis_cache(args, &output_path);
+    let resolver =
+        ImportResolver::load(work_path, &sources, &manifests).with_include_dirs(&args.include_dirs);
+    let (all_memory, all_symbols, large_file_symbols, file_graph) =
+        analyze_files(&sources, &mut cache, &resolver, args.jobs)?;
+    save_analysis_cache(
+        args,
+        &output_path,
+        &mut cache,
+        &files,
+        diff_file_set.is_none(),
+    )?;
 
     if args.verbosity() > 0 && !args.json {
         eprintln!(
@@ -64,26 +105,69 @@ pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
         eprintln!("  Hub files (3+ importers): {}", hub_files.len());
     }
 
-    let output_path = if args.output.is_absolute() {
-        args.output.clone()
-    } else {
-        work_path.join(&args.output)
-    };
+    let references = ReferenceIndex::build(&sources, &all_symbols, &file_graph);
+    let layout = ProjectLayout::from_contents(&manifests);
+    let modules = detect_modules(&files, &layout, &args.module_options());
+    let dependencies = DependencyInventory::from_manifests(&manifests, &files, &file_graph);
+
+    if args.json {
+        return run_json_output(
+            work_path,
+            &files,
+            &modules,
+            &layout.packages,
+            &dependencies,
+            &references,
+            &large_file_symbols,
+            &all_memory,
+            &entry_points,
+            &hub_files,
+            diff,
+        )
+        .map(|()| true);
+    }
 
     run_hierarchical_output(
         args,
         work_path,
         &output_path,
         &files,
+        &modules,
+        &layout.packages,
         &all_symbols,
         &all_memory,
         &file_graph,
+        &references,
+        &dependencies,
         &entry_points,
         &hub_files,
     )
+    .map(|()| true)
+}
+
+/// Build the symbol cross-reference index for the whole project without
+/// writing any output. Unchanged files are read from the analysis cache.
+pub fn load_references(args: &Args, work_path: &Path) -> Result<ReferenceIndex> {
+    let output_path = if args.output.is_absolute() {
+        args.output.clone()
+    } else {
+        work_path.join(&args.output)
+    };
+
+    let ProjectScan { sources, manifests } =
+        scan_project(work_path, &args.scan_options()).context("Failed to scan directory")?;
+    let mut cache = load_analysis_cache(args, &output_path);
+    let resolver =
+        ImportResolver::load(work_path, &sources, &manifests).with_include_dirs(&args.include_dirs);
+    let (_, all_symbols, _, file_graph) =
+        analyze_files(&sources, &mut cache, &resolver, args.jobs)?;
+
+    Ok(ReferenceIndex::build(&sources, &all_symbols, &file_graph))
 }
 
-fn get_diff_file_set(args: &Args, work_path: &Path) -> Option<std::collections::HashSet<String>> {
+/// Files changed against the `--diff` base, if the flag is set and this is
+/// a git repository
+fn get_diff(args: &Args, work_path: &Path) -> Option<DiffInfo> {
     args.diff.as_ref()?;
 
     if !is_git_repo(work_path) {
@@ -101,65 +185,199 @@ fn get_diff_file_set(args: &Args, work_path: &Path) -> Option<std::collections::
         eprintln!("  Diff mode: comparing against {}", base_ref);
     }
 
-    get_diff_files(work_path, &base_ref).map(|stats| stats.iter().map(|s| s.path.clone()).collect())
+    let files = get_diff_files(work_path, &base_ref)?;
+    Some(DiffInfo { base_ref, files })
 }
 
-type AnalysisResult = (
+pub type AnalysisResult = (
     Vec<MemoryEntry>,
     HashMap<String, Vec<Symbol>>,
     Vec<(FileEntry, Vec<Symbol>)>,
     FileGraph,
 );
 
-fn analyze_files(files: &[FileEntry]) -> Result<AnalysisResult> {
+/// Lock the output directory for a run that writes to it. Waits for a
+/// concurrent run to finish, or returns `None` with `--no-wait` so the caller
+/// can skip.
+pub fn lock_output(args: &Args, output_path: &Path) -> Result<Option<OutputLock>> {
+    if let Some(lock) = OutputLock::try_acquire(output_path)? {
+        return Ok(Some(lock));
+    }
+
+    if args.no_wait {
+        if args.verbosity() > 0 {
+            eprintln!(
+                "Another agentlens run is updating {}, skipping",
+                output_path.display()
+            );
+        }
+        return Ok(None);
+    }
+
+    if args.verbosity() > 0 {
+        eprintln!("  Waiting for another agentlens run to finish...");
+    }
+    OutputLock::acquire(output_path).map(Some)
+}
+
+/// Load the per-file analysis cache; `--force` starts from an empty one
+pub fn load_analysis_cache(args: &Args, output_path: &Path) -> AnalysisCache {
+    if args.force {
+        AnalysisCache::default()
+    } else {
+        AnalysisCache::load(output_path)
+    }
+}
+
+/// Persist the analysis cache unless this is a dry run or JSON output.
+/// Entries for deleted files are pruned only when `files` is the full scan.
+pub fn save_analysis_cache(
+    args: &Args,
+    output_path: &Path,
+    cache: &mut AnalysisCache,
+    files: &[FileEntry],
+    is_full_scan: bool,
+) -> Result<()> {
+    if args.verbosity() > 0 && !args.json {
+        let (hits, misses) = cache.stats();
+        eprintln!("  Analysis cache: {} hits, {} parsed", hits, misses);
+    }
+
+    if args.dry_run || args.json {
+        return Ok(());
+    }
+    if is_full_scan {
+        cache.retain_files(files);
+    }
+    cache
+        .save(output_path)
+        .context("Failed to save analysis cache")
+}
+
+/// Extract symbols, imports and memory markers for every file,
+/// reusing cached results for files whose content is unchanged.
+/// Imports are resolved to project files with `resolver` before they enter
+/// the file graph; resolution isn't cached since it depends on other files.
+/// Cache misses are parsed on `jobs` threads (0 = one per CPU); results are
+/// assembled in input order so output does not depend on scheduling.
+pub fn analyze_files(
+    sources: &[SourceFile],
+    cache: &mut AnalysisCache,
+    resolver: &ImportResolver,
+    jobs: usize,
+) -> Result<AnalysisResult> {
+    let mut analyses: Vec<Option<CachedAnalysis>> = sources
+        .iter()
+        .map(|s| {
+            cache
+                .get(&s.entry.relative_path, &s.entry.content_hash)
+                .cloned()
+        })
+        .collect();
+
+    let misses: Vec<&SourceFile> = sources
+        .iter()
+        .zip(&analyses)
+        .filter(|(_, cached)| cached.is_none())
+        .map(|(source, _)| source)
+        .collect();
+
+    let parsed = parallel_map(&misses, jobs, |source| CachedAnalysis {
+        hash: source.entry.content_hash.clone(),
+        symbols: extract_symbols(&source.entry, &source.content),
+        imports: extract_imports(&source.entry, &source.content),
+        memory: extract_memory_markers(&source.content, &source.entry.relative_path),
+    });
+
+    let mut parsed = parsed.into_iter();
+    for (source, slot) in sources.iter().zip(analyses.iter_mut()) {
+        if slot.is_none() {
+            let analysis = parsed.next().expect("one parse result per cache miss");
+            cache.insert(source.entry.relative_path.clone(), analysis.clone());
+            *slot = Some(analysis);
+        }
+    }
+
     let mut all_memory: Vec<MemoryEntry> = Vec::new();
     let mut all_symbols: HashMap<String, Vec<Symbol>> = HashMap::new();
     let mut large_file_symbols: Vec<(FileEntry, Vec<Symbol>)> = Vec::new();
     let mut file_graph = FileGraph::new();
 
-    for file in files {
-        let content = match fs::read_to_string(&file.path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-
-        let memory_entries = extract_memory_markers(&content, &file.relative_path);
-        all_memory.extend(memory_entries);
+    for (source, analysis) in sources.iter().zip(analyses) {
+        let analysis = analysis.expect("every file analyzed");
+        let file = &source.entry;
 
-        let imports = extract_imports(file, &content);
-        file_graph.add_file(&file.relative_path, imports);
-
-        let symbols = extract_symbols(file, &content);
-        all_symbols.insert(file.relative_path.clone(), symbols.clone());
+        all_memory.extend(analysis.memory);
+        file_graph.add_file(
+            &file.relative_path,
+            resolver.resolve_all(file, &analysis.imports),
+        );
 
         if file.is_large {
-            large_file_symbols.push((file.clone(), symbols));
+            large_file_symbols.push((file.clone(), analysis.symbols.clone()));
         }
+        all_symbols.insert(file.relative_path.clone(), analysis.symbols);
     }
 
     Ok((all_memory, all_symbols, large_file_symbols, file_graph))
 }
 
+/// Map `f` over `items` on up to `jobs` scoped threads (0 = one per CPU),
+/// returning results in input order
+fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = if jobs == 0 {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        jobs
+    };
+
+    if jobs <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(jobs);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<R>>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("analysis worker panicked"))
+            .collect()
+    })
+}
+
+/// Generate INDEX.md, AGENT.md and the module and file docs that are stale,
+/// then record the new module states in the manifest
 #[allow(clippy::too_many_arguments)]
-fn run_hierarchical_output(
+pub fn run_hierarchical_output(
     args: &Args,
     work_path: &Path,
     output_path: &Path,
     files: &[FileEntry],
+    modules: &[ModuleInfo],
+    packages: &[WorkspacePackage],
     all_symbols: &HashMap<String, Vec<Symbol>>,
     all_memory: &[MemoryEntry],
     file_graph: &FileGraph,
+    references: &ReferenceIndex,
+    dependencies: &DependencyInventory,
     entry_points: &[String],
     hub_files: &[(String, usize)],
 ) -> Result<()> {
-    let modules = detect_modules(files);
-
     if args.verbosity() > 0 {
         eprintln!("  Modules detected: {}", modules.len());
     }
 
     if args.verbosity() > 1 {
-        for module in &modules {
+        for module in modules {
             eprintln!(
                 "    {} ({} files, {:?})",
                 module.slug,
@@ -175,16 +393,10 @@ fn run_hierarchical_output(
         Manifest::load(output_path)
     };
 
-    let module_states: HashMap<String, _> = modules
-        .iter()
-        .map(|m| {
-            let module_files: Vec<_> = files
-                .iter()
-                .filter(|f| m.files.contains(&f.relative_path))
-                .collect();
-            (m.slug.clone(), calculate_module_state(&module_files))
-        })
-        .collect();
+    let violations = args.layer_rules()?.check(file_graph);
+    let cycles = CycleReport::detect(modules, file_graph);
+    let module_states =
+        calculate_module_states(modules, files, file_graph, references, &cycles, &violations);
 
     let modules_to_regenerate: Vec<_> = modules
         .iter()
@@ -202,6 +414,21 @@ fn run_hierarchical_output(
         );
     }
 
+    if args.verbosity() > 1 && !args.force {
+        for module in &modules_to_regenerate {
+            let state = &module_states[&module.slug];
+            for change in manifest.changed_files(&module.slug, state) {
+                eprintln!("    {} {}", change.kind.marker(), change.path);
+            }
+            if manifest.edges_changed(&module.slug, state) {
+                eprintln!("    {}: import edges changed", module.slug);
+            }
+            if manifest.references_changed(&module.slug, state) {
+                eprintln!("    {}: symbol references changed", module.slug);
+            }
+        }
+    }
+
     let hub_module_slugs: Vec<(String, usize)> = hub_files
         .iter()
         .filter_map(|(path, count)| {
@@ -213,15 +440,23 @@ fn run_hierarchical_output(
         .collect();
 
     let index_config = IndexConfig {
-        modules: &modules,
+        modules,
         memory_entries: all_memory,
         entry_points,
         hub_modules: &hub_module_slugs,
         project_name: None,
         file_graph: Some(file_graph),
+        packages,
+        dependencies: args.diff.is_none().then_some(dependencies),
+        cycles: Some(&cycles),
+        violations: Some(&violations),
     };
     let index_md = generate_index_md(&index_config);
     let mut output = HierarchicalOutput::new(index_md);
+    // A --diff run only sees part of the graph, so usage would be undercounted
+    if args.diff.is_none() && !dependencies.is_empty() {
+        output.set_dependencies_md(generate_dependencies_md(dependencies, modules));
+    }
 
     let warning_count = all_memory
         .iter()
@@ -230,7 +465,7 @@ fn run_hierarchical_output(
 
     let git_head = get_git_head(work_path);
     let agent_config = AgentConfig {
-        modules: &modules,
+        modules,
         total_files: files.len(),
         warning_count,
         git_head: git_head.as_deref(),
@@ -263,6 +498,9 @@ fn run_hierarchical_output(
             &large_file_symbols,
             &module_memory,
             file_graph,
+            dependencies,
+            &cycles,
+            &violations,
         );
 
         output.add_module(module.slug.clone(), content);
@@ -279,14 +517,31 @@ fn run_hierarchical_output(
                     .filter(|m| &m.source_file == file_path)
                     .cloned()
                     .collect();
-                let file_doc = generate_file_doc(file, symbols, &file_memory, &module.slug);
+                let file_doc =
+                    generate_file_doc(file, symbols, &file_memory, &module.slug, references);
                 let file_slug = file_path_to_slug(&file.relative_path);
                 output.add_file(file_slug, file_doc);
             }
         }
     }
 
-    write_hierarchical(output_path, &output, args.dry_run)
+    // A --diff run only sees part of the tree, so it can't tell what is orphaned
+    if args.diff.is_none() {
+        let live_files = files
+            .iter()
+            .filter(|f| {
+                let symbols = all_symbols.get(&f.relative_path).map_or(&[][..], |v| v);
+                is_complex_file(f, symbols, args.complex_threshold, 50)
+            })
+            .map(|f| file_path_to_slug(&f.relative_path))
+            .collect();
+        output.set_live(LiveOutputs {
+            modules: modules.iter().map(|m| m.slug.clone()).collect(),
+            files: live_files,
+        });
+    }
+
+    let removed = write_hierarchical(output_path, &output, args.dry_run)
         .context("Failed to write hierarchical outputs")?;
 
     if !args.dry_run {
@@ -308,6 +563,9 @@ fn run_hierarchical_output(
         if output.agent_md.is_some() {
             eprintln!("  {}/AGENT.md", output_path.display());
         }
+        if output.dependencies_md.is_some() {
+            eprintln!("  {}/dependencies.md", output_path.display());
+        }
         eprintln!(
             "  {}/modules/ ({} modules regenerated)",
             output_path.display(),
@@ -320,7 +578,92 @@ fn run_hierarchical_output(
                 output.files.len()
             );
         }
+        if !removed.is_empty() {
+            eprintln!("  Removed {} orphaned outputs:", removed.len());
+            for path in &removed {
+                eprintln!("    {}", path.display());
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Print the analysis as JSON instead of writing docs
+#[allow(clippy::too_many_arguments)]
+fn run_json_output(
+    work_path: &Path,
+    files: &[FileEntry],
+    modules: &[ModuleInfo],
+    packages: &[WorkspacePackage],
+    dependencies: &DependencyInventory,
+    references: &ReferenceIndex,
+    large_file_symbols: &[(FileEntry, Vec<Symbol>)],
+    all_memory: &[MemoryEntry],
+    entry_points: &[String],
+    hub_files: &[(String, usize)],
+    diff: Option<DiffInfo>,
+) -> Result<()> {
+    let critical_files = get_critical_files(all_memory);
+    let module_outputs: Vec<ModuleOutput> = modules
+        .iter()
+        .map(|m| ModuleOutput::from_module_info(m, all_memory, large_file_symbols, hub_files))
+        .collect();
+
+    let json_output = JsonOutput {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: Utc::now(),
+        project: ProjectInfo {
+            path: work_path.display().to_string(),
+            files_scanned: files.len(),
+            large_files_count: large_file_symbols.len(),
+            memory_markers_count: all_memory.len(),
+            modules_count: modules.len(),
+        },
+        packages: packages
+            .iter()
+            .map(|p| PackageOutput::from_package(p, modules))
+            .collect(),
+        modules: module_outputs,
+        files: files.to_vec(),
+        large_files: large_file_symbols
+            .iter()
+            .map(|(f, syms)| LargeFileEntry {
+                path: f.relative_path.clone(),
+                line_count: f.line_count,
+                language: format!("{:?}", f.language),
+                symbols: syms.clone(),
+            })
+            .collect(),
+        memory: all_memory.to_vec(),
+        entry_points: entry_points.to_vec(),
+        critical_files: critical_files
+            .iter()
+            .map(|(path, count)| CriticalFile {
+                path: path.clone(),
+                high_priority_markers: *count,
+            })
+            .collect(),
+        hub_files: hub_files
+            .iter()
+            .map(|(path, count)| HubFile {
+                path: path.clone(),
+                imported_by: *count,
+            })
+            .collect(),
+        dependencies: dependencies
+            .dependencies
+            .iter()
+            .map(DependencyOutput::from_dependency)
+            .collect(),
+        refs: references
+            .symbols()
+            .iter()
+            .filter(|s| !s.used_by.is_empty())
+            .cloned()
+            .collect(),
+        diff,
+    };
+    println!("{}", json_output.to_json());
+    Ok(())
+}
@@ -1,5 +1,73 @@
 use crate::types::Language;
-use std::path::Path;
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Repo-level ignore file, gitignore syntax, applied on top of .gitignore
+pub const IGNORE_FILE_NAME: &str = ".agentlensignore";
+
+/// Ignore globs and language allow-list shared by the scanner, `--check`,
+/// watch mode and the MCP server so they all agree on which files exist.
+#[derive(Debug, Clone)]
+pub struct PathFilter {
+    root: PathBuf,
+    ignore: Gitignore,
+    languages: Vec<String>,
+}
+
+impl PathFilter {
+    /// Build a filter rooted at `root` from `--ignore` patterns, the root
+    /// `.agentlensignore` file and `--lang` names
+    pub fn new(root: &Path, ignore_patterns: &[String], languages: &[String]) -> Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+
+        let ignore_file = root.join(IGNORE_FILE_NAME);
+        if ignore_file.is_file() {
+            if let Some(err) = builder.add(&ignore_file) {
+                return Err(err).context("Failed to parse .agentlensignore");
+            }
+        }
+
+        for pattern in ignore_patterns {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("Invalid ignore pattern: {}", pattern))?;
+        }
+
+        let ignore = builder.build().context("Failed to build ignore matcher")?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            ignore,
+            languages: languages.to_vec(),
+        })
+    }
+
+    /// Check if a path (or any of its parent directories) matches an ignore glob
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.root) {
+                Ok(rel) => rel,
+                Err(_) => return false,
+            }
+        } else {
+            path
+        };
+
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        self.ignore
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+
+    /// Check if a file should be scanned: not ignored and in an allowed language
+    pub fn includes_file(&self, path: &Path) -> bool {
+        !self.is_ignored(path, false) && should_include_file(path, &self.languages)
+    }
+}
 
 pub fn should_include_file(path: &Path, allowed_languages: &[String]) -> bool {
     let extension = match path.extension().and_then(|e| e.to_str()) {
@@ -25,6 +93,7 @@ pub fn should_include_file(path: &Path, allowed_languages: &[String]) -> bool {
         Language::Go => "go",
         Language::Php => "php",
         Language::Java => "java",
+        Language::Kotlin => "kotlin",
         Language::CSharp => "csharp",
         Language::C => "c",
         Language::Cpp => "cpp",
@@ -38,3 +107,41 @@ pub fn should_include_file(path: &Path, allowed_languages: &[String]) -> bool {
         .iter()
         .any(|l| l.to_lowercase() == lang_name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_should_include_file_language_filter() {
+        let langs = vec!["Rust".to_string()];
+        assert!(should_include_file(Path::new("src/lib.rs"), &langs));
+        assert!(!should_include_file(Path::new("src/app.ts"), &langs));
+        assert!(should_include_file(Path::new("src/app.ts"), &[]));
+        assert!(!should_include_file(Path::new("README.md"), &[]));
+    }
+
+    #[test]
+    fn test_ignore_patterns_match_dirs_and_globs() {
+        let temp = TempDir::new().unwrap();
+        let patterns = vec!["fixtures/".to_string(), "*.pb.go".to_string()];
+        let filter = PathFilter::new(temp.path(), &patterns, &[]).unwrap();
+
+        assert!(filter.is_ignored(Path::new("tests/fixtures/a.rs"), false));
+        assert!(filter.is_ignored(&temp.path().join("api/user.pb.go"), false));
+        assert!(!filter.is_ignored(Path::new("api/user.go"), false));
+        assert!(filter.includes_file(Path::new("api/user.go")));
+    }
+
+    #[test]
+    fn test_agentlensignore_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join(IGNORE_FILE_NAME), "generated/\n").unwrap();
+        let filter = PathFilter::new(temp.path(), &[], &[]).unwrap();
+
+        assert!(filter.is_ignored(Path::new("src/generated/proto.rs"), false));
+        assert!(!filter.is_ignored(Path::new("src/lib.rs"), false));
+    }
+}
@@ -3,9 +3,14 @@ pub mod git;
 pub mod remote;
 mod walker;
 
-pub use filter::should_include_file;
+pub use filter::{should_include_file, PathFilter, IGNORE_FILE_NAME};
 pub use git::{
     get_default_branch, get_diff_files, get_git_head, is_git_repo, DiffStat, DiffStatus,
 };
 pub use remote::{cleanup_temp, clone_to_temp, is_remote_url};
-pub use walker::scan_directory;
+pub use walker::{
+    scan_directory, scan_project, scan_sources, ProjectScan, ScanOptions, SourceFile,
+};
+
+#[cfg(test)]
+pub use walker::source;
@@ -1,128 +1,380 @@
-use crate::types::{FileEntry, Language};
+use crate::emit::hash_content;
+use crate::scan::filter::PathFilter;
+use crate::types::FileEntry;
 use anyhow::{Context, Result};
-use ignore::WalkBuilder;
-use std::fs::File;
+use ignore::{WalkBuilder, WalkState};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
+use std::sync::Mutex;
 
 const BINARY_CHECK_SIZE: usize = 8192;
 const MINIFIED_LINE_LENGTH_THRESHOLD: usize = 200;
 
-pub fn scan_directory(
-    root: &Path,
-    threshold: usize,
-    respect_gitignore: bool,
-    max_depth: Option<usize>,
-) -> Result<Vec<FileEntry>> {
-    let mut entries = Vec::new();
-    let root = root
-        .canonicalize()
-        .context("Failed to canonicalize root path")?;
+/// Options controlling which files `scan_directory` returns
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Line threshold for "large" files
+    pub threshold: usize,
+    /// Respect .gitignore, global gitignore and .git/info/exclude
+    pub respect_gitignore: bool,
+    /// Max directory depth (None = unlimited)
+    pub max_depth: Option<usize>,
+    /// Additional gitignore-style patterns to exclude
+    pub ignore_patterns: Vec<String>,
+    /// Language allow-list (empty = all languages)
+    pub languages: Vec<String>,
+    /// Worker threads for walking and reading (0 = one per CPU)
+    pub jobs: usize,
+}
 
-    let mut builder = WalkBuilder::new(&root);
+/// A scanned file together with its content, so later stages never re-read it
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub entry: FileEntry,
+    pub content: String,
+}
+
+/// Everything one walk of the project reads: source files and the build
+/// manifests that describe its layout
+#[derive(Debug, Clone, Default)]
+pub struct ProjectScan {
+    /// Sorted by relative path
+    pub sources: Vec<SourceFile>,
+    /// Manifest contents keyed by relative path
+    pub manifests: BTreeMap<String, String>,
+}
+
+/// An in-memory source file for tests
+#[cfg(test)]
+pub fn source(path: &str, content: &str) -> SourceFile {
+    SourceFile {
+        entry: FileEntry::new(path.into(), path.to_string(), 0, 0, 500),
+        content: content.to_string(),
+    }
+}
+
+impl ScanOptions {
+    /// Build the path filter for these options, rooted at `root`
+    pub fn path_filter(&self, root: &Path) -> Result<PathFilter> {
+        PathFilter::new(root, &self.ignore_patterns, &self.languages)
+    }
+}
+
+/// Build manifests and compiler configs that describe project layout (module
+/// roots, packages), module resolution and third-party dependencies
+const MANIFEST_FILE_NAMES: &[&str] = &[
+    "composer.json",
+    "pubspec.yaml",
+    "Package.swift",
+    "Cargo.toml",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "go.work",
+    "go.mod",
+    "pyproject.toml",
+    "tsconfig.json",
+    "jsconfig.json",
+    "compile_commands.json",
+    "requirements.txt",
+    "Gemfile",
+];
+const MANIFEST_EXTENSIONS: &[&str] = &["csproj"];
+
+fn build_walker(root: &Path, options: &ScanOptions, filter: &PathFilter) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
     builder
         .hidden(true)
-        .git_ignore(respect_gitignore)
-        .git_global(respect_gitignore)
-        .git_exclude(respect_gitignore);
+        .git_ignore(options.respect_gitignore)
+        .git_global(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore);
 
-    if let Some(depth) = max_depth {
+    if let Some(depth) = options.max_depth {
         builder.max_depth(Some(depth));
     }
 
-    let walker = builder.build();
+    let dir_filter = filter.clone();
+    builder.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        !is_dir || !dir_filter.is_ignored(entry.path(), true)
+    });
+
+    builder
+}
 
-    for result in walker {
-        let entry = match result {
-            Ok(e) => e,
-            Err(err) => {
-                eprintln!("Warning: {}", err);
-                continue;
+/// List the included files without keeping their content, for callers that
+/// don't analyse it. Files are streamed to count lines, so memory stays flat.
+/// Entries carry no content hash.
+pub fn scan_directory(root: &Path, options: &ScanOptions) -> Result<Vec<FileEntry>> {
+    let (mut entries, _) = walk_files(root, options, false, |root, path| {
+        read_entry(root, path, options.threshold)
+    })?;
+    entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(entries)
+}
+
+/// Walk `root` in parallel, reading each included file exactly once.
+/// Results are sorted by relative path regardless of thread scheduling.
+pub fn scan_sources(root: &Path, options: &ScanOptions) -> Result<Vec<SourceFile>> {
+    scan_project(root, options).map(|scan| scan.sources)
+}
+
+/// Like `scan_sources`, also reading the build manifests (composer.json,
+/// *.csproj, ...) met on the same walk
+pub fn scan_project(root: &Path, options: &ScanOptions) -> Result<ProjectScan> {
+    let (mut sources, manifests) = walk_files(root, options, true, |root, path| {
+        read_source(root, path, options.threshold)
+    })?;
+    sources.sort_by(|a, b| a.entry.relative_path.cmp(&b.entry.relative_path));
+    Ok(ProjectScan { sources, manifests })
+}
+
+/// Walk `root` in parallel and collect `read` for every included file, and
+/// the contents of build manifests when `with_manifests` is set.
+/// `read` returns `None` to skip a file; errors are reported and skipped.
+fn walk_files<T: Send>(
+    root: &Path,
+    options: &ScanOptions,
+    with_manifests: bool,
+    read: impl Fn(&Path, &Path) -> Result<Option<T>> + Sync,
+) -> Result<(Vec<T>, BTreeMap<String, String>)> {
+    let root = root
+        .canonicalize()
+        .context("Failed to canonicalize root path")?;
+
+    let filter = options.path_filter(&root)?;
+    let walker = build_walker(&root, options, &filter)
+        .threads(options.jobs)
+        .build_parallel();
+
+    let results: Mutex<Vec<T>> = Mutex::new(Vec::new());
+    let manifests: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+    walker.run(|| {
+        let root = &root;
+        let filter = &filter;
+        let results = &results;
+        let manifests = &manifests;
+        let read = &read;
+        Box::new(move |result| {
+            let entry = match result {
+                Ok(e) => e,
+                Err(err) => {
+                    eprintln!("Warning: {}", err);
+                    return WalkState::Continue;
+                }
+            };
+
+            let path = entry.path();
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                return WalkState::Continue;
+            }
+            if with_manifests && is_manifest_file(root, path) && !filter.is_ignored(path, false) {
+                if let Ok(content) = fs::read_to_string(path) {
+                    manifests
+                        .lock()
+                        .unwrap()
+                        .insert(relative_path(root, path), content);
+                }
+            }
+            if !filter.includes_file(path) {
+                return WalkState::Continue;
             }
-        };
 
-        let path = entry.path();
+            match read(root, path) {
+                Ok(Some(value)) => results.lock().unwrap().push(value),
+                Ok(None) => {}
+                Err(err) => eprintln!("Warning: {:#}", err),
+            }
+            WalkState::Continue
+        })
+    });
 
-        if !path.is_file() {
-            continue;
-        }
+    Ok((
+        results.into_inner().unwrap(),
+        manifests.into_inner().unwrap(),
+    ))
+}
 
-        let extension = match path.extension().and_then(|e| e.to_str()) {
-            Some(ext) => ext,
-            None => continue,
-        };
+/// Read a file once and build its entry; `None` for binary or minified files
+fn read_source(root: &Path, path: &Path, threshold: usize) -> Result<Option<SourceFile>> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
 
-        let language = Language::from_extension(extension);
-        if matches!(language, Language::Unknown) {
-            continue;
-        }
+    if is_binary(&bytes) {
+        return Ok(None);
+    }
 
-        if is_binary_file(path)? {
-            continue;
-        }
+    let content = String::from_utf8_lossy(&bytes).into_owned();
+    let (line_count, is_minified) = count_lines_and_check_minified(content.lines().map(str::len));
+    if is_minified {
+        return Ok(None);
+    }
 
-        let (line_count, is_minified) = count_lines_and_check_minified(path)?;
-        if is_minified {
-            continue;
-        }
+    let entry = FileEntry::new(
+        path.to_path_buf(),
+        relative_path(root, path),
+        bytes.len() as u64,
+        line_count,
+        threshold,
+    )
+    .with_content_hash(hash_content(&bytes));
 
-        let relative_path = path
-            .strip_prefix(&root)
-            .unwrap_or(path)
-            .to_string_lossy()
-            .to_string();
-
-        let size_bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
-
-        entries.push(FileEntry::new(
-            path.to_path_buf(),
-            relative_path,
-            size_bytes,
-            line_count,
-            threshold,
-        ));
+    Ok(Some(SourceFile { entry, content }))
+}
+
+/// Stream a file to build its entry; `None` for binary or minified files
+fn read_entry(root: &Path, path: &Path, threshold: usize) -> Result<Option<FileEntry>> {
+    let open = || File::open(path).with_context(|| format!("Failed to open {}", path.display()));
+
+    let mut head = Vec::with_capacity(BINARY_CHECK_SIZE);
+    open()?
+        .take(BINARY_CHECK_SIZE as u64)
+        .read_to_end(&mut head)?;
+    if is_binary(&head) {
+        return Ok(None);
     }
 
-    entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    let mut line_lengths = Vec::new();
+    for line in BufReader::new(open()?).split(b'\n') {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        line_lengths.push(line.strip_suffix(b"\r").unwrap_or(&line).len());
+    }
+    let (line_count, is_minified) = count_lines_and_check_minified(line_lengths.into_iter());
+    if is_minified {
+        return Ok(None);
+    }
 
-    Ok(entries)
+    let size_bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok(Some(FileEntry::new(
+        path.to_path_buf(),
+        relative_path(root, path),
+        size_bytes,
+        line_count,
+        threshold,
+    )))
 }
 
-fn is_binary_file(path: &Path) -> Result<bool> {
-    let file = File::open(path).context("Failed to open file for binary check")?;
-    let mut reader = BufReader::new(file);
-    let mut buffer = [0u8; BINARY_CHECK_SIZE];
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
 
-    let bytes_read = reader.read(&mut buffer)?;
+fn is_manifest_file(root: &Path, path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if !MANIFEST_FILE_NAMES.contains(&name) && !MANIFEST_EXTENSIONS.contains(&extension) {
+        return false;
+    }
 
-    Ok(buffer[..bytes_read].contains(&0))
+    // Installed dependencies are never part of the project's own layout
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    !relative
+        .components()
+        .any(|c| c.as_os_str() == "node_modules")
 }
 
-fn count_lines_and_check_minified(path: &Path) -> Result<(usize, bool)> {
-    let file = File::open(path).context("Failed to open file for line count")?;
-    let reader = BufReader::new(file);
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_CHECK_SIZE)].contains(&0)
+}
 
+fn count_lines_and_check_minified(line_lengths: impl Iterator<Item = usize>) -> (usize, bool) {
     let mut line_count = 0;
     let mut total_chars = 0;
     let mut non_empty_lines = 0;
 
-    for line in reader.lines() {
-        let line = line.context("Failed to read line")?;
+    for len in line_lengths {
         line_count += 1;
-        let len = line.len();
         if len > 0 {
             total_chars += len;
             non_empty_lines += 1;
         }
     }
 
-    let avg_line_length = if non_empty_lines > 0 {
-        total_chars / non_empty_lines
-    } else {
-        0
-    };
+    let avg_line_length = total_chars.checked_div(non_empty_lines).unwrap_or(0);
 
     let is_minified = avg_line_length > MINIFIED_LINE_LENGTH_THRESHOLD;
 
-    Ok((line_count, is_minified))
+    (line_count, is_minified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_sources_is_sorted_and_reads_content() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..20 {
+            fs::write(dir.path().join(format!("f{:02}.rs", i)), "fn a() {}\n").unwrap();
+        }
+        fs::write(dir.path().join("blob.rs"), b"fn a() {}\0\x01").unwrap();
+        fs::write(
+            dir.path().join("min.js"),
+            "x".repeat(MINIFIED_LINE_LENGTH_THRESHOLD + 1),
+        )
+        .unwrap();
+
+        let options = ScanOptions {
+            threshold: 500,
+            jobs: 4,
+            ..Default::default()
+        };
+        let sources = scan_sources(dir.path(), &options).unwrap();
+
+        let paths: Vec<_> = sources
+            .iter()
+            .map(|s| s.entry.relative_path.clone())
+            .collect();
+        let expected: Vec<_> = (0..20).map(|i| format!("f{:02}.rs", i)).collect();
+        assert_eq!(paths, expected);
+
+        let first = &sources[0];
+        assert_eq!(first.content, "fn a() {}\n");
+        assert_eq!(first.entry.line_count, 1);
+        assert_eq!(first.entry.content_hash, hash_content(b"fn a() {}\n"));
+    }
+
+    #[test]
+    fn test_scan_directory_matches_scan_sources() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() {}\r\n\nfn b() {}").unwrap();
+        fs::write(dir.path().join("blob.rs"), b"fn a() {}\0\x01").unwrap();
+        fs::write(
+            dir.path().join("min.js"),
+            "x".repeat(MINIFIED_LINE_LENGTH_THRESHOLD + 1),
+        )
+        .unwrap();
+
+        let options = ScanOptions::default();
+        let entries = scan_directory(dir.path(), &options).unwrap();
+        let sources = scan_sources(dir.path(), &options).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].relative_path, sources[0].entry.relative_path);
+        assert_eq!(entries[0].line_count, 3);
+        assert_eq!(entries[0].line_count, sources[0].entry.line_count);
+        assert_eq!(entries[0].size_bytes, sources[0].entry.size_bytes);
+    }
+
+    #[test]
+    fn test_scan_project_reads_manifests() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("web/node_modules/react")).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        fs::write(dir.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.path().join("web/package.json"), "{}").unwrap();
+        fs::write(dir.path().join("web/node_modules/react/package.json"), "{}").unwrap();
+        fs::write(dir.path().join("notes.md"), "# notes\n").unwrap();
+
+        let scan = scan_project(dir.path(), &ScanOptions::default()).unwrap();
+
+        assert_eq!(scan.sources.len(), 1);
+        let manifests: Vec<_> = scan.manifests.keys().map(String::as_str).collect();
+        assert_eq!(manifests, vec!["Cargo.toml", "web/package.json"]);
+        assert_eq!(scan.manifests["web/package.json"], "{}");
+    }
 }
@@ -10,6 +10,7 @@ pub enum Language {
     Go,
     Php,
     Java,
+    Kotlin,
     CSharp,
     C,
     Cpp,
@@ -29,6 +30,7 @@ impl Language {
             "go" => Language::Go,
             "php" | "phtml" => Language::Php,
             "java" => Language::Java,
+            "kt" | "kts" => Language::Kotlin,
             "cs" => Language::CSharp,
             "c" => Language::C,
             "h" | "hpp" | "hh" | "hxx" => Language::Cpp,
@@ -70,6 +72,9 @@ pub struct FileEntry {
     pub size_bytes: u64,
     pub line_count: usize,
     pub is_large: bool,
+    /// SHA-256 of the file content (hex), filled in by the scanner
+    #[serde(skip)]
+    pub content_hash: String,
 }
 
 impl FileEntry {
@@ -98,6 +103,12 @@ impl FileEntry {
             size_bytes,
             line_count,
             is_large: line_count > threshold,
+            content_hash: String::new(),
         }
     }
+
+    pub fn with_content_hash(mut self, hash: String) -> Self {
+        self.content_hash = hash;
+        self
+    }
 }
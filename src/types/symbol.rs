@@ -1,6 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SymbolKind {
     Function,
     Method,
@@ -31,7 +31,7 @@ impl std::fmt::Display for SymbolKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
@@ -50,7 +50,7 @@ impl std::fmt::Display for Visibility {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LineRange {
     pub start: usize,
     pub end: usize,
@@ -69,7 +69,7 @@ impl LineRange {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub kind: SymbolKind,
     pub name: String,
@@ -77,6 +77,9 @@ pub struct Symbol {
     pub line_range: LineRange,
     pub visibility: Visibility,
     pub doc_comment: Option<String>,
+    /// Name of the enclosing type or module (`impl Foo`, `class Bar`, a Go receiver)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
 }
 
 impl Symbol {
@@ -88,6 +91,7 @@ impl Symbol {
             line_range: LineRange::single(line),
             visibility,
             doc_comment: None,
+            parent: None,
         }
     }
 
@@ -105,4 +109,80 @@ impl Symbol {
         self.doc_comment = Some(doc);
         self
     }
+
+    pub fn with_parent(mut self, parent: String) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Whether this kind of symbol can contain other symbols
+    pub fn is_container(&self) -> bool {
+        matches!(
+            self.kind,
+            SymbolKind::Class
+                | SymbolKind::Struct
+                | SymbolKind::Enum
+                | SymbolKind::Trait
+                | SymbolKind::Interface
+                | SymbolKind::Module
+        )
+    }
+
+    /// Name qualified with its parent, e.g. `Foo::bar`
+    pub fn qualified_name(&self) -> String {
+        match self.parent {
+            Some(ref parent) => format!("{}::{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// First sentence of the doc comment, on one line
+    pub fn doc_summary(&self) -> Option<String> {
+        let doc = self.doc_comment.as_deref()?;
+        let paragraph: Vec<&str> = doc
+            .lines()
+            .map(|l| l.trim())
+            .take_while(|l| !l.is_empty() && !l.starts_with('@'))
+            .collect();
+        let paragraph = paragraph.join(" ");
+
+        let end = paragraph
+            .match_indices(['.', '?', '!'])
+            .map(|(i, _)| i + 1)
+            // A sentence ends before a capitalised word, so `e.g. foo` doesn't split
+            .find(|&i| {
+                let rest = &paragraph[i..];
+                rest.is_empty()
+                    || (rest.starts_with(' ') && rest[1..].starts_with(|c: char| c.is_uppercase()))
+            })
+            .unwrap_or(paragraph.len());
+        let summary = paragraph[..end].trim();
+
+        if summary.is_empty() {
+            None
+        } else {
+            Some(summary.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_summary_first_sentence() {
+        let sym = Symbol::new(SymbolKind::Function, "f".to_string(), 1, Visibility::Public)
+            .with_doc_comment(
+                "Parses the file, e.g. a config.\nReturns None on error.\n\nMore.".to_string(),
+            );
+        assert_eq!(
+            sym.doc_summary().as_deref(),
+            Some("Parses the file, e.g. a config.")
+        );
+
+        let tagged = Symbol::new(SymbolKind::Function, "g".to_string(), 1, Visibility::Public)
+            .with_doc_comment("Loads users\n@param id the id".to_string());
+        assert_eq!(tagged.doc_summary().as_deref(), Some("Loads users"));
+    }
 }